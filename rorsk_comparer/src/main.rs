@@ -1,187 +1,480 @@
-use std::{fs, collections::HashMap, path::PathBuf, thread, slice::Iter, sync::Mutex};
-
-lazy_static::lazy_static! {
-    static ref PRINT_MUTEX: Mutex<String> = Mutex::new(String::new());
-
-    static ref VENDOR_IDS: HashMap<u32, &'static str> = {
-        let mut m = HashMap::new();
-        m.insert(4098, "AMD");
-        m.insert(4318, "NVIDIA");
-        m.insert(32902, "Intel");
-        m
-    };
+use std::{fs::{self, File}, collections::BTreeMap, path::PathBuf, process::ExitCode, thread};
+
+use clap::{Parser, ValueEnum};
+use error::Error;
+use rorsk_core::result_file::{DeviceInfo, ResultFile, ResultHeader};
+use majority::MajorityReport;
+use serde::Serialize;
+use stats::{Accumulator, Stats};
+
+mod error;
+mod majority;
+mod stats;
+
+/// Compares data generated by rorsk_generator between devices.
+#[derive(Parser)]
+#[command(version)]
+struct Cli {
+    /// Directory with result files, can be repeated.
+    #[arg(short, long = "input", value_name = "DIR", default_value = "../output")]
+    inputs: Vec<PathBuf>,
+
+    /// File where results are saved.
+    #[arg(short, long, value_name = "PATH", default_value = "../output/results.txt")]
+    output: PathBuf,
+
+    /// Format of the saved results.
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Problem name or glob pattern like `f32-*`, can be repeated. Every found problem is compared when omitted.
+    #[arg(short, long = "problem", value_name = "PATTERN")]
+    problems: Vec<glob::Pattern>,
+
+    /// Number of differences allowed in conformant data of each problem. The comparer exits with code 1 when any
+    /// problem exceeds it, unconformant data is only reported.
+    #[arg(short, long, value_name = "COUNT", default_value_t = 0)]
+    tolerance: u64,
+
+    /// ULP error reported in the `within N ULPs` statistic, next to the fixed 0, 1 and 2.
+    #[arg(long, value_name = "N", default_value_t = 4)]
+    ulps: u64,
+}
 
-    static ref DEVICE_IDS: HashMap<u32, &'static str> = {
-        let mut m = HashMap::new();
-        m.insert(29695, "Radeon RX 6600 XT");
-        m.insert(9988, "RTX 4080");
-        m.insert(22176, "Arc A770 Graphics");
-        m
-    };
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Text,
+    Json,
 }
 
+#[derive(Clone)]
 struct CompareTask {
-    device_name: String,
+    header: ResultHeader,
     path: PathBuf
 }
 
-fn main() {
-    let mut threads = Vec::new();
+#[derive(Default)]
+struct ProblemTasks {
+    conformant: Vec<CompareTask>,
+    unconformant: Vec<CompareTask>,
+    /// Results computed on the CPU. The IEEE-754 reference is compared with both conformant and unconformant results,
+    /// the conformant emulation with conformant results only.
+    reference: Vec<CompareTask>,
+}
 
-    for (problem_name, task) in search_tasks() {
-        let a = problem_name.to_owned();
-        threads.push(thread::spawn(move || {
-            compare_task(a, task.0, true);
-        }));
-        threads.push(thread::spawn(move || {
-            compare_task(problem_name, task.1, false);
-        }));
-    }
+type Tasks = BTreeMap<String, ProblemTasks>;
+
+#[derive(Serialize)]
+struct Report {
+    problem_name: String,
+    expression: String,
+    is_conformant: bool,
+    /// Like `sf32` or `ieee`, empty for unconformant data and files which do not record it.
+    conformant_mode: String,
+    devices: Vec<String>,
+    /// Driver, API version, subgroup size and float controls of every device in the order of `devices`, empty when the
+    /// file does not record them.
+    device_properties: Vec<String>,
+    skipped: Vec<SkippedFile>,
+    data_bits: usize,
+    /// Indices where devices do not all return the same result.
+    differences: u64,
+    /// Number of differences between every two devices, rows and columns are in the order of `devices`.
+    matrix: Vec<Vec<u64>>,
+    pairs: Vec<PairReport>,
+    majority: MajorityReport,
+    /// Every device against the reference computed on the CPU, empty when no reference was found.
+    reference: Vec<PairReport>,
+    /// Every device against the conformant emulation computed on the CPU, empty when no emulation was found.
+    emulation: Vec<PairReport>,
+    /// Devices whose differences from the emulation exceed the tolerance.
+    deviating: Vec<String>,
+}
 
-    for thread in threads {
-        thread.join().unwrap();
-    }
+#[derive(Serialize)]
+struct SkippedFile {
+    path: PathBuf,
+    reason: String,
+}
 
-    let lock = PRINT_MUTEX.lock().unwrap();
-    fs::write("../output/results.txt", lock.as_bytes()).unwrap();
-    println!("Done! Saved result logs to `output/results.txt` file.");
+#[derive(Serialize)]
+struct PairReport {
+    expected_device: String,
+    device: String,
+    stats: Stats,
 }
 
-fn search_tasks() -> HashMap<String, (Vec<CompareTask>, Vec<CompareTask>)> {
-    let mut searched = HashMap::new();
+impl Report {
+    fn to_text(&self) -> String {
+        let mut message = String::new();
+        for file in &self.skipped {
+            message.push_str(&format!("\nSkipped `{}`, {}.", file.path.display(), file.reason));
+        }
 
-    for file_wrapped in fs::read_dir("../output/").unwrap() {
-        let file = file_wrapped.unwrap();
-        let os_string = file.file_name();
-        if os_string.to_str().unwrap() == "results.txt" {
-            continue;
+        let conformant_str = match (self.is_conformant, self.conformant_mode.as_str()) {
+            (false, _) => "unconformant".to_owned(),
+            (true, "") => "conformant".to_owned(),
+            (true, mode) => format!("conformant ({mode})"),
+        };
+        message.push_str(&format!("\nProblem `{}` on {} was tested with devices:", self.problem_name, conformant_str));
+        for (i, device) in self.devices.iter().enumerate() {
+            message.push_str(&format!("\n  - [{i}] {}", device));
+            if !self.device_properties[i].is_empty() {
+                message.push_str(&format!("\n        {}", self.device_properties[i]));
+            }
         }
 
-        let mut split = os_string.to_str().unwrap().split('_');
+        message.push_str("\nResults:");
+        message.push_str(&format!("\n  - Expression: `{}`", self.expression));
+        message.push_str(&format!("\n  - Data count: {} bits", self.data_bits));
+        message.push_str(&format!("\n  - Number of differences: {}", self.differences));
 
-        let problem_name = split.next().unwrap();
-        let vec;
-        if let Some(v) = searched.get_mut(problem_name) {
-            vec = v;
-        } else {
-            searched.insert(problem_name.to_owned(), (Vec::new(), Vec::new()));
-            vec = searched.get_mut(problem_name).unwrap();
+        let width = self.matrix.iter().flatten().max().unwrap_or(&0).to_string().len().max(4);
+        message.push_str(&format!("\n  - Difference matrix:\n      {:width$}", ""));
+        for i in 0..self.devices.len() {
+            message.push_str(&format!(" {:>width$}", format!("[{i}]")));
+        }
+        for (i, row) in self.matrix.iter().enumerate() {
+            message.push_str(&format!("\n      {:>width$}", format!("[{i}]")));
+            for count in row {
+                message.push_str(&format!(" {count:>width$}"));
+            }
         }
 
-        let vendor_id: u32 = split.next().unwrap().parse().unwrap();
-        split = split.next().unwrap().split('.');
-        let device_id: u32 = split.next().unwrap().parse().unwrap();
-
-        let is_conformant = match split.next().unwrap() {
-            "bin" => false,
-            "binc" => true,
-            &_ => continue
-        };
+        message.push_str(&format!("\n  - Indices without a majority: {}", self.majority.no_majority));
+        for (i, device) in self.majority.devices.iter().enumerate() {
+            if device.disagreements != 0 {
+                message.push_str(&format!(
+                    "\n  - [{i}] {} disagrees with the majority at {} indices, first: {:?}",
+                    device.device, device.disagreements, device.sample_indices
+                ));
+            }
+        }
 
-        let device_name =
-            format!("{} {}", VENDOR_IDS.get(&vendor_id).unwrap(), DEVICE_IDS.get(&device_id).unwrap());
+        for pair in self.emulation.iter().filter(|pair| self.deviating.contains(&pair.device)) {
+            message.push_str(&format!(
+                "\n  - {} deviates from {} at {} indices", pair.device, pair.expected_device, pair.stats.differences
+            ));
+        }
 
-        let v = if is_conformant { &mut vec.0 } else { &mut vec.1 };
-        v.push(CompareTask {
-            device_name,
-            path: file.path(),
-        });
+        for pair in self.pairs.iter().chain(&self.reference).chain(&self.emulation) {
+            message.push_str(&format!("\n  - {} against {}:", pair.device, pair.expected_device));
+            message.push_str(&pair.stats.to_text("\n      "));
+        }
+        message
     }
-
-    searched
 }
 
-fn compare_task(problem_name: String, data: Vec<CompareTask>, is_conformant: bool) {
-    let type_name = problem_name.split('-').next().unwrap();
-    let target = fs::read(&data[0].path).unwrap();
+fn main() -> ExitCode {
+    let cli = Cli::parse();
 
-    let mut count: u64 = 0;
+    let tasks = match search_tasks(&cli.inputs, &cli.problems) {
+        Ok(tasks) => tasks,
+        Err(e) => {
+            eprintln!("Failed to search result files: {e}.");
+            return ExitCode::from(2);
+        }
+    };
+    if tasks.is_empty() {
+        eprintln!("No result files were found.");
+        return ExitCode::from(2);
+    }
 
-    for task in data.iter().skip(1) {
-        let read = fs::read(&task.path).unwrap();
-        let mut target_temp = target.iter();
-        let mut read_temp = read.iter();
-        while let Some(difference) = compare_difference(&mut target_temp, &mut read_temp, type_name, is_conformant) {
-            if difference {
-                count += 1;
+    let mut threads = Vec::new();
+    for (problem_name, tasks) in tasks {
+        for (data, is_conformant) in [(tasks.conformant, true), (tasks.unconformant, false)] {
+            if data.is_empty() {
+                continue;
             }
+
+            let problem_name = problem_name.clone();
+            let references = tasks.reference.clone();
+            let (ulps, tolerance) = (cli.ulps, cli.tolerance);
+            threads.push(thread::spawn(move || {
+                compare_task(problem_name, data, references, is_conformant, ulps, tolerance)
+            }));
         }
     }
 
-    let conformant_str = if is_conformant { "conformant" } else { "unconformant" };
+    let mut reports = Vec::new();
+    let mut failed = 0;
+    for thread in threads {
+        match thread.join() {
+            Ok(Ok(report)) => reports.push(report),
+            Ok(Err(e)) => {
+                eprintln!("Failed to compare results: {e}.");
+                failed += 1;
+            }
+            // A panic of one comparison is reported, so the reports of the others are still saved.
+            Err(_) => {
+                eprintln!("Failed to compare results: the comparison panicked.");
+                failed += 1;
+            }
+        }
+    }
 
-    let mut message = String::new();
-    message.push_str(&format!("\nProblem `{}` on {} was tested with devices:", problem_name, conformant_str));
-    for task in data.iter() {
-        message.push_str(&format!("\n  - {}", task.device_name));
+    let mut text = String::new();
+    for report in &reports {
+        let message = report.to_text();
+        println!("{}", message);
+        text.push_str(&message);
     }
 
-    message.push_str("\nResults:");
-    message.push_str(&format!("\n  - Data count: {} bits", target.len() * 8));
-    message.push_str(&format!("\n  - Number of differences: {}", count));
+    let saved = match cli.format {
+        Format::Text => text,
+        Format::Json => match serde_json::to_string_pretty(&reports) {
+            Ok(json) => json,
+            Err(e) => {
+                eprintln!("Failed to serialize results: {e}.");
+                return ExitCode::from(2);
+            }
+        },
+    };
+    if let Err(e) = fs::write(&cli.output, saved) {
+        eprintln!("Failed to save results to `{}`: {e}.", cli.output.display());
+        return ExitCode::from(2);
+    }
+    println!("Done! Saved result logs to `{}` file.", cli.output.display());
 
-    let mut lock = PRINT_MUTEX.lock().unwrap();
-    lock.push_str(&message);
-    println!("{}", message);
+    // Every failure is printed before the exit code is chosen, a failed comparison takes precedence.
+    let exceeded = reports.iter().filter(|r| r.is_conformant && r.differences > cli.tolerance).count();
+    if exceeded != 0 {
+        eprintln!("Conformant data of {exceeded} problem(s) exceeds the tolerance of {} differences.", cli.tolerance);
+    }
+
+    let deviating = reports.iter().map(|r| r.deviating.len()).sum::<usize>();
+    if deviating != 0 {
+        eprintln!("{deviating} device result(s) deviate from the conformant emulation.");
+    }
+
+    if failed != 0 {
+        eprintln!("Results of {failed} problem(s) could not be compared.");
+    }
+
+    if failed != 0 {
+        ExitCode::from(2)
+    } else if exceeded != 0 || deviating != 0 {
+        ExitCode::from(1)
+    } else {
+        ExitCode::SUCCESS
+    }
 }
 
-fn compare_difference(expected: &mut Iter<'_, u8>, data: &mut Iter<'_, u8>, type_name: &str, is_conformant: bool) -> Option<bool> {
-    match type_name {
-        "i32" => {
-            if let Some(a) = read_i32(expected) {
-                if let Some(b) = read_i32(data) {
-                    if is_conformant && a != b {
-                        println!("i32 {} {}", a, b);
-                    }
+fn search_tasks(inputs: &[PathBuf], patterns: &[glob::Pattern]) -> Result<Tasks, Error> {
+    let mut searched = Tasks::new();
 
-                    return Some(a != b);
-                }
+    for input in inputs {
+        for file_wrapped in fs::read_dir(input).map_err(Error::io(input))? {
+            let path = file_wrapped.map_err(Error::io(input))?.path();
+            if !path.is_file() {
+                continue;
             }
-        }
-        "f32" => {
-            if let Some(a) = read_f32(expected) {
-                if let Some(b) = read_f32(data) {
-                    let compare;
-                    if a.is_finite() {
-                        compare = a.to_bits() != b.to_bits();
-                    } else if a.is_nan() {
-                        compare = !b.is_nan();
-                    } else {
-                        assert!(a.is_infinite());
-                        compare = !b.is_infinite();
-                    }
-
-                    if compare && is_conformant {
-                        println!("f32 {:b} {:b} {}", a.to_bits(), b.to_bits(), (a - b).abs());
-                    }
-                    return Some(compare);
+
+            let mut file = match File::open(&path) {
+                Ok(file) => file,
+                Err(e) => {
+                    eprintln!("Skipped `{}`, {e}.", path.display());
+                    continue;
                 }
+            };
+
+            // Anything which is not a result file, like `results.txt`, is skipped.
+            let header = match ResultHeader::read(&mut file) {
+                Ok(header) => header,
+                Err(_) => continue,
+            };
+
+            if !patterns.is_empty() && !patterns.iter().any(|p| p.matches(&header.problem_name)) {
+                continue;
             }
+
+            let tasks = searched.entry(header.problem_name.clone()).or_default();
+            let v = if header.is_reference {
+                &mut tasks.reference
+            } else if header.is_conformant {
+                &mut tasks.conformant
+            } else {
+                &mut tasks.unconformant
+            };
+            v.push(CompareTask { header, path });
         }
-        &_ => panic!("Unknown type name")
     }
 
-    None
+    Ok(searched)
 }
 
-fn read_i32(data: &mut Iter<'_, u8>) -> Option<i32> {
-    read_four_bytes(data).map(i32::from_le_bytes)
-}
+fn compare_task(
+    problem_name: String, mut data: Vec<CompareTask>, references: Vec<CompareTask>, is_conformant: bool, ulps: u64,
+    tolerance: u64
+) -> Result<Report, Error> {
+    data.sort_by(|a, b| {
+        (&a.header.device.name, a.header.device.index).cmp(&(&b.header.device.name, b.header.device.index))
+    });
+
+    // Results are only comparable when they were computed from the same input and in the same conformant mode, the most
+    // common one is kept.
+    let input_key = |task: &CompareTask| {
+        (task.header.element_type, task.header.input_sha256.clone(), task.header.conformant_mode.clone())
+    };
+    let input = data.iter()
+        .map(|task| (input_key(task), data.iter().filter(|t| input_key(t) == input_key(task)).count()))
+        .max_by_key(|(_, count)| *count)
+        .unwrap().0;
+    let (data, skipped): (Vec<_>, Vec<_>) = data.into_iter().partition(|task| input_key(task) == input);
+    let mut skipped = skipped.into_iter()
+        .map(|task| SkippedFile {
+            path: task.path,
+            reason: "it was computed from other input data or in another conformant mode".to_owned(),
+        })
+        .collect::<Vec<_>>();
+
+    // Unreadable or truncated files are skipped, so they do not prevent comparing the other devices.
+    let mut files: Vec<ResultFile> = Vec::new();
+    for task in &data {
+        let reason = match (ResultFile::open(&task.path), files.first()) {
+            (Ok(file), Some(first)) if file.data.len() != first.data.len() => {
+                format!("its data has {} bytes, expected {}", file.data.len(), first.data.len())
+            },
+            (Ok(file), _) => {
+                files.push(file);
+                continue;
+            },
+            (Err(e), _) => e.to_string(),
+        };
+        skipped.push(SkippedFile { path: task.path.clone(), reason });
+    }
+    if files.is_empty() {
+        return Err(Error::NoData { problem_name });
+    }
 
-fn read_f32(data: &mut Iter<'_, u8>) -> Option<f32> {
-    read_four_bytes(data).map(f32::from_le_bytes)
-}
+    let element_type = input.0;
+    let devices = files.iter().map(|f| device_label(&f.header.device, &files)).collect::<Vec<_>>();
+    let device_properties = files.iter().map(|f| describe_device(&f.header.device)).collect();
+
+    let mut matrix = vec![vec![0; files.len()]; files.len()];
+    let mut pairs = Vec::new();
+    for i in 0..files.len() {
+        for j in i + 1..files.len() {
+            let mut accumulator = Accumulator::new(element_type, ulps, false);
+            accumulator.add_data(&files[i].data, &files[j].data);
+            let stats = accumulator.finish();
+
+            matrix[i][j] = stats.differences;
+            matrix[j][i] = stats.differences;
+            pairs.push(PairReport {
+                expected_device: devices[i].clone(),
+                device: devices[j].clone(),
+                stats,
+            });
+        }
+    }
 
-fn read_four_bytes(data: &mut Iter<'_, u8>) -> Option<[u8; 4]> {
-    if let Some(a) = data.next() {
-        if let Some(b) = data.next() {
-            if let Some(c) = data.next() {
-                if let Some(d) = data.next() {
-                    return Some([*a, *b, *c, *d]);
+    let mut compare_reference = |is_emulation: bool| {
+        let mut reports = Vec::new();
+        // The IEEE-754 reference has no conformant mode, the emulation has the mode of the data.
+        let task = references.iter().find(|task| {
+            let (element_type, input_sha256, conformant_mode) = input_key(task);
+            task.header.is_conformant == is_emulation && element_type == input.0 && input_sha256 == input.1
+                && (!is_emulation || conformant_mode == input.2)
+        });
+        if let Some(task) = task {
+            let reason = match ResultFile::open(&task.path) {
+                Ok(expected) if expected.data.len() == files[0].data.len() => Ok(expected),
+                Ok(expected) => {
+                    Err(format!("its data has {} bytes, expected {}", expected.data.len(), files[0].data.len()))
+                },
+                Err(e) => Err(e.to_string()),
+            };
+            let expected = match reason {
+                Ok(expected) => expected,
+                Err(reason) => {
+                    skipped.push(SkippedFile { path: task.path.clone(), reason });
+                    return reports;
                 }
+            };
+
+            for (file, device) in files.iter().zip(&devices) {
+                // The CPU results are bit-exact, so any other element is a deviation.
+                let mut accumulator = Accumulator::new(element_type, ulps, true);
+                accumulator.add_data(&expected.data, &file.data);
+                reports.push(PairReport {
+                    expected_device: expected.header.device.name.clone(),
+                    device: device.clone(),
+                    stats: accumulator.finish(),
+                });
             }
         }
+        reports
+    };
+
+    let reference = compare_reference(false);
+    let emulation = if is_conformant { compare_reference(true) } else { Vec::new() };
+    let deviating = emulation.iter()
+        .filter(|pair| pair.stats.differences > tolerance)
+        .map(|pair| pair.device.clone())
+        .collect();
+
+    let majority = majority::compute(
+        &devices, &files.iter().map(|f| f.data.as_slice()).collect::<Vec<_>>(), element_type
+    );
+
+    Ok(Report {
+        problem_name,
+        expression: files[0].header.expression.clone(),
+        is_conformant,
+        conformant_mode: input.2,
+        skipped,
+        data_bits: files[0].data.len() * 8,
+        differences: majority.disagreements,
+        devices,
+        device_properties,
+        matrix,
+        pairs,
+        majority,
+        reference,
+        emulation,
+        deviating,
+    })
+}
+
+/// Name of the device, with its UUID or index when other files come from devices with the same name.
+fn device_label(device: &DeviceInfo, files: &[ResultFile]) -> String {
+    if files.iter().filter(|f| f.header.device.name == device.name).count() < 2 {
+        return device.name.clone();
+    }
+    if device.uuid == [0; 16] {
+        format!("{} #{}", device.name, device.index)
+    } else {
+        format!("{} ({})", device.name, device.uuid.iter().map(|byte| format!("{byte:02x}")).collect::<String>())
     }
+}
 
-    None
+fn describe_device(device: &DeviceInfo) -> String {
+    let mut parts = Vec::new();
+    if !device.driver_name.is_empty() {
+        parts.push(format!("driver `{}` {} (id {})", device.driver_name, device.driver_info, device.driver_id));
+    }
+    if device.api_version != 0 {
+        let version = device.api_version;
+        parts.push(format!("Vulkan {}.{}.{}", version >> 22 & 0x7f, version >> 12 & 0x3ff, version & 0xfff));
+    }
+    if device.conformance_version != [0; 4] {
+        let [major, minor, subminor, patch] = device.conformance_version;
+        parts.push(format!("conformance {major}.{minor}.{subminor}.{patch}"));
+    }
+    if device.subgroup_size != 0 {
+        parts.push(format!("subgroup size {}", device.subgroup_size));
+    }
+    for (width, controls) in [16, 32, 64].iter().zip(device.float_controls.iter().flatten()) {
+        let flags = [
+            (controls.signed_zero_inf_nan_preserve, "preserves signed zero/inf/nan"),
+            (controls.denorm_preserve, "preserves denormals"),
+            (controls.denorm_flush_to_zero, "flushes denormals"),
+            (controls.rounding_mode_rte, "rte"),
+            (controls.rounding_mode_rtz, "rtz"),
+        ].iter().filter(|(set, _)| *set).map(|(_, name)| *name).collect::<Vec<_>>();
+        let flags = if flags.is_empty() { "no guarantees".to_owned() } else { flags.join(", ") };
+        parts.push(format!("f{width}: {flags}"));
+    }
+    parts.join("; ")
 }
@@ -0,0 +1,72 @@
+use rorsk_core::result_file::ElementType;
+use serde::Serialize;
+
+use crate::stats::element_key;
+
+/// Number of indices stored for each device, the rest is only counted.
+const SAMPLE_INDICES: usize = 8;
+
+#[derive(Serialize)]
+pub struct MajorityReport {
+    /// Indices where devices do not all return the same result.
+    pub disagreements: u64,
+    /// Indices where no result is returned by more than half of the devices.
+    pub no_majority: u64,
+    pub devices: Vec<DeviceMajority>,
+}
+
+#[derive(Serialize)]
+pub struct DeviceMajority {
+    pub device: String,
+    /// Indices where a majority exists and this device is not a part of it.
+    pub disagreements: u64,
+    pub sample_indices: Vec<usize>,
+}
+
+pub fn compute(devices: &[String], data: &[&[u8]], element_type: ElementType) -> MajorityReport {
+    let size = element_type.size();
+    let count = data[0].len() / size;
+
+    let mut report = MajorityReport {
+        disagreements: 0,
+        no_majority: 0,
+        devices: devices.iter().map(|device| DeviceMajority {
+            device: device.clone(),
+            disagreements: 0,
+            sample_indices: Vec::new(),
+        }).collect(),
+    };
+
+    let mut keys = vec![0; data.len()];
+    for index in 0..count {
+        for (key, data) in keys.iter_mut().zip(data) {
+            *key = element_key(&data[index * size..(index + 1) * size], element_type);
+        }
+
+        if keys.iter().all(|key| *key == keys[0]) {
+            continue;
+        }
+        report.disagreements += 1;
+
+        // Device counts are small, so the most common key is searched by brute force.
+        let (majority_key, majority_count) = keys.iter()
+            .map(|key| (*key, keys.iter().filter(|k| *k == key).count()))
+            .max_by_key(|(_, count)| *count)
+            .unwrap();
+        if majority_count * 2 <= keys.len() {
+            report.no_majority += 1;
+            continue;
+        }
+
+        for (device, key) in report.devices.iter_mut().zip(&keys) {
+            if *key != majority_key {
+                device.disagreements += 1;
+                if device.sample_indices.len() < SAMPLE_INDICES {
+                    device.sample_indices.push(index);
+                }
+            }
+        }
+    }
+
+    report
+}
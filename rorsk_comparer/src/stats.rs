@@ -0,0 +1,333 @@
+use rorsk_core::result_file::ElementType;
+use serde::Serialize;
+
+/// ULP errors up to this value are counted exactly, larger ones only by their power of two bucket.
+const EXACT_ULPS: usize = 256;
+
+/// Element decoded for statistics. Integers are treated as floats whose unit in the last place is 1.
+struct Value {
+    bits: u64,
+    /// Integer which grows by one with every representable value, -0.0 and 0.0 are both 0.
+    ordered: i64,
+    value: f64,
+    is_nan: bool,
+    is_infinite: bool,
+}
+
+impl Value {
+    fn read(bytes: &[u8], element_type: ElementType) -> Self {
+        match element_type {
+            ElementType::I32 => {
+                let v = i32::from_le_bytes(bytes.try_into().unwrap());
+                Value { bits: v as u32 as u64, ordered: v as i64, value: v as f64, is_nan: false, is_infinite: false }
+            }
+            ElementType::F32 => {
+                let bits = u32::from_le_bytes(bytes.try_into().unwrap());
+                let v = f32::from_bits(bits);
+                let ordered = sign_magnitude_to_ordered(bits as u64, 31);
+                Value { bits: bits as u64, ordered, value: v as f64, is_nan: v.is_nan(), is_infinite: v.is_infinite() }
+            }
+            ElementType::F64 => {
+                let bits = u64::from_le_bytes(bytes.try_into().unwrap());
+                let v = f64::from_bits(bits);
+                let ordered = sign_magnitude_to_ordered(bits, 63);
+                Value { bits, ordered, value: v, is_nan: v.is_nan(), is_infinite: v.is_infinite() }
+            }
+            ElementType::F16 => {
+                let bits = u16::from_le_bytes(bytes.try_into().unwrap());
+                let ordered = sign_magnitude_to_ordered(bits as u64, 15);
+                let abs = bits & 0x7FFF;
+                Value {
+                    bits: bits as u64,
+                    ordered,
+                    value: f16_to_f64(bits),
+                    is_nan: abs > 0x7C00,
+                    is_infinite: abs == 0x7C00,
+                }
+            }
+        }
+    }
+
+    fn key(&self) -> u64 {
+        if self.is_nan {
+            u64::MAX
+        } else if self.is_infinite {
+            u64::MAX - 1
+        } else {
+            self.bits
+        }
+    }
+
+    /// Like `key`, but infinities keep their sign.
+    fn exact_key(&self) -> u64 {
+        if self.is_nan { u64::MAX } else { self.bits }
+    }
+}
+
+/// Key which is equal for two elements exactly when they are not counted as a difference.
+pub fn element_key(bytes: &[u8], element_type: ElementType) -> u64 {
+    Value::read(bytes, element_type).key()
+}
+
+fn sign_magnitude_to_ordered(bits: u64, sign_bit: u32) -> i64 {
+    let magnitude = (bits & ((1 << sign_bit) - 1)) as i64;
+    if bits >> sign_bit != 0 { -magnitude } else { magnitude }
+}
+
+fn f16_to_f64(bits: u16) -> f64 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exp = ((bits >> 10) & 0x1F) as i32;
+    let fraction = (bits & 0x3FF) as f64;
+    match exp {
+        0 => sign * fraction * 2f64.powi(-24),
+        0x1F if fraction == 0.0 => sign * f64::INFINITY,
+        0x1F => f64::NAN,
+        _ => sign * (1.0 + fraction / 1024.0) * 2f64.powi(exp - 15),
+    }
+}
+
+#[derive(Serialize)]
+pub struct HistogramBucket {
+    pub min_ulp: u64,
+    pub max_ulp: u64,
+    pub count: u64,
+}
+
+#[derive(Serialize)]
+pub struct Stats {
+    pub count: u64,
+    /// Elements whose bits differ, every NaN is treated as equal. Infinities are compared without their sign between
+    /// devices, but with it against the CPU reference and emulation.
+    pub differences: u64,
+    /// Elements where only one of the devices returned NaN. They are left out of every error below.
+    pub nan_mismatches: u64,
+    /// Elements where the devices returned zeros of a different sign. They are counted in `differences`, but have an
+    /// error of 0 ULP.
+    pub signed_zero_mismatches: u64,
+    pub max_ulp: u64,
+    pub mean_ulp: f64,
+    /// Exact up to 256 ULPs, above that it is the lower bound of the power of two bucket holding the median.
+    pub median_ulp: u64,
+    pub within_0_ulp: u64,
+    pub within_1_ulp: u64,
+    pub within_2_ulp: u64,
+    pub within_n_ulp: u64,
+    pub n_ulp: u64,
+    pub max_absolute_error: f64,
+    pub mean_absolute_error: f64,
+    pub max_relative_error: f64,
+    pub mean_relative_error: f64,
+    /// Power of two buckets, the first one holds exactly 0 ULP. Empty buckets are omitted.
+    pub histogram: Vec<HistogramBucket>,
+}
+
+pub struct Accumulator {
+    element_type: ElementType,
+    bit_exact: bool,
+    n_ulp: u64,
+    count: u64,
+    differences: u64,
+    nan_mismatches: u64,
+    signed_zero_mismatches: u64,
+    exact: Vec<u64>,
+    above_exact: [u64; 65],
+    buckets: [u64; 65],
+    max_ulp: u64,
+    ulp_sum: u128,
+    within_n_ulp: u64,
+    max_absolute_error: f64,
+    absolute_error_sum: f64,
+    absolute_error_count: u64,
+    max_relative_error: f64,
+    relative_error_sum: f64,
+    relative_error_count: u64,
+}
+
+impl Accumulator {
+    /// With `bit_exact` set, infinities of a different sign are counted as a difference too.
+    pub fn new(element_type: ElementType, n_ulp: u64, bit_exact: bool) -> Self {
+        Accumulator {
+            element_type,
+            bit_exact,
+            n_ulp,
+            count: 0,
+            differences: 0,
+            nan_mismatches: 0,
+            signed_zero_mismatches: 0,
+            exact: vec![0; EXACT_ULPS + 1],
+            above_exact: [0; 65],
+            buckets: [0; 65],
+            max_ulp: 0,
+            ulp_sum: 0,
+            within_n_ulp: 0,
+            max_absolute_error: 0.0,
+            absolute_error_sum: 0.0,
+            absolute_error_count: 0,
+            max_relative_error: 0.0,
+            relative_error_sum: 0.0,
+            relative_error_count: 0,
+        }
+    }
+
+    /// Adds every element of two result data buffers, `expected` is used as the base of relative errors.
+    pub fn add_data(&mut self, expected: &[u8], data: &[u8]) {
+        let size = self.element_type.size();
+        for (a, b) in expected.chunks_exact(size).zip(data.chunks_exact(size)) {
+            self.add(a, b);
+        }
+    }
+
+    fn add(&mut self, expected: &[u8], data: &[u8]) {
+        let a = Value::read(expected, self.element_type);
+        let b = Value::read(data, self.element_type);
+        self.count += 1;
+
+        let differs = if self.bit_exact { a.exact_key() != b.exact_key() } else { a.key() != b.key() };
+        if differs {
+            self.differences += 1;
+            if a.value == 0.0 && b.value == 0.0 {
+                self.signed_zero_mismatches += 1;
+            }
+        }
+
+        if a.is_nan != b.is_nan {
+            self.nan_mismatches += 1;
+            return;
+        }
+
+        let ulp = if a.is_nan { 0 } else { a.ordered.abs_diff(b.ordered) };
+        if ulp as usize <= EXACT_ULPS {
+            self.exact[ulp as usize] += 1;
+        } else {
+            self.above_exact[bucket_index(ulp)] += 1;
+        }
+        self.buckets[bucket_index(ulp)] += 1;
+        self.max_ulp = self.max_ulp.max(ulp);
+        self.ulp_sum += ulp as u128;
+        if ulp <= self.n_ulp {
+            self.within_n_ulp += 1;
+        }
+
+        if a.is_nan || a.is_infinite || b.is_infinite {
+            return;
+        }
+
+        let absolute_error = (a.value - b.value).abs();
+        self.max_absolute_error = self.max_absolute_error.max(absolute_error);
+        self.absolute_error_sum += absolute_error;
+        self.absolute_error_count += 1;
+
+        if a.value != 0.0 {
+            let relative_error = absolute_error / a.value.abs();
+            self.max_relative_error = self.max_relative_error.max(relative_error);
+            self.relative_error_sum += relative_error;
+            self.relative_error_count += 1;
+        }
+    }
+
+    pub fn finish(&self) -> Stats {
+        let compared = self.count - self.nan_mismatches;
+
+        let mut histogram = Vec::new();
+        for (index, count) in self.buckets.iter().enumerate() {
+            if *count != 0 {
+                let (min_ulp, max_ulp) = bucket_range(index);
+                histogram.push(HistogramBucket { min_ulp, max_ulp, count: *count });
+            }
+        }
+
+        Stats {
+            count: self.count,
+            differences: self.differences,
+            nan_mismatches: self.nan_mismatches,
+            signed_zero_mismatches: self.signed_zero_mismatches,
+            max_ulp: self.max_ulp,
+            mean_ulp: mean(self.ulp_sum as f64, compared),
+            median_ulp: self.median_ulp(compared),
+            within_0_ulp: self.exact[0],
+            within_1_ulp: self.exact[..=1].iter().sum(),
+            within_2_ulp: self.exact[..=2].iter().sum(),
+            within_n_ulp: self.within_n_ulp,
+            n_ulp: self.n_ulp,
+            max_absolute_error: self.max_absolute_error,
+            mean_absolute_error: mean(self.absolute_error_sum, self.absolute_error_count),
+            max_relative_error: self.max_relative_error,
+            mean_relative_error: mean(self.relative_error_sum, self.relative_error_count),
+            histogram,
+        }
+    }
+
+    fn median_ulp(&self, compared: u64) -> u64 {
+        if compared == 0 {
+            return 0;
+        }
+
+        let middle = (compared - 1) / 2;
+        let mut seen = 0;
+        for (ulp, count) in self.exact.iter().enumerate() {
+            seen += count;
+            if seen > middle {
+                return ulp as u64;
+            }
+        }
+        for (index, count) in self.above_exact.iter().enumerate() {
+            seen += count;
+            if seen > middle {
+                return bucket_range(index).0.max(EXACT_ULPS as u64 + 1);
+            }
+        }
+
+        unreachable!()
+    }
+}
+
+fn bucket_index(ulp: u64) -> usize {
+    (u64::BITS - ulp.leading_zeros()) as usize
+}
+
+fn bucket_range(index: usize) -> (u64, u64) {
+    match index {
+        0 => (0, 0),
+        64 => (1 << 63, u64::MAX),
+        _ => (1 << (index - 1), (1 << index) - 1),
+    }
+}
+
+fn mean(sum: f64, count: u64) -> f64 {
+    if count == 0 { 0.0 } else { sum / count as f64 }
+}
+
+impl Stats {
+    pub fn to_text(&self, prefix: &str) -> String {
+        let mut message = String::new();
+        message.push_str(&format!("{prefix}Differences: {} of {}", self.differences, self.count));
+        message.push_str(&format!("{prefix}NaN mismatches: {}", self.nan_mismatches));
+        message.push_str(&format!("{prefix}Signed zero mismatches: {}", self.signed_zero_mismatches));
+        message.push_str(&format!(
+            "{prefix}ULP error: max {}, mean {:.4}, median {}", self.max_ulp, self.mean_ulp, self.median_ulp
+        ));
+        message.push_str(&format!(
+            "{prefix}Within 0/1/2/{} ULP: {}/{}/{}/{}",
+            self.n_ulp, self.within_0_ulp, self.within_1_ulp, self.within_2_ulp, self.within_n_ulp
+        ));
+        message.push_str(&format!(
+            "{prefix}Absolute error: max {:e}, mean {:e}", self.max_absolute_error, self.mean_absolute_error
+        ));
+        message.push_str(&format!(
+            "{prefix}Relative error: max {:e}, mean {:e}", self.max_relative_error, self.mean_relative_error
+        ));
+
+        message.push_str(&format!("{prefix}ULP histogram:"));
+        for bucket in &self.histogram {
+            if bucket.min_ulp == bucket.max_ulp {
+                message.push_str(&format!("{prefix}  {}: {}", bucket.min_ulp, bucket.count));
+            } else {
+                message.push_str(&format!("{prefix}  {}-{}: {}", bucket.min_ulp, bucket.max_ulp, bucket.count));
+            }
+        }
+        message
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,106 @@
+use rorsk_core::result_file::ElementType;
+
+use super::{bucket_index, bucket_range, element_key, Accumulator, Stats, EXACT_ULPS};
+
+/// Compares pairs of f32 bits, the first of each pair is expected.
+fn f32_stats(pairs: &[(u32, u32)], bit_exact: bool) -> Stats {
+    let expected = pairs.iter().flat_map(|(a, _)| a.to_le_bytes()).collect::<Vec<_>>();
+    let data = pairs.iter().flat_map(|(_, b)| b.to_le_bytes()).collect::<Vec<_>>();
+    let mut accumulator = Accumulator::new(ElementType::F32, 4, bit_exact);
+    accumulator.add_data(&expected, &data);
+    accumulator.finish()
+}
+
+/// Pairs of 1.0 and values the given number of ULPs above it.
+fn ulp_pairs(ulps: &[u32]) -> Vec<(u32, u32)> {
+    let one = 1f32.to_bits();
+    ulps.iter().map(|ulp| (one, one + ulp)).collect()
+}
+
+const NEGATIVE_ZERO: u32 = 0x8000_0000;
+
+#[test]
+fn ulp_distance_across_zero() {
+    // The smallest subnormals of both signs are two ULPs apart, both zeros are between them.
+    assert_eq!(f32_stats(&[(1, 0x8000_0001)], false).max_ulp, 2);
+    assert_eq!(f32_stats(&[(NEGATIVE_ZERO, 1)], false).max_ulp, 1);
+    assert_eq!(f32_stats(&[(0x8000_0001, 0)], false).max_ulp, 1);
+
+    // Across the sign boundary the distance is the sum of both magnitudes.
+    let (one, minus_one) = (1f32.to_bits(), (-1f32).to_bits());
+    assert_eq!(f32_stats(&[(minus_one, one)], false).max_ulp, 2 * one as u64);
+}
+
+#[test]
+fn signed_zeros_differ_with_zero_ulp() {
+    for bit_exact in [false, true] {
+        let stats = f32_stats(&[(0, NEGATIVE_ZERO), (NEGATIVE_ZERO, 0), (0, 0)], bit_exact);
+        assert_eq!(stats.differences, 2);
+        assert_eq!(stats.signed_zero_mismatches, 2);
+        assert_eq!((stats.max_ulp, stats.within_0_ulp), (0, 3));
+    }
+    let key = |value: f32| element_key(&value.to_le_bytes(), ElementType::F32);
+    assert_ne!(key(0.0), key(-0.0));
+}
+
+#[test]
+fn subnormals() {
+    // The largest subnormal and the smallest normal are neighbours.
+    let stats = f32_stats(&[(0x007F_FFFF, 0x0080_0000), (0x807F_FFFF, 0x8080_0000), (0x0000_0010, 0x0000_0001)], false);
+    assert_eq!(stats.max_ulp, 15);
+    assert_eq!(stats.within_1_ulp, 2);
+    assert!(stats.max_absolute_error > 0.0);
+
+    // f16 subnormals are ordered the same way.
+    let mut accumulator = Accumulator::new(ElementType::F16, 4, false);
+    accumulator.add_data(&[0xFF, 0x03, 0x01, 0x80], &[0x00, 0x04, 0x01, 0x00]);
+    let stats = accumulator.finish();
+    assert_eq!((stats.max_ulp, stats.within_2_ulp), (2, 2));
+}
+
+#[test]
+fn exact_median() {
+    assert_eq!(f32_stats(&ulp_pairs(&[0, 5, 256]), false).median_ulp, 5);
+    assert_eq!(f32_stats(&ulp_pairs(&[256, 1, 256]), false).median_ulp, 256);
+    // The lower of the two middle values.
+    assert_eq!(f32_stats(&ulp_pairs(&[7, 3, 1, 1000]), false).median_ulp, 3);
+    assert_eq!(f32_stats(&[], false).median_ulp, 0);
+}
+
+#[test]
+fn bucketed_median() {
+    // 300 and 500 share the 256..=511 bucket, whose lower bound is above the exact range.
+    let stats = f32_stats(&ulp_pairs(&[300, 500, 1000]), false);
+    assert_eq!(stats.median_ulp, EXACT_ULPS as u64 + 1);
+    let stats = f32_stats(&ulp_pairs(&[0, 1000, 5000]), false);
+    assert_eq!(stats.median_ulp, 512);
+    assert_eq!(stats.max_ulp, 5000);
+}
+
+#[test]
+fn bucket_edges() {
+    for (ulp, index) in [(0, 0), (1, 1), (2, 2), (3, 2), (4, 3), (255, 8), (256, 9), (1 << 63, 64), (u64::MAX, 64)] {
+        assert_eq!(bucket_index(ulp), index, "{ulp}");
+        let (min, max) = bucket_range(index);
+        assert!(min <= ulp && ulp <= max, "{ulp} is not in {min}..={max}");
+    }
+    for index in 1..64 {
+        assert_eq!(bucket_range(index).1 + 1, bucket_range(index + 1).0);
+    }
+
+    let stats = f32_stats(&ulp_pairs(&[0, 1, 2, 3, 4]), false);
+    let histogram = stats.histogram.iter().map(|b| (b.min_ulp, b.max_ulp, b.count)).collect::<Vec<_>>();
+    assert_eq!(histogram, [(0, 0, 1), (1, 1, 1), (2, 3, 2), (4, 7, 1)]);
+}
+
+#[test]
+fn nan_mismatches_are_excluded() {
+    let nan = f32::NAN.to_bits();
+    let stats = f32_stats(&[(nan, 1f32.to_bits()), (1f32.to_bits(), nan), (nan, nan | 1), (1, 2)], false);
+    assert_eq!(stats.nan_mismatches, 2);
+    // NaNs with different payloads are equal.
+    assert_eq!(stats.differences, 3);
+    assert_eq!((stats.max_ulp, stats.within_0_ulp, stats.within_1_ulp), (1, 1, 2));
+    assert_eq!(stats.mean_ulp, 0.5);
+    assert_eq!(stats.histogram.iter().map(|b| b.count).sum::<u64>(), 2);
+}
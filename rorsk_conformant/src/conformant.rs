@@ -0,0 +1,1807 @@
+use std::{mem, collections::HashMap, fmt, str::FromStr};
+
+use crate::{disassembler, spirv::{result_layout, Block, Function, Instruction, Module, ParseError}, validator::{self, ValidationError}};
+
+mod glsl_std_450;
+mod ieee;
+mod ieee_f16;
+mod ieee_f64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// Deterministic software float with a custom 24+8 bit layout. Identical across vendors, but not IEEE-754 correct.
+    Sf32,
+    /// Correctly rounded (round to nearest, ties to even) IEEE-754 binary32.
+    Ieee,
+}
+
+impl FromStr for Mode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sf32" => Ok(Mode::Sf32),
+            "ieee" => Ok(Mode::Ieee),
+            _ => Err(format!("unknown mode `{s}`, expected `sf32` or `ieee`")),
+        }
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mode::Sf32 => write!(f, "sf32"),
+            Mode::Ieee => write!(f, "ieee"),
+        }
+    }
+}
+
+/// Groups of float instructions which are rewritten together. Instructions of groups which are not selected are left
+/// to the driver.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OpSet {
+    /// OpFAdd, OpFSub, OpFMul, OpFDiv and OpVectorTimesScalar. OpFRem, OpFMod, matrix products, OpOuterProduct and
+    /// OpDot are not lowered yet, modules which use them on floats can not be rewritten with this op set.
+    Arithmetic,
+    /// Sqrt and InverseSqrt. Sqrt is correctly rounded, InverseSqrt is within 1.5 ULP.
+    Sqrt,
+    /// Fma.
+    Fma,
+    /// Exp, Exp2, Log, Log2 and Pow. Exp and Exp2 are within 1.5 ULP, Log and Log2 within 3 ULP and Pow within 2 ULP.
+    Exponential,
+    /// Sin and Cos, within 2 ULP for every finite argument.
+    Trigonometric,
+}
+
+impl OpSet {
+    pub const ALL: [OpSet; 5] = [
+        OpSet::Arithmetic, OpSet::Sqrt, OpSet::Fma, OpSet::Exponential, OpSet::Trigonometric
+    ];
+}
+
+impl FromStr for OpSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "arithmetic" => Ok(OpSet::Arithmetic),
+            "sqrt" => Ok(OpSet::Sqrt),
+            "fma" => Ok(OpSet::Fma),
+            "exponential" => Ok(OpSet::Exponential),
+            "trigonometric" => Ok(OpSet::Trigonometric),
+            _ => Err(format!(
+                "unknown op set `{s}`, expected `arithmetic`, `sqrt`, `fma`, `exponential` or `trigonometric`"
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Options {
+    pub mode: Mode,
+    pub op_sets: Vec<OpSet>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            mode: Mode::Sf32,
+            op_sets: OpSet::ALL.to_vec(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Parse(ParseError),
+    /// The module is invalid before it was rewritten.
+    InvalidInput(ValidationError),
+    /// A float instruction of a selected op set is left with driver dependent precision, because it has no software
+    /// implementation. `instruction` is its name, like `OpFRem` or `GLSL.std.450 Tan`.
+    NotConformant { instruction: String, result: Option<u32>, reason: &'static str },
+    /// The rewrite produced an invalid module, which is a bug of the rewriter.
+    InvalidOutput(ValidationError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse(e) => write!(f, "failed to parse SPIR-V module: {e}"),
+            Error::InvalidInput(e) => write!(f, "SPIR-V module is invalid: {e}"),
+            Error::NotConformant { instruction, result: Some(result), reason } => {
+                write!(f, "{instruction} defining %{result} has driver dependent precision, {reason}")
+            },
+            Error::NotConformant { instruction, result: None, reason } => {
+                write!(f, "{instruction} has driver dependent precision, {reason}")
+            },
+            Error::InvalidOutput(e) => write!(f, "rewritten SPIR-V module is invalid: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub struct Processed {
+    pub original: Module,
+    pub module: Module,
+    /// Ids of the types, constants and functions which were created or reused by the rewrite, by their names.
+    pub created: HashMap<String, u32>,
+}
+
+/// Rewrites float instructions of the selected op sets, so their results do not depend on the driver.
+pub fn process(spirv: &[u32], options: Options) -> Result<Vec<u32>, Error> {
+    rewrite(spirv, options).map(|processed| processed.module.emit())
+}
+
+/// Like [`process`], but also returns the original module and the names of ids created by the rewrite.
+pub fn rewrite(spirv: &[u32], options: Options) -> Result<Processed, Error> {
+    let original = Module::parse(spirv).map_err(Error::Parse)?;
+    // The rewriter relies on valid operands, so invalid modules are rejected before it sees them.
+    validator::validate(&original).map_err(Error::InvalidInput)?;
+
+    let mut buffer = Buffer::new(original.clone(), options);
+    buffer.process();
+    buffer.check_conformant()?;
+    validator::validate(&buffer.module).map_err(Error::InvalidOutput)?;
+
+    Ok(Processed {
+        original,
+        module: buffer.module,
+        created: buffer.created,
+    })
+}
+
+/// Returns the op set of a GLSL.std.450 instruction with driver dependent precision.
+fn ext_inst_op_set(number: u32) -> Option<OpSet> {
+    match number {
+        // Sin .. Atanh, Atan2
+        13..=25 => Some(OpSet::Trigonometric),
+        // Pow, Exp, Log, Exp2, Log2
+        26..=30 => Some(OpSet::Exponential),
+        // Sqrt, InverseSqrt
+        31 | 32 => Some(OpSet::Sqrt),
+        // Fma
+        50 => Some(OpSet::Fma),
+        // Determinant, MatrixInverse, Length .. Refract
+        33 | 34 | 66..=72 => Some(OpSet::Arithmetic),
+        _ => None,
+    }
+}
+
+struct Buffer {
+    module: Module,
+    mode: Mode,
+    op_sets: Vec<OpSet>,
+    created: HashMap<String, u32>,
+    functions: Vec<Function>,
+}
+
+impl Buffer {
+    fn new(module: Module, options: Options) -> Self {
+        let mut created = HashMap::new();
+
+        for instruction in &module.types_global_values {
+            let operands = &instruction.operands;
+            let name = match instruction.opcode {
+                // OpTypeBool
+                20 => "bool".to_owned(),
+                // OpTypeInt
+                21 => format!("int-{}-{}", operands[1], operands[2]),
+                // OpTypeFloat
+                22 => format!("float-{}", operands[1]),
+                // OpTypeVector
+                23 => format!("vector-{}-{}", operands[1], operands[2]),
+                // OpTypeFunction
+                33 => format!("function-{}-{:?}", operands[1], &operands[2..]),
+                // OpTypePointer
+                32 => format!("pointer-{}-{}", operands[2], operands[1]),
+                // OpConstant
+                43 => match module.global(operands[0]).map(|t| (t.opcode, &t.operands)) {
+                    Some((21, t)) if operands.len() == 3 || operands[3] == 0 => {
+                        format!("int-{}-{}-{}", t[1], t[2], operands[2])
+                    },
+                    Some((22, t)) if t[1] == 32 => format!("float-32-{}", f32::from_bits(operands[2])),
+                    _ => continue,
+                },
+                _ => continue,
+            };
+
+            if let Some(id) = instruction.result_id() {
+                created.entry(name).or_insert(id);
+            }
+        }
+
+        for instruction in &module.ext_inst_imports {
+            created.insert(format!("ext-inst-import-{}", instruction.string(1)), instruction.operands[0]);
+        }
+
+        Buffer {
+            module,
+            mode: options.mode,
+            op_sets: options.op_sets,
+            created,
+            functions: Vec::new(),
+        }
+    }
+
+    fn process(&mut self) {
+        for function in mem::take(&mut self.module.functions) {
+            self.functions.push(Function {
+                definition: function.definition,
+                parameters: function.parameters,
+                blocks: Vec::new(),
+            });
+
+            for block in function.blocks {
+                self.insert_op_label(block.label);
+
+                for instruction in block.instructions {
+                    match instruction.opcode {
+                        129 | 131 | 133 | 136 | 142 if !self.op_sets.contains(&OpSet::Arithmetic) => {
+                            self.insert(instruction)
+                        },
+                        // OpFAdd
+                        129 => self.op_f_binary(Self::conformant_add, &instruction),
+                        // OpFSub
+                        131 => self.op_f_binary(Self::conformant_sub, &instruction),
+                        // OpFMul
+                        133 => self.op_f_binary(Self::conformant_mul, &instruction),
+                        // OpFDiv
+                        136 => self.op_f_binary(Self::conformant_div, &instruction),
+                        // OpVectorTimesScalar
+                        142 => self.op_f_binary(Self::conformant_mul, &instruction),
+                        // OpExtInst
+                        12 => self.op_ext_inst(&instruction),
+                        _ => self.insert(instruction),
+                    };
+                }
+            }
+
+            self.insert_op_function_end();
+        }
+    }
+
+    /// Returns an error when a float instruction of a selected op set, whose precision depends on the driver, is left
+    /// in the module.
+    fn check_conformant(&self) -> Result<(), Error> {
+        let glsl_std_450 = self.created.get("ext-inst-import-GLSL.std.450").copied();
+
+        for instruction in self.module.functions.iter().flat_map(|f| &f.blocks).flat_map(|b| &b.instructions) {
+            let op_set = match instruction.opcode {
+                // OpFAdd, OpFSub, OpFMul, OpFDiv, OpFRem, OpFMod, OpVectorTimesScalar .. OpDot
+                129 | 131 | 133 | 136 | 140 | 141 | 142..=148 => Some(OpSet::Arithmetic),
+                // OpExtInst
+                12 if Some(instruction.operands[2]) != glsl_std_450 => None,
+                12 => ext_inst_op_set(instruction.operands[3]),
+                _ => None,
+            };
+
+            if !op_set.is_some_and(|s| self.op_sets.contains(&s)) {
+                continue;
+            }
+            let Some(float) = self.float_component_count(instruction.operands[0]) else { continue };
+
+            let reason = match (instruction.opcode, float) {
+                (140 | 141, _) => "the floating point remainder has no software implementation yet",
+                (143..=148, _) => "matrix and dot products are not lowered to multiplications and additions yet",
+                (12, (64, _)) => "it has no f64 software implementation yet",
+                _ => "it has no software implementation yet",
+            };
+            let instruction_name = match instruction.opcode {
+                12 => format!("GLSL.std.450 {}", disassembler::glsl_std_450_name(instruction.operands[3])
+                    .map_or(instruction.operands[3].to_string(), str::to_owned)),
+                opcode => disassembler::opcode_name(opcode).map_or(format!("Op{opcode}"), str::to_owned),
+            };
+            return Err(Error::NotConformant { instruction: instruction_name, result: instruction.result_id(), reason });
+        }
+
+        Ok(())
+    }
+
+    fn op_f_binary(&mut self, conformant_function: fn(&mut Self, u32) -> u32, instruction: &Instruction) {
+        let (result_type, result_id) = (instruction.operands[0], instruction.operands[1]);
+        let (lhs, rhs) = (instruction.operands[2], instruction.operands[3]);
+
+        // Other types are left to the driver.
+        let Some((width, component_count)) = self.float_component_count(result_type) else {
+            self.insert(instruction.clone());
+            return;
+        };
+
+        let conformant_function = conformant_function(self, width);
+        let float = self.get_op_type_float(width);
+
+        // Vectors are lowered component-wise, vec4 r = vec4(f(a.x, b.x), f(a.y, b.y), ...);
+        let mut components = Vec::new();
+        for i in 0..component_count {
+            let (lhs, rhs, result_type, id) = match component_count {
+                1 => (lhs, rhs, result_type, result_id),
+                _ => {
+                    let lhs = self.insert_op_composite_extract(float, lhs, i);
+                    let rhs = match instruction.opcode {
+                        // OpVectorTimesScalar
+                        142 => rhs,
+                        _ => self.insert_op_composite_extract(float, rhs, i),
+                    };
+                    (lhs, rhs, float, self.get_next_id())
+                },
+            };
+
+            match width {
+                32 => self.insert_f32_conformant_call(conformant_function, result_type, id, lhs, rhs),
+                _ => {
+                    // Other widths call the IEEE-754 implementation on bits directly.
+                    let uint = self.get_op_type_int(width.max(32), false);
+                    let lhs = self.insert_float_to_bits(width, lhs);
+                    let rhs = self.insert_float_to_bits(width, rhs);
+                    let call = self.insert_op_function_call(uint, conformant_function, &[lhs, rhs]);
+                    self.insert_float_from_bits(width, result_type, id, call);
+                },
+            };
+            components.push(id);
+        }
+
+        if component_count != 1 {
+            // OpCompositeConstruct
+            let mut operands = vec![result_type, result_id];
+            operands.extend(components);
+            self.insert(Instruction::new(80, &operands));
+        }
+    }
+
+    fn insert_f32_conformant_call(&mut self, conformant_function: u32, result_type: u32, result_id: u32, lhs: u32, rhs: u32) {
+        let f32 = self.get_op_type_float(32);
+        let pf32 = self.get_pointer_type(f32, 7);
+
+        // Create variables.
+        let lhs_variable = self.insert_op_variable(pf32, 7);
+        let rhs_variable = self.insert_op_variable(pf32, 7);
+
+        // Copy parameters to variables.
+        self.insert_op_store(lhs_variable, lhs);
+        self.insert_op_store(rhs_variable, rhs);
+
+        // Execute conformant function.
+        self.insert(Instruction::new(57, &[
+            result_type, result_id, conformant_function, lhs_variable, rhs_variable
+        ]));
+    }
+
+    fn op_ext_inst(&mut self, instruction: &Instruction) {
+        let (result_type, result_id) = (instruction.operands[0], instruction.operands[1]);
+        let (set, number) = (instruction.operands[2], instruction.operands[3]);
+        let arguments = &instruction.operands[4..];
+
+        // Both modes use the IEEE-754 implementations, sf32 has no counterparts for these.
+        let ieee_function: fn(&mut Self) -> u32 = match number {
+            _ if self.created.get("ext-inst-import-GLSL.std.450") != Some(&set)
+                || !ext_inst_op_set(number).is_some_and(|s| self.op_sets.contains(&s)) =>
+            {
+                self.insert(instruction.clone());
+                return;
+            },
+            // Sin
+            13 => Self::ieee_f32_sin,
+            // Cos
+            14 => Self::ieee_f32_cos,
+            // Pow
+            26 => Self::ieee_f32_pow,
+            // Exp
+            27 => Self::ieee_f32_exp,
+            // Log
+            28 => Self::ieee_f32_log,
+            // Exp2
+            29 => Self::ieee_f32_exp2,
+            // Log2
+            30 => Self::ieee_f32_log2,
+            // Sqrt
+            31 => Self::ieee_f32_sqrt,
+            // InverseSqrt
+            32 => Self::ieee_f32_inverse_sqrt,
+            // Fma
+            50 => Self::ieee_f32_fma,
+            _ => {
+                self.insert(instruction.clone());
+                return;
+            },
+        };
+
+        // f64 is left to the driver, Vulkan supports most of these for 16 and 32 bit floats only.
+        let Some((width @ (16 | 32), component_count)) = self.float_component_count(result_type) else {
+            self.insert(instruction.clone());
+            return;
+        };
+
+        let ieee_function = ieee_function(self);
+
+        let float = self.get_op_type_float(width);
+        let u32 = self.get_op_type_int(32, false);
+
+        // f16 is computed in f32 and rounded back.
+        // float r = uintBitsToFloat(f(floatBitsToUint(a), ...));
+        // Vectors are lowered component-wise, like in op_f_binary.
+        let mut components = Vec::new();
+        for i in 0..component_count {
+            let operands = arguments.iter().map(|argument| {
+                let component = match component_count {
+                    1 => *argument,
+                    _ => self.insert_op_composite_extract(float, *argument, i),
+                };
+                let bits = self.insert_float_to_bits(width, component);
+                match width {
+                    16 => {
+                        let ieee_f16_to_f32 = self.ieee_f16_to_f32();
+                        self.insert_op_function_call(u32, ieee_f16_to_f32, &[bits])
+                    },
+                    _ => bits,
+                }
+            }).collect::<Vec<_>>();
+
+            let call = self.insert_op_function_call(u32, ieee_function, &operands);
+            let call = match width {
+                16 => {
+                    let ieee_f32_to_f16 = self.ieee_f32_to_f16();
+                    self.insert_op_function_call(u32, ieee_f32_to_f16, &[call])
+                },
+                _ => call,
+            };
+
+            let (result_type, id) = match component_count {
+                1 => (result_type, result_id),
+                _ => (float, self.get_next_id()),
+            };
+            self.insert_float_from_bits(width, result_type, id, call);
+            components.push(id);
+        }
+
+        if component_count != 1 {
+            // OpCompositeConstruct
+            let mut operands = vec![result_type, result_id];
+            operands.extend(components);
+            self.insert(Instruction::new(80, &operands));
+        }
+    }
+
+    /// Returns the width and the component count of a float scalar or vector, None for every other type.
+    fn float_component_count(&self, type_id: u32) -> Option<(u32, u32)> {
+        match self.module.global(type_id).map(|t| (t.opcode, &t.operands)) {
+            // OpTypeFloat
+            Some((22, t)) => Some((t[1], 1)),
+            // OpTypeVector
+            Some((23, t)) => self.float_component_count(t[1]).map(|(width, _)| (width, t[2])),
+            _ => None,
+        }
+    }
+
+    /// Returns the bits of a float as uint, or as uint64_t for f64.
+    fn insert_float_to_bits(&mut self, width: u32, value: u32) -> u32 {
+        match width {
+            16 => {
+                // There is no 16 bit integer without the Int16 capability, so the half is paired into a 32 bit
+                // vector.
+                // floatBitsToUint(f16vec2(value, value)) & 0xFFFF
+                let f16 = self.get_op_type_float(16);
+                let f16vec2 = self.get_op_type_vector(f16, 2);
+                let u32 = self.get_op_type_int(32, false);
+                let construct = self.insert_result(80, f16vec2, &[value, value]);
+                let bitcast = self.insert_op_bitcast(u32, construct);
+                let temp = self.get_const_int(32, false, 0xFFFF);
+                self.insert_op_bitwise_and(u32, bitcast, temp)
+            },
+            _ => {
+                let uint = self.get_op_type_int(width, false);
+                self.insert_op_bitcast(uint, value)
+            },
+        }
+    }
+
+    fn insert_float_from_bits(&mut self, width: u32, result_type: u32, result_id: u32, bits: u32) {
+        match width {
+            16 => {
+                // uintBitsToFloat(f16vec2(bits)).x
+                let f16 = self.get_op_type_float(16);
+                let f16vec2 = self.get_op_type_vector(f16, 2);
+                let bitcast = self.insert_op_bitcast(f16vec2, bits);
+                self.insert(Instruction::new(81, &[result_type, result_id, bitcast, 0]));
+            },
+            // OpBitcast
+            _ => self.insert(Instruction::new(124, &[result_type, result_id, bits])),
+        };
+    }
+
+    fn insert(&mut self, instruction: Instruction) {
+        self.functions.last_mut().expect("Instruction inserted outside of a function.")
+            .blocks.last_mut().expect("Instruction inserted outside of a block.")
+            .instructions.push(instruction);
+    }
+
+    fn insert_result(&mut self, opcode: u16, type_id: u32, operands: &[u32]) -> u32 {
+        let id = self.get_next_id();
+
+        let mut instruction = Instruction::new(opcode, &[type_id, id]);
+        instruction.operands.extend_from_slice(operands);
+        self.insert(instruction);
+
+        id
+    }
+
+    fn insert_global(&mut self, name: String, opcode: u16, operands: &[u32]) -> u32 {
+        let id = self.get_next_id();
+
+        let mut instruction = Instruction::new(opcode, operands);
+        instruction.operands.insert(if result_layout(opcode).0 { 1 } else { 0 }, id);
+        self.module.types_global_values.push(instruction);
+
+        self.created.insert(name, id);
+        id
+    }
+
+    fn get_next_id(&mut self) -> u32 {
+        let id = self.module.header.bound;
+        self.module.header.bound += 1;
+        id
+    }
+
+    fn enable_capability(&mut self, capability: u32) {
+        if !self.module.capabilities.iter().any(|i| i.operands[0] == capability) {
+            self.module.capabilities.push(Instruction::new(17, &[capability]));
+        }
+    }
+
+    fn get_ext_inst_import(&mut self, set: &str) -> u32 {
+        let name = format!("ext-inst-import-{}", set);
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let id = self.get_next_id();
+
+        let mut bytes = set.as_bytes().to_vec();
+        bytes.resize((bytes.len() / 4 + 1) * 4, 0);
+        let mut operands = vec![id];
+        operands.extend(bytes.chunks_exact(4).map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]])));
+        self.module.ext_inst_imports.push(Instruction::new(11, &operands));
+
+        self.created.insert(name, id);
+        id
+    }
+
+    fn get_op_type_bool(&mut self) -> u32 {
+        let name = "bool".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        self.insert_global(name, 20, &[])
+    }
+
+    fn get_op_type_int(&mut self, width: u32, signedness: bool) -> u32 {
+        let name = format!("int-{}-{}", width, if signedness { "1" } else { "0" });
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        if width == 64 {
+            // Enable Int64 capabilty.
+            self.enable_capability(11);
+        }
+
+        self.insert_global(name, 21, &[width, signedness as u32])
+    }
+
+    fn get_op_type_float(&mut self, width: u32) -> u32 {
+        let name = format!("float-{}", width);
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        self.insert_global(name, 22, &[width])
+    }
+
+    fn get_const_int(&mut self, width: u32, signedness: bool, value: u32) -> u32 {
+        let name = format!("int-{}-{}-{}", width, if signedness { "1" } else { "0" }, value);
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let i_id = self.get_op_type_int(width, signedness);
+
+        if width == 64 {
+            self.insert_global(name, 43, &[i_id, value, 0])
+        } else {
+            self.insert_global(name, 43, &[i_id, value])
+        }
+    }
+
+    fn get_const_u64(&mut self, value: u64) -> u32 {
+        let name = format!("int-64-0-{}", value);
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let i_id = self.get_op_type_int(64, false);
+        self.insert_global(name, 43, &[i_id, value as u32, (value >> 32) as u32])
+    }
+
+    fn get_const_f32(&mut self, width: u32, value: f32) -> u32 {
+        let name = format!("float-{}-{}", width, value);
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let f32_id = self.get_op_type_float(width);
+        self.insert_global(name, 43, &[f32_id, value.to_bits()])
+    }
+
+    fn get_op_type_vector(&mut self, component_type: u32, component_count: u32) -> u32 {
+        let name = format!("vector-{}-{}", component_type, component_count);
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        self.insert_global(name, 23, &[component_type, component_count])
+    }
+
+    fn get_type_function(&mut self, return_type: u32, parameter_types: &[u32]) -> u32 {
+        let name = format!("function-{}-{:?}", return_type, parameter_types);
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let mut operands = vec![return_type];
+        operands.extend_from_slice(parameter_types);
+        self.insert_global(name, 33, &operands)
+    }
+
+    fn get_pointer_type(&mut self, type_: u32, storage_class: u32) -> u32 {
+        let name = format!("pointer-{}-{}", type_, storage_class);
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        self.insert_global(name, 32, &[storage_class, type_])
+    }
+
+    fn insert_op_function(&mut self, result_type_id: u32, function_control: u32, function_type_id: u32) -> u32 {
+        let id = self.get_next_id();
+
+        self.functions.push(Function {
+            definition: Instruction::new(54, &[result_type_id, id, function_control, function_type_id]),
+            parameters: Vec::new(),
+            blocks: Vec::new(),
+        });
+
+        id
+    }
+
+    fn insert_op_function_parameter(&mut self, type_id: u32) -> u32 {
+        let id = self.get_next_id();
+
+        self.functions.last_mut().expect("Parameter inserted outside of a function.")
+            .parameters.push(Instruction::new(55, &[type_id, id]));
+
+        id
+    }
+
+    fn insert_op_label(&mut self, id: u32) {
+        self.functions.last_mut().expect("Label inserted outside of a function.").blocks.push(Block {
+            label: id,
+            instructions: Vec::new(),
+        });
+    }
+
+    fn insert_op_function_end(&mut self) {
+        let function = self.functions.pop().expect("Function end inserted outside of a function.");
+        self.module.functions.push(function);
+    }
+
+    fn insert_op_variable(&mut self, type_id: u32, storage_class: u32) -> u32 {
+        let id = self.get_next_id();
+
+        // Variables must be declared at the beginning of the first block of a function.
+        let block = self.functions.last_mut().expect("Variable inserted outside of a function.")
+            .blocks.first_mut().expect("Variable inserted outside of a block.");
+        let index = block.instructions.iter().take_while(|i| i.opcode == 59).count();
+        block.instructions.insert(index, Instruction::new(59, &[type_id, id, storage_class]));
+
+        id
+    }
+
+    fn insert_op_branch(&mut self, target_label: u32) {
+        self.insert(Instruction::new(249, &[target_label]));
+    }
+
+    fn insert_op_store(&mut self, variable_pointer: u32, object: u32) {
+        self.insert(Instruction::new(62, &[variable_pointer, object]));
+    }
+
+    fn insert_op_undef(&mut self, result_type_id: u32) -> u32 {
+        self.insert_result(1, result_type_id, &[])
+    }
+
+    fn insert_op_selection_merge(&mut self, merge_block: u32, selection_control: u32) {
+        self.insert(Instruction::new(247, &[merge_block, selection_control]));
+    }
+
+    fn insert_op_branch_conditional(&mut self, condition: u32, true_label: u32, false_label: u32) {
+        self.insert(Instruction::new(250, &[condition, true_label, false_label]));
+    }
+
+    fn insert_op_loop_merge(&mut self, merge_block: u32, continue_target: u32, loop_control: u32) {
+        self.insert(Instruction::new(246, &[merge_block, continue_target, loop_control]));
+    }
+
+    fn insert_op_return_value(&mut self, value_id: u32) {
+        self.insert(Instruction::new(254, &[value_id]));
+    }
+
+    fn insert_op_ext_inst(&mut self, type_id: u32, set: u32, instruction: u32, operands: &[u32]) -> u32 {
+        let mut words = vec![set, instruction];
+        words.extend_from_slice(operands);
+        self.insert_result(12, type_id, &words)
+    }
+
+    fn insert_op_function_call(&mut self, type_id: u32, function_id: u32, operands: &[u32]) -> u32 {
+        let mut words = vec![function_id];
+        words.extend_from_slice(operands);
+        self.insert_result(57, type_id, &words)
+    }
+
+    fn insert_op_select(&mut self, type_id: u32, condition: u32, object_1: u32, object_2: u32) -> u32 {
+        self.insert_result(169, type_id, &[condition, object_1, object_2])
+    }
+
+    fn insert_op_load(&mut self, type_id: u32, variable_pointer: u32) -> u32 {
+        self.insert_result(61, type_id, &[variable_pointer])
+    }
+
+    fn insert_op_composite_extract(&mut self, type_id: u32, composite: u32, index: u32) -> u32 {
+        self.insert_result(81, type_id, &[composite, index])
+    }
+
+    fn insert_op_u_convert(&mut self, type_id: u32, unsigned_value: u32) -> u32 {
+        self.insert_result(113, type_id, &[unsigned_value])
+    }
+
+    fn insert_op_s_convert(&mut self, type_id: u32, signed_value: u32) -> u32 {
+        self.insert_result(114, type_id, &[signed_value])
+    }
+
+    fn insert_op_bitcast(&mut self, type_id: u32, operand: u32) -> u32 {
+        self.insert_result(124, type_id, &[operand])
+    }
+
+    fn insert_op_s_negate(&mut self, type_id: u32, operand: u32) -> u32 {
+        self.insert_result(126, type_id, &[operand])
+    }
+
+    fn insert_op_sless_than(&mut self, type_id: u32, lhs_id: u32, rhs_id: u32) -> u32 {
+        self.insert_result(177, type_id, &[lhs_id, rhs_id])
+    }
+
+    fn insert_op_f_ord_equal(&mut self, type_id: u32, lhs_id: u32, rhs_id: u32) -> u32 {
+        self.insert_result(180, type_id, &[lhs_id, rhs_id])
+    }
+
+    fn insert_op_s_less_than_equal(&mut self, type_id: u32, lhs_id: u32, rhs_id: u32) -> u32 {
+        self.insert_result(179, type_id, &[lhs_id, rhs_id])
+    }
+
+    fn insert_op_s_greater_than_equal(&mut self, type_id: u32, lhs_id: u32, rhs_id: u32) -> u32 {
+        self.insert_result(175, type_id, &[lhs_id, rhs_id])
+    }
+
+    fn insert_op_i_equal(&mut self, type_id: u32, lhs_id: u32, rhs_id: u32) -> u32 {
+        self.insert_result(170, type_id, &[lhs_id, rhs_id])
+    }
+
+    fn insert_op_i_not_equal(&mut self, type_id: u32, lhs_id: u32, rhs_id: u32) -> u32 {
+        self.insert_result(171, type_id, &[lhs_id, rhs_id])
+    }
+
+    fn insert_op_u_less_than(&mut self, type_id: u32, lhs: u32, rhs: u32) -> u32 {
+        self.insert_result(176, type_id, &[lhs, rhs])
+    }
+
+    fn insert_op_u_greater_than_equal(&mut self, type_id: u32, lhs: u32, rhs: u32) -> u32 {
+        self.insert_result(174, type_id, &[lhs, rhs])
+    }
+
+    fn insert_op_u_greater_than(&mut self, type_id: u32, lhs: u32, rhs: u32) -> u32 {
+        self.insert_result(172, type_id, &[lhs, rhs])
+    }
+
+    fn insert_op_logical_or(&mut self, type_id: u32, lhs: u32, rhs: u32) -> u32 {
+        self.insert_result(166, type_id, &[lhs, rhs])
+    }
+
+    fn insert_op_logical_and(&mut self, type_id: u32, lhs: u32, rhs: u32) -> u32 {
+        self.insert_result(167, type_id, &[lhs, rhs])
+    }
+
+    fn insert_op_i_add(&mut self, type_id: u32, lhs: u32, rhs: u32) -> u32 {
+        self.insert_result(128, type_id, &[lhs, rhs])
+    }
+
+    fn insert_op_i_sub(&mut self, type_id: u32, lhs: u32, rhs: u32) -> u32 {
+        self.insert_result(130, type_id, &[lhs, rhs])
+    }
+
+    fn insert_op_i_mul(&mut self, type_id: u32, lhs: u32, rhs: u32) -> u32 {
+        self.insert_result(132, type_id, &[lhs, rhs])
+    }
+
+    fn insert_op_s_div(&mut self, type_id: u32, lhs: u32, rhs: u32) -> u32 {
+        self.insert_result(135, type_id, &[lhs, rhs])
+    }
+
+    fn insert_op_u_div(&mut self, type_id: u32, lhs: u32, rhs: u32) -> u32 {
+        self.insert_result(134, type_id, &[lhs, rhs])
+    }
+
+    fn insert_op_shift_right_logical(&mut self, type_id: u32, base: u32, shift: u32) -> u32 {
+        self.insert_result(194, type_id, &[base, shift])
+    }
+
+    fn insert_op_shift_right_arithmetic(&mut self, type_id: u32, base: u32, shift: u32) -> u32 {
+        self.insert_result(195, type_id, &[base, shift])
+    }
+
+    fn insert_op_shift_left_logical(&mut self, type_id: u32, base: u32, shift: u32) -> u32 {
+        self.insert_result(196, type_id, &[base, shift])
+    }
+
+    fn insert_op_bitwise_or(&mut self, type_id: u32, lhs: u32, rhs: u32) -> u32 {
+        self.insert_result(197, type_id, &[lhs, rhs])
+    }
+
+    fn insert_op_bitwise_xor(&mut self, type_id: u32, lhs: u32, rhs: u32) -> u32 {
+        self.insert_result(198, type_id, &[lhs, rhs])
+    }
+
+    fn insert_op_bitwise_and(&mut self, type_id: u32, lhs: u32, rhs: u32) -> u32 {
+        self.insert_result(199, type_id, &[lhs, rhs])
+    }
+
+    fn sf32_from_fraction_and_exp(&mut self) -> u32 {
+        let name = "sf32_from_fraction_And_exp".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let bool = self.get_op_type_bool();
+        let i32 = self.get_op_type_int(32, true);
+        let pi32 = self.get_pointer_type(i32, 7);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(i32, &[pi32, pi32]);
+        let id = self.insert_op_function(i32, 0x8, op_type_function);
+        let traw32 = self.insert_op_function_parameter(pi32);
+        let exp = self.insert_op_function_parameter(pi32);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        // if (exp < 0)
+        let loaded_exp = self.insert_op_load(i32, exp);
+        let temp = self.get_const_int(32, true, 0);
+        let cmp = self.insert_op_sless_than(bool, loaded_exp, temp);
+        let false_label = self.get_next_id();
+        self.insert_op_selection_merge(false_label, 0x0);
+        let true_label = self.get_next_id();
+        self.insert_op_branch_conditional(cmp, true_label, false_label);
+
+        // return 0;
+        self.insert_op_label(true_label);
+        self.insert_op_return_value(temp);
+
+        // exp = min(exp, 255);
+        self.insert_op_label(false_label);
+        let loaded_exp = self.insert_op_load(i32, exp);
+        let temp = self.get_const_int(32, true, 255);
+        let glsl_std_450 = self.get_ext_inst_import("GLSL.std.450");
+        let min = self.insert_op_ext_inst(i32, glsl_std_450, 39, &[loaded_exp, temp]);
+        self.insert_op_store(exp, min);
+
+        // return (traw32 << 8) | exp;
+        let loaded_traw32 = self.insert_op_load(i32, traw32);
+        let temp = self.get_const_int(32, true, 8);
+        let shift = self.insert_op_shift_left_logical(i32, loaded_traw32, temp);
+        let loaded_exp = self.insert_op_load(i32, exp);
+        let or = self.insert_op_bitwise_or(i32, shift, loaded_exp);
+        self.insert_op_return_value(or);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    fn sf32_from_float(&mut self) -> u32 {
+        let name = "sf32_from_float".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let sf32_from_fraction_and_exp = self.sf32_from_fraction_and_exp();
+
+        let bool = self.get_op_type_bool();
+        let f32 = self.get_op_type_float(32);
+        let pf32 = self.get_pointer_type(f32, 7);
+        let i32 = self.get_op_type_int(32, true);
+        let pi32 = self.get_pointer_type(i32, 7);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(i32, &[pf32]);
+        let id = self.insert_op_function(i32, 0x8, op_type_function);
+        let value = self.insert_op_function_parameter(pf32);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        let t754raw = self.insert_op_variable(pi32, 7);
+        let t_raction = self.insert_op_variable(pi32, 7);
+        let exponent = self.insert_op_variable(pi32, 7);
+
+        // if (value == 0)
+        let loaded_value = self.insert_op_load(f32, value);
+        let temp = self.get_const_f32(32, 0.0);
+        let cmp = self.insert_op_f_ord_equal(bool, loaded_value, temp);
+        let false_label = self.get_next_id();
+        self.insert_op_selection_merge(false_label, 0x0);
+        let true_label = self.get_next_id();
+        self.insert_op_branch_conditional(cmp, true_label, false_label);
+
+        // return 0;
+        self.insert_op_label(true_label);
+        let temp = self.get_const_int(32, true, 0);
+        self.insert_op_return_value(temp);
+
+        // int t754raw = floatBitsToInt(value);
+        self.insert_op_label(false_label);
+        let loaded_value = self.insert_op_load(f32, value);
+        let bitcast = self.insert_op_bitcast(i32, loaded_value);
+        self.insert_op_store(t754raw, bitcast);
+
+        // int tRaction = (t754raw & 0x007FFFFF) + 0x00800000;
+        let loaded_t754raw = self.insert_op_load(i32, t754raw);
+        let temp = self.get_const_int(32, true, 0x007FFFFF);
+        let and = self.insert_op_bitwise_and(i32, loaded_t754raw, temp);
+        let temp = self.get_const_int(32, true, 0x00800000);
+        let add = self.insert_op_i_add(i32, and, temp);
+        self.insert_op_store(t_raction, add);
+
+        // int exponent = (t754raw & 0x7FFFFFFF) >> 23;
+        let loaded_t754raw = self.insert_op_load(i32, t754raw);
+        let temp = self.get_const_int(32, true, 0x7FFFFFFF);
+        let and = self.insert_op_bitwise_and(i32, loaded_t754raw, temp);
+        let temp = self.get_const_int(32, true, 23);
+        let shift = self.insert_op_shift_right_arithmetic(i32, and, temp);
+        self.insert_op_store(exponent, shift);
+
+        // if (t754raw < 0)
+        let loaded_t754raw = self.insert_op_load(i32, t754raw);
+        let temp = self.get_const_int(32, true, 0);
+        let cmp = self.insert_op_sless_than(bool, loaded_t754raw, temp);
+        let false_label = self.get_next_id();
+        self.insert_op_selection_merge(false_label, 0x0);
+        let true_label = self.get_next_id();
+        self.insert_op_branch_conditional(cmp, true_label, false_label);
+
+        // tRaction = -tRaction;
+        self.insert_op_label(true_label);
+        let loaded_t_raction = self.insert_op_load(i32, t_raction);
+        let negate = self.insert_op_s_negate(i32, loaded_t_raction);
+        self.insert_op_store(t_raction, negate);
+        self.insert_op_branch(false_label);
+
+        // return FromFractionAndExp(tRaction >> 1, exponent - 22);
+        self.insert_op_label(false_label);
+        let loaded_t_raction = self.insert_op_load(i32, t_raction);
+        let temp = self.get_const_int(32, true, 1);
+        let shift = self.insert_op_shift_right_arithmetic(i32, loaded_t_raction, temp);
+        self.insert_op_store(t_raction, shift);
+
+        let loaded_exponent = self.insert_op_load(i32, exponent);
+        let temp = self.get_const_int(32, true, 22);
+        let sub = self.insert_op_i_sub(i32, loaded_exponent, temp);
+        self.insert_op_store(exponent, sub);
+
+        let result = self.insert_op_function_call(i32, sf32_from_fraction_and_exp, &[t_raction, exponent]);
+        self.insert_op_return_value(result);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    fn sf32_get_fraction(&mut self) -> u32 {
+        let name = "sf32_get_fraction".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let i32 = self.get_op_type_int(32, true);
+        let pi32 = self.get_pointer_type(i32, 7);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(i32, &[pi32]);
+        let id = self.insert_op_function(i32, 0x8, op_type_function);
+        let sf32 = self.insert_op_function_parameter(pi32);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        // return gfloat >> 8;
+        let loaded_sf32 = self.insert_op_load(i32, sf32);
+        let temp = self.get_const_int(32, true, 8);
+        let shift = self.insert_op_shift_right_arithmetic(i32, loaded_sf32, temp);
+        self.insert_op_return_value(shift);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    fn sf32_get_exponent(&mut self) -> u32 {
+        let name = "sf32_get_exponent".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let i32 = self.get_op_type_int(32, true);
+        let pi32 = self.get_pointer_type(i32, 7);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(i32, &[pi32]);
+        let id = self.insert_op_function(i32, 0x8, op_type_function);
+        let sf32 = self.insert_op_function_parameter(pi32);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        // return gfloat & 0xFF;
+        let loaded_sf32 = self.insert_op_load(i32, sf32);
+        let temp = self.get_const_int(32, true, 0xFF);
+        let and = self.insert_op_bitwise_and(i32, loaded_sf32, temp);
+        self.insert_op_return_value(and);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    fn sf32_to_float(&mut self) -> u32 {
+        let name = "sf32_to_float".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let sf32_get_exponent = self.sf32_get_exponent();
+        let sf32_get_fraction = self.sf32_get_fraction();
+        let ieee_f32_round_pack = self.ieee_f32_round_pack();
+
+        let bool = self.get_op_type_bool();
+        let i32 = self.get_op_type_int(32, true);
+        let pi32 = self.get_pointer_type(i32, 7);
+        let u32 = self.get_op_type_int(32, false);
+        let f32 = self.get_op_type_float(32);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(f32, &[pi32]);
+        let id = self.insert_op_function(f32, 0x8, op_type_function);
+        let sf32 = self.insert_op_function_parameter(pi32);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        // The float is assembled from integers only, pow(2, exponent) would depend on the driver's precision.
+        // int fraction = GetFraction(gfloat);
+        // if (fraction == 0) return 0.0;
+        let fraction = self.insert_op_function_call(i32, sf32_get_fraction, &[sf32]);
+        let temp = self.get_const_int(32, true, 0);
+        let cmp = self.insert_op_i_equal(bool, fraction, temp);
+        let temp = self.get_const_f32(32, 0.0);
+        self.insert_early_return(cmp, temp);
+
+        // int exponent = GetExponent(gfloat) - 127;
+        let call = self.insert_op_function_call(i32, sf32_get_exponent, &[sf32]);
+        let temp = self.get_const_int(32, true, 127);
+        let exponent = self.insert_op_i_sub(i32, call, temp);
+
+        // uint magnitude = uint(abs(fraction));
+        // int msb = findMSB(magnitude);
+        let glsl_std_450 = self.get_ext_inst_import("GLSL.std.450");
+        let abs = self.insert_op_ext_inst(i32, glsl_std_450, 5, &[fraction]);
+        let magnitude = self.insert_op_bitcast(u32, abs);
+        let msb = self.insert_op_ext_inst(i32, glsl_std_450, 75, &[magnitude]);
+
+        // fraction * 2^exponent, rounded to nearest even when it falls into the subnormal range.
+        // return uintBitsToFloat(RoundPack(uint(fraction < 0), exponent + msb + 126, magnitude << (30 - msb)));
+        let temp = self.get_const_int(32, true, 0);
+        let cmp = self.insert_op_sless_than(bool, fraction, temp);
+        let sign = self.insert_bool_to_uint(cmp);
+        let add = self.insert_op_i_add(i32, exponent, msb);
+        let temp = self.get_const_int(32, true, 126);
+        let exp = self.insert_op_i_add(i32, add, temp);
+        let temp = self.get_const_int(32, true, 30);
+        let sub = self.insert_op_i_sub(i32, temp, msb);
+        let sig = self.insert_op_shift_left_logical(u32, magnitude, sub);
+        let call = self.insert_op_function_call(u32, ieee_f32_round_pack, &[sign, exp, sig]);
+        let bitcast = self.insert_op_bitcast(f32, call);
+        self.insert_op_return_value(bitcast);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    fn find_s_msb_64(&mut self) -> u32 {
+        let name = "find_s_msb_64".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let bool = self.get_op_type_bool();
+        let i32 = self.get_op_type_int(32, true);
+        let pi32 = self.get_pointer_type(i32, 7);
+        let i64 = self.get_op_type_int(64, true);
+        let pi64 = self.get_pointer_type(i64, 7);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(i32, &[pi64]);
+        let id = self.insert_op_function(i32, 0x8, op_type_function);
+        let num = self.insert_op_function_parameter(pi64);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        let i = self.insert_op_variable(pi32, 7);
+
+        // for (int i = 63; i >= 0; i--) {
+        let temp = self.get_const_int(32, true, 63);
+        self.insert_op_store(i, temp);
+        let loop_start = self.get_next_id();
+        self.insert_op_branch(loop_start);
+        self.insert_op_label(loop_start);
+
+        let loop_end = self.get_next_id();
+        let continue_target = self.get_next_id();
+        self.insert_op_loop_merge(loop_end, continue_target, 0);
+
+        let body = self.get_next_id();
+        self.insert_op_branch(body);
+        self.insert_op_label(body);
+
+        let loaded_i = self.insert_op_load(i32, i);
+        let temp = self.get_const_int(32, true, 0);
+        let cmp = self.insert_op_s_greater_than_equal(bool, loaded_i, temp);
+        let true_label = self.get_next_id();
+        self.insert_op_branch_conditional(cmp, true_label, loop_end);
+
+        // if ((num & (int64_t(1) << int64_t(i))) != 0)
+        self.insert_op_label(true_label);
+        let loaded_num = self.insert_op_load(i64, num);
+        let loaded_i = self.insert_op_load(i32, i);
+        let convert = self.insert_op_s_convert(i64, loaded_i);
+        let temp = self.get_const_int(64, true, 1);
+        let shift = self.insert_op_shift_left_logical(i64, temp, convert);
+        let and = self.insert_op_bitwise_and(i64, loaded_num, shift);
+
+        let temp = self.get_const_int(64, true, 0);
+        let cmp = self.insert_op_i_not_equal(bool, and, temp);
+        let true_label = self.get_next_id();
+        let false_label = self.get_next_id();
+        self.insert_op_selection_merge(false_label, 0);
+        self.insert_op_branch_conditional(cmp, true_label, false_label);
+
+        // return i;
+        self.insert_op_label(true_label);
+        let loaded_i = self.insert_op_load(i32, i);
+        self.insert_op_return_value(loaded_i);
+
+        // Loop continuation
+        self.insert_op_label(false_label);
+        self.insert_op_branch(continue_target);
+        self.insert_op_label(continue_target);
+        let loaded_i = self.insert_op_load(i32, i);
+        let temp = self.get_const_int(32, true, 1);
+        let sub = self.insert_op_i_sub(i32, loaded_i, temp);
+        self.insert_op_store(i, sub);
+        self.insert_op_branch(loop_start);
+
+        // return 0;
+        self.insert_op_label(loop_end);
+        let temp = self.get_const_int(32, true, 0);
+        self.insert_op_return_value(temp);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    fn sf32_normalize_64(&mut self) -> u32 {
+        let name = "sf32_normalize_64".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let find_s_msb_64 = self.find_s_msb_64();
+        let sf32_from_fraction_and_exp = self.sf32_from_fraction_and_exp();
+
+        let bool = self.get_op_type_bool();
+        let i32 = self.get_op_type_int(32, true);
+        let pi32 = self.get_pointer_type(i32, 7);
+        let i64 = self.get_op_type_int(64, true);
+        let pi64 = self.get_pointer_type(i64, 7);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(i32, &[pi64, pi32]);
+        let id = self.insert_op_function(i32, 0x8, op_type_function);
+        let traw_value = self.insert_op_function_parameter(pi64);
+        let t_exponent = self.insert_op_function_parameter(pi32);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        let temp_i64 = self.insert_op_variable(pi64, 7);
+        let index = self.insert_op_variable(pi32, 7);
+        let param = self.insert_op_variable(pi32, 7);
+
+        // if (trawValue == 0)
+        let loaded_traw_value = self.insert_op_load(i64, traw_value);
+        let temp = self.get_const_int(64, true, 0);
+        let cmp = self.insert_op_i_equal(bool, loaded_traw_value, temp);
+        let false_label = self.get_next_id();
+        self.insert_op_selection_merge(false_label, 0);
+        let true_label = self.get_next_id();
+        self.insert_op_branch_conditional(cmp, true_label, false_label);
+
+        // return 0;
+        self.insert_op_label(true_label);
+        let temp = self.get_const_int(32, true, 0);
+        self.insert_op_return_value(temp);
+
+        // int index = GBitScanReverse64(abs(trawValue));
+        self.insert_op_label(false_label);
+        let loaded_traw_value = self.insert_op_load(i64, traw_value);
+        let glsl_std_450 = self.get_ext_inst_import("GLSL.std.450");
+        let abs = self.insert_op_ext_inst(i64, glsl_std_450, 5, &[loaded_traw_value]);
+        self.insert_op_store(temp_i64, abs);
+        let call = self.insert_op_function_call(i32, find_s_msb_64, &[temp_i64]);
+        self.insert_op_store(index, call);
+
+        // if (index <= 22) {
+        let loaded_index = self.insert_op_load(i32, index);
+        let temp = self.get_const_int(32, true, 22);
+        let cmp = self.insert_op_s_less_than_equal(bool, loaded_index, temp);
+        let selection_merge = self.get_next_id();
+        self.insert_op_selection_merge(selection_merge, 0);
+        let true_label = self.get_next_id();
+        let false_label = self.get_next_id();
+        self.insert_op_branch_conditional(cmp, true_label, false_label);
+
+        // int uDelta = 22 - index;
+        self.insert_op_label(true_label);
+        let loaded_index = self.insert_op_load(i32, index);
+        let temp = self.get_const_int(32, true, 22);
+        let sub = self.insert_op_i_sub(i32, temp, loaded_index);
+        self.insert_op_store(index, sub);
+
+        // return FromFractionAndExp(int(trawValue << uDelta), tExponent - uDelta);
+        let loaded_traw_value = self.insert_op_load(i64, traw_value);
+        let loaded_u_delta = self.insert_op_load(i32, index);
+        let shift = self.insert_op_shift_left_logical(i64, loaded_traw_value, loaded_u_delta);
+        let convert = self.insert_op_s_convert(i32, shift);
+        self.insert_op_store(param, convert);
+
+        let loaded_exponent = self.insert_op_load(i32, t_exponent);
+        let loaded_u_delta = self.insert_op_load(i32, index);
+        let sub = self.insert_op_i_sub(i32, loaded_exponent, loaded_u_delta);
+        self.insert_op_store(index, sub);
+
+        let call = self.insert_op_function_call(i32, sf32_from_fraction_and_exp, &[param, index]);
+        self.insert_op_return_value(call);
+
+        // Else:
+        // int uDelta = index - 22;
+        self.insert_op_label(false_label);
+        let loaded_index = self.insert_op_load(i32, index);
+        let temp = self.get_const_int(32, true, 22);
+        let sub = self.insert_op_i_sub(i32, loaded_index, temp);
+        self.insert_op_store(index, sub);
+
+        // return FromFractionAndExp(int(trawValue >> uDelta), tExponent + uDelta);
+        let loaded_traw_value = self.insert_op_load(i64, traw_value);
+        let loaded_u_delta = self.insert_op_load(i32, index);
+        let shift = self.insert_op_shift_right_arithmetic(i64, loaded_traw_value, loaded_u_delta);
+        let convert = self.insert_op_s_convert(i32, shift);
+        self.insert_op_store(param, convert);
+
+        let loaded_exponent = self.insert_op_load(i32, t_exponent);
+        let loaded_u_delta = self.insert_op_load(i32, index);
+        let sub = self.insert_op_i_add(i32, loaded_exponent, loaded_u_delta);
+        self.insert_op_store(index, sub);
+
+        let call = self.insert_op_function_call(i32, sf32_from_fraction_and_exp, &[param, index]);
+        self.insert_op_return_value(call);
+
+        // End:
+        self.insert_op_label(selection_merge);
+        let undef = self.insert_op_undef(i32);
+        self.insert_op_return_value(undef);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    fn sf32_div(&mut self) -> u32 {
+        let name = "sf32_div".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let sf32_get_fraction = self.sf32_get_fraction();
+        let sf32_get_exponent = self.sf32_get_exponent();
+        let sf32_normalize_64 = self.sf32_normalize_64();
+
+        let bool = self.get_op_type_bool();
+        let i32 = self.get_op_type_int(32, true);
+        let pi32 = self.get_pointer_type(i32, 7);
+        let i64 = self.get_op_type_int(64, true);
+        let pi64 = self.get_pointer_type(i64, 7);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(i32, &[pi32, pi32]);
+        let id = self.insert_op_function(i32, 0x8, op_type_function);
+        let lhs = self.insert_op_function_parameter(pi32);
+        let rhs = self.insert_op_function_parameter(pi32);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        let temp_i32 = self.insert_op_variable(pi32, 7);
+        let temp_i32_2 = self.insert_op_variable(pi32, 7);
+        let temp_i64 = self.insert_op_variable(pi64, 7);
+
+        // int nDivid = GetFraction(rhs);
+        let loaded_rhs = self.insert_op_load(i32, rhs);
+        self.insert_op_store(temp_i32, loaded_rhs);
+        let call = self.insert_op_function_call(i32, sf32_get_fraction, &[temp_i32]);
+        self.insert_op_store(temp_i32, call);
+
+        // if (nDivid == 0)
+        let loaded_n_divid = self.insert_op_load(i32, temp_i32);
+        let temp = self.get_const_int(32, true, 0);
+        let cmp = self.insert_op_i_equal(bool, loaded_n_divid, temp);
+        let false_label = self.get_next_id();
+        self.insert_op_selection_merge(false_label, 0);
+        let true_label = self.get_next_id();
+        self.insert_op_branch_conditional(cmp, true_label, false_label);
+
+        // return 0;
+        self.insert_op_label(true_label);
+        let temp = self.get_const_int(32, true, 0);
+        self.insert_op_return_value(temp);
+
+        // int64_t trawValue = (int64_t(GetFraction(lhs)) << 32) / nDivid;
+        self.insert_op_label(false_label);
+        let loaded_lhs = self.insert_op_load(i32, lhs);
+        self.insert_op_store(temp_i32_2, loaded_lhs);
+        let call = self.insert_op_function_call(i32, sf32_get_fraction, &[temp_i32_2]);
+        let convert = self.insert_op_s_convert(i64, call);
+        let temp = self.get_const_int(64, true, 32);
+        let shift = self.insert_op_shift_left_logical(i64, convert, temp);
+        let loaded_n_divid = self.insert_op_load(i32, temp_i32);
+        let convert = self.insert_op_s_convert(i64, loaded_n_divid);
+        let div = self.insert_op_s_div(i64, shift, convert);
+        self.insert_op_store(temp_i64, div);
+
+        // int tExponent = GetExponent(lhs) - GetExponent(rhs) + 95;
+        let loaded_lhs = self.insert_op_load(i32, lhs);
+        self.insert_op_store(temp_i32, loaded_lhs);
+        let call = self.insert_op_function_call(i32, sf32_get_exponent, &[temp_i32]);
+        let loaded_rhs = self.insert_op_load(i32, rhs);
+        self.insert_op_store(temp_i32, loaded_rhs);
+        let call2 = self.insert_op_function_call(i32, sf32_get_exponent, &[temp_i32]);
+        let sub = self.insert_op_i_sub(i32, call, call2);
+        let temp = self.get_const_int(32, true, 95);
+        let add = self.insert_op_i_add(i32, sub, temp);
+        self.insert_op_store(temp_i32, add);
+
+        // return Normalize64(trawValue, tExponent);
+        let call = self.insert_op_function_call(i32, sf32_normalize_64, &[temp_i64, temp_i32]);
+        self.insert_op_return_value(call);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    fn sf32_add(&mut self) -> u32 {
+        let name = "sf32_add".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let sf32_get_fraction = self.sf32_get_fraction();
+        let sf32_get_exponent = self.sf32_get_exponent();
+        let sf32_normalize_64 = self.sf32_normalize_64();
+
+        let bool = self.get_op_type_bool();
+        let i32 = self.get_op_type_int(32, true);
+        let pi32 = self.get_pointer_type(i32, 7);
+        let i64 = self.get_op_type_int(64, true);
+        let pi64 = self.get_pointer_type(i64, 7);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(i32, &[pi32, pi32]);
+        let id = self.insert_op_function(i32, 0x8, op_type_function);
+        let lhs = self.insert_op_function_parameter(pi32);
+        let rhs = self.insert_op_function_parameter(pi32);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        let temp_i32 = self.insert_op_variable(pi32, 7);
+        let lhs_exponent = self.insert_op_variable(pi32, 7);
+        let rhs_exponent = self.insert_op_variable(pi32, 7);
+        let lhs_raw = self.insert_op_variable(pi64, 7);
+        let rhs_raw = self.insert_op_variable(pi64, 7);
+
+        // int lhsExponent = GetExponent(lhs);
+        let loaded_lhs = self.insert_op_load(i32, lhs);
+        self.insert_op_store(temp_i32, loaded_lhs);
+        let call = self.insert_op_function_call(i32, sf32_get_exponent, &[temp_i32]);
+        self.insert_op_store(lhs_exponent, call);
+
+        // int rhsExponent = GetExponent(rhs);
+        let loaded_rhs = self.insert_op_load(i32, rhs);
+        self.insert_op_store(temp_i32, loaded_rhs);
+        let call = self.insert_op_function_call(i32, sf32_get_exponent, &[temp_i32]);
+        self.insert_op_store(rhs_exponent, call);
+
+        // int64_t lhsRaw = int64_t(GetFraction(lhs)) << 32;
+        let loaded_lhs = self.insert_op_load(i32, lhs);
+        self.insert_op_store(temp_i32, loaded_lhs);
+        let call = self.insert_op_function_call(i32, sf32_get_fraction, &[temp_i32]);
+        let convert = self.insert_op_s_convert(i64, call);
+        let temp = self.get_const_int(64, true, 32);
+        let shift = self.insert_op_shift_left_logical(i64, convert, temp);
+        self.insert_op_store(lhs_raw, shift);
+
+        // int64_t rhsRaw = int64_t(GetFraction(rhs)) << 32;
+        let loaded_rhs = self.insert_op_load(i32, rhs);
+        self.insert_op_store(temp_i32, loaded_rhs);
+        let call = self.insert_op_function_call(i32, sf32_get_fraction, &[temp_i32]);
+        let convert = self.insert_op_s_convert(i64, call);
+        let temp = self.get_const_int(64, true, 32);
+        let shift = self.insert_op_shift_left_logical(i64, convert, temp);
+        self.insert_op_store(rhs_raw, shift);
+
+        // if (lhsExponent < rhsExponent) {
+        let loaded_lhs_exponent = self.insert_op_load(i32, lhs_exponent);
+        let loaded_rhs_exponent = self.insert_op_load(i32, rhs_exponent);
+        let cmp = self.insert_op_sless_than(bool, loaded_lhs_exponent, loaded_rhs_exponent);
+        let false_label = self.get_next_id();
+        self.insert_op_selection_merge(false_label, 0);
+        let true_label = self.get_next_id();
+        self.insert_op_branch_conditional(cmp, true_label, false_label);
+
+        // lhsRaw = lhsRaw >> min(rhsExponent - lhsExponent, 63);
+        self.insert_op_label(true_label);
+        let loaded_lhs_exponent = self.insert_op_load(i32, lhs_exponent);
+        let loaded_rhs_exponent = self.insert_op_load(i32, rhs_exponent);
+        let sub = self.insert_op_i_sub(i32, loaded_rhs_exponent, loaded_lhs_exponent);
+        let temp = self.get_const_int(32, true, 63);
+        let glsl_std_450 = self.get_ext_inst_import("GLSL.std.450");
+        let min = self.insert_op_ext_inst(i32, glsl_std_450, 39, &[sub, temp]);
+        let loaded_lhs_raw = self.insert_op_load(i64, lhs_raw);
+        let shift = self.insert_op_shift_right_arithmetic(i64, loaded_lhs_raw, min);
+        self.insert_op_store(lhs_raw, shift);
+
+        // return Normalize64(lhsRaw + rhsRaw, rhsExponent - 32);
+        let loaded_lhs_raw = self.insert_op_load(i64, lhs_raw);
+        let loaded_rhs_raw = self.insert_op_load(i64, rhs_raw);
+        let add = self.insert_op_i_add(i64, loaded_lhs_raw, loaded_rhs_raw);
+        self.insert_op_store(lhs_raw, add);
+
+        let loaded_rhs_exponent = self.insert_op_load(i32, rhs_exponent);
+        let temp = self.get_const_int(32, true, 32);
+        let sub = self.insert_op_i_sub(i32, loaded_rhs_exponent, temp);
+        self.insert_op_store(temp_i32, sub);
+
+        let call = self.insert_op_function_call(i32, sf32_normalize_64, &[lhs_raw, temp_i32]);
+        self.insert_op_return_value(call);
+
+        // rhsRaw = rhsRaw >> min(lhsExponent - rhsExponent, 63);
+        self.insert_op_label(false_label);
+        let loaded_lhs_exponent = self.insert_op_load(i32, lhs_exponent);
+        let loaded_rhs_exponent = self.insert_op_load(i32, rhs_exponent);
+        let sub = self.insert_op_i_sub(i32, loaded_lhs_exponent, loaded_rhs_exponent);
+        let temp = self.get_const_int(32, true, 63);
+        let min = self.insert_op_ext_inst(i32, glsl_std_450, 39, &[sub, temp]);
+        let loaded_rhs_raw = self.insert_op_load(i64, rhs_raw);
+        let shift = self.insert_op_shift_right_arithmetic(i64, loaded_rhs_raw, min);
+        self.insert_op_store(rhs_raw, shift);
+
+        // return Normalize64(lhsRaw + rhsRaw, lhsExponent - 32);
+        let loaded_lhs_raw = self.insert_op_load(i64, lhs_raw);
+        let loaded_rhs_raw = self.insert_op_load(i64, rhs_raw);
+        let add = self.insert_op_i_add(i64, loaded_lhs_raw, loaded_rhs_raw);
+        self.insert_op_store(lhs_raw, add);
+
+        let loaded_lhs_exponent = self.insert_op_load(i32, lhs_exponent);
+        let temp = self.get_const_int(32, true, 32);
+        let sub = self.insert_op_i_sub(i32, loaded_lhs_exponent, temp);
+        self.insert_op_store(temp_i32, sub);
+
+        let call = self.insert_op_function_call(i32, sf32_normalize_64, &[lhs_raw, temp_i32]);
+        self.insert_op_return_value(call);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    fn sf32_sub(&mut self) -> u32 {
+        let name = "sf32_sub".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let sf32_get_fraction = self.sf32_get_fraction();
+        let sf32_get_exponent = self.sf32_get_exponent();
+        let sf32_from_fraction_and_exp = self.sf32_from_fraction_and_exp();
+        let sf32_add = self.sf32_add();
+
+        let i32 = self.get_op_type_int(32, true);
+        let pi32 = self.get_pointer_type(i32, 7);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(i32, &[pi32, pi32]);
+        let id = self.insert_op_function(i32, 0x8, op_type_function);
+        let lhs = self.insert_op_function_parameter(pi32);
+        let rhs = self.insert_op_function_parameter(pi32);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        let temp_i32 = self.insert_op_variable(pi32, 7);
+        let fraction = self.insert_op_variable(pi32, 7);
+        let exponent = self.insert_op_variable(pi32, 7);
+
+        // return GAdd(lhs, FromFractionAndExp(-GetFraction(rhs), GetExponent(rhs)));
+        let loaded_rhs = self.insert_op_load(i32, rhs);
+        self.insert_op_store(temp_i32, loaded_rhs);
+        let call = self.insert_op_function_call(i32, sf32_get_fraction, &[temp_i32]);
+        let negate = self.insert_op_s_negate(i32, call);
+        self.insert_op_store(fraction, negate);
+
+        let call = self.insert_op_function_call(i32, sf32_get_exponent, &[temp_i32]);
+        self.insert_op_store(exponent, call);
+
+        let call = self.insert_op_function_call(i32, sf32_from_fraction_and_exp, &[fraction, exponent]);
+        self.insert_op_store(temp_i32, call);
+
+        let call = self.insert_op_function_call(i32, sf32_add, &[lhs, temp_i32]);
+        self.insert_op_return_value(call);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    fn sf32_mul(&mut self) -> u32 {
+        let name = "sf32_mul".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let sf32_get_fraction = self.sf32_get_fraction();
+        let sf32_get_exponent = self.sf32_get_exponent();
+        let sf32_normalize_64 = self.sf32_normalize_64();
+
+        let i32 = self.get_op_type_int(32, true);
+        let pi32 = self.get_pointer_type(i32, 7);
+        let i64 = self.get_op_type_int(64, true);
+        let pi64 = self.get_pointer_type(i64, 7);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(i32, &[pi32, pi32]);
+        let id = self.insert_op_function(i32, 0x8, op_type_function);
+        let lhs = self.insert_op_function_parameter(pi32);
+        let rhs = self.insert_op_function_parameter(pi32);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        let temp_i32 = self.insert_op_variable(pi32, 7);
+        let temp_i64 = self.insert_op_variable(pi64, 7);
+
+        // int64_t trawValue = int64_t(GetFraction(lhs)) * int64_t(GetFraction(rhs));
+        let loaded_lhs = self.insert_op_load(i32, lhs);
+        self.insert_op_store(temp_i32, loaded_lhs);
+        let call = self.insert_op_function_call(i32, sf32_get_fraction, &[temp_i32]);
+        let convert = self.insert_op_s_convert(i64, call);
+        let loaded_rhs = self.insert_op_load(i32, rhs);
+        self.insert_op_store(temp_i32, loaded_rhs);
+        let call = self.insert_op_function_call(i32, sf32_get_fraction, &[temp_i32]);
+        let convert2 = self.insert_op_s_convert(i64, call);
+        let mul = self.insert_op_i_mul(i64, convert, convert2);
+        self.insert_op_store(temp_i64, mul);
+
+        // int tExponent = GetExponent(lhs) + GetExponent(rhs) - 127;
+        let loaded_lhs = self.insert_op_load(i32, lhs);
+        self.insert_op_store(temp_i32, loaded_lhs);
+        let call = self.insert_op_function_call(i32, sf32_get_exponent, &[temp_i32]);
+        let loaded_rhs = self.insert_op_load(i32, rhs);
+        self.insert_op_store(temp_i32, loaded_rhs);
+        let call2 = self.insert_op_function_call(i32, sf32_get_exponent, &[temp_i32]);
+        let add = self.insert_op_i_add(i32, call, call2);
+        let temp = self.get_const_int(32, true, 127);
+        let sub = self.insert_op_i_sub(i32, add, temp);
+        self.insert_op_store(temp_i32, sub);
+
+        // return Normalize64(trawValue, tExponent);
+        let call = self.insert_op_function_call(i32, sf32_normalize_64, &[temp_i64, temp_i32]);
+        self.insert_op_return_value(call);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    // Only f32 has an sf32 counterpart, f16 and f64 always use IEEE-754.
+    fn conformant_add(&mut self, width: u32) -> u32 {
+        match width {
+            16 => self.ieee_f16_add(),
+            64 => self.ieee_f64_add(),
+            _ => self.f32_conformant_add(),
+        }
+    }
+
+    fn conformant_sub(&mut self, width: u32) -> u32 {
+        match width {
+            16 => self.ieee_f16_sub(),
+            64 => self.ieee_f64_sub(),
+            _ => self.f32_conformant_sub(),
+        }
+    }
+
+    fn conformant_mul(&mut self, width: u32) -> u32 {
+        match width {
+            16 => self.ieee_f16_mul(),
+            64 => self.ieee_f64_mul(),
+            _ => self.f32_conformant_mul(),
+        }
+    }
+
+    fn conformant_div(&mut self, width: u32) -> u32 {
+        match width {
+            16 => self.ieee_f16_div(),
+            64 => self.ieee_f64_div(),
+            _ => self.f32_conformant_div(),
+        }
+    }
+
+    fn f32_conformant_add(&mut self) -> u32 {
+        match self.mode {
+            Mode::Sf32 => {
+                let sf32_add = self.sf32_add();
+                let ieee_f32_add = self.ieee_f32_add();
+                self.f32_conformant_binary("f32_conformant_add", sf32_add, ieee_f32_add)
+            },
+            Mode::Ieee => {
+                let ieee_f32_add = self.ieee_f32_add();
+                self.f32_ieee_binary("f32_ieee_add", ieee_f32_add)
+            },
+        }
+    }
+
+    fn f32_conformant_sub(&mut self) -> u32 {
+        match self.mode {
+            Mode::Sf32 => {
+                let sf32_sub = self.sf32_sub();
+                let ieee_f32_sub = self.ieee_f32_sub();
+                self.f32_conformant_binary("f32_conformant_sub", sf32_sub, ieee_f32_sub)
+            },
+            Mode::Ieee => {
+                let ieee_f32_sub = self.ieee_f32_sub();
+                self.f32_ieee_binary("f32_ieee_sub", ieee_f32_sub)
+            },
+        }
+    }
+
+    fn f32_conformant_mul(&mut self) -> u32 {
+        match self.mode {
+            Mode::Sf32 => {
+                let sf32_mul = self.sf32_mul();
+                let ieee_f32_mul = self.ieee_f32_mul();
+                self.f32_conformant_binary("f32_conformant_mul", sf32_mul, ieee_f32_mul)
+            },
+            Mode::Ieee => {
+                let ieee_f32_mul = self.ieee_f32_mul();
+                self.f32_ieee_binary("f32_ieee_mul", ieee_f32_mul)
+            },
+        }
+    }
+
+    fn f32_conformant_div(&mut self) -> u32 {
+        match self.mode {
+            Mode::Sf32 => {
+                let sf32_div = self.sf32_div();
+                let ieee_f32_div = self.ieee_f32_div();
+                self.f32_conformant_binary("f32_conformant_div", sf32_div, ieee_f32_div)
+            },
+            Mode::Ieee => {
+                let ieee_f32_div = self.ieee_f32_div();
+                self.f32_ieee_binary("f32_ieee_div", ieee_f32_div)
+            },
+        }
+    }
+
+    fn f32_conformant_binary(&mut self, name: &str, sf32_operation: u32, ieee_operation: u32) -> u32 {
+        if let Some(id) = self.created.get(name) {
+            return *id;
+        }
+
+        let sf32_from_float = self.sf32_from_float();
+        let sf32_to_float = self.sf32_to_float();
+
+        let bool = self.get_op_type_bool();
+        let i32 = self.get_op_type_int(32, true);
+        let pi32 = self.get_pointer_type(i32, 7);
+        let u32 = self.get_op_type_int(32, false);
+        let f32 = self.get_op_type_float(32);
+        let pf32 = self.get_pointer_type(f32, 7);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(f32, &[pf32, pf32]);
+        let id = self.insert_op_function(f32, 0x8, op_type_function);
+        let lhs = self.insert_op_function_parameter(pf32);
+        let rhs = self.insert_op_function_parameter(pf32);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        let temp_f32 = self.insert_op_variable(pf32, 7);
+        let temp_i32 = self.insert_op_variable(pi32, 7);
+        let temp_i32_2 = self.insert_op_variable(pi32, 7);
+
+        // Special values can not be represented by sf32, so they are handled by the IEEE-754 implementation.
+        // if (IsSpecial(lhs) || IsSpecial(rhs)) return uintBitsToFloat(IeeeOperation(floatBitsToUint(lhs), floatBitsToUint(rhs)));
+        let loaded_lhs = self.insert_op_load(f32, lhs);
+        let bitcast_lhs = self.insert_op_bitcast(u32, loaded_lhs);
+        let loaded_rhs = self.insert_op_load(f32, rhs);
+        let bitcast_rhs = self.insert_op_bitcast(u32, loaded_rhs);
+        let is_special_lhs = self.insert_ieee_f32_is_special(bitcast_lhs);
+        let is_special_rhs = self.insert_ieee_f32_is_special(bitcast_rhs);
+        let cmp = self.insert_op_logical_or(bool, is_special_lhs, is_special_rhs);
+        self.insert_early_return_with(cmp, |s| {
+            let call = s.insert_op_function_call(u32, ieee_operation, &[bitcast_lhs, bitcast_rhs]);
+            s.insert_op_bitcast(f32, call)
+        });
+
+        // return ToFloat(Operation(FromFloat(lhs), FromFloat(rhs)));
+        let loaded_lhs = self.insert_op_load(f32, lhs);
+        self.insert_op_store(temp_f32, loaded_lhs);
+        let call = self.insert_op_function_call(i32, sf32_from_float, &[temp_f32]);
+        self.insert_op_store(temp_i32, call);
+
+        let loaded_rhs = self.insert_op_load(f32, rhs);
+        self.insert_op_store(temp_f32, loaded_rhs);
+        let call = self.insert_op_function_call(i32, sf32_from_float, &[temp_f32]);
+        self.insert_op_store(temp_i32_2, call);
+
+        let call = self.insert_op_function_call(i32, sf32_operation, &[temp_i32, temp_i32_2]);
+        self.insert_op_store(temp_i32, call);
+
+        let call = self.insert_op_function_call(f32, sf32_to_float, &[temp_i32]);
+        self.insert_op_return_value(call);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name.to_owned(), id);
+        id
+    }
+}
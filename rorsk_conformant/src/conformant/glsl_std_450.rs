@@ -0,0 +1,1161 @@
+use std::f32::consts::{FRAC_PI_4, LN_2, LOG2_E};
+
+use super::Buffer;
+
+// Taylor series of 2^x and e^x, highest degree first.
+const EXP2_COEFFICIENTS: [f32; 8] = [
+    1.5252734e-5, 0.0001540353, 0.0013333558, 0.009618129, 0.05550411, 0.2402265, LN_2, 1.0
+];
+const EXP_COEFFICIENTS: [f32; 8] = [0.0001984127, 0.0013888889, 0.008333334, 0.041666668, 0.16666667, 0.5, 1.0, 1.0];
+
+// ln(m) = 2 * atanh(s) = s * (2 + 2/3 * s^2 + 2/5 * s^4 + ...), for log2 multiplied by log2(e).
+const LOG_COEFFICIENTS: [f32; 5] = [0.22222222, 0.2857143, 0.4, 0.6666667, 2.0];
+const LOG2_COEFFICIENTS: [f32; 5] = [0.3205989, 0.41219857, 0.57707804, 0.9617967, 2.88539];
+// atanh(s) = s + s^3/3 + s^3 * s^2 * (1/5 + 1/7 * s^2 + ...), the tail of the series used by pow.
+const ATANH_TAIL_COEFFICIENTS: [f32; 5] = [0.07692308, 0.09090909, 0.11111111, 0.14285715, 0.2];
+
+// ln(2) split in parts, where the leading part has enough trailing zeros to be multiplied exactly.
+const LN_2_HI: f32 = 0.6933594;
+const LN_2_LO: f32 = -0.00021219444;
+
+// 1/3 and 2/ln(2) as the sum of two floats.
+const FRAC_1_3_HI: f32 = 0.33333334;
+const FRAC_1_3_LO: f32 = -9.934108e-9;
+const FRAC_2_LN_2_HI: f32 = 2.88539;
+const FRAC_2_LN_2_LO: f32 = 3.851926e-8;
+
+// The first 224 bits of 2/pi after a zero word, enough to reduce the largest f32 modulo pi/2.
+const FRAC_2_PI_WORDS: [u32; 8] = [
+    0, 0xA2F9836E, 0x4E441529, 0xFC2757D1, 0xF534DDC0, 0xDB629599, 0x3C439041, 0xFE5163AB
+];
+// pi/2 * 2^31, rounded down.
+const FRAC_PI_2_FIXED: u64 = 0xC90FDAA2;
+
+// Minimax polynomials of sin(r) and cos(r) for r in [-pi/4, pi/4], from Cephes.
+const SIN_COEFFICIENTS: [f32; 3] = [-0.00019515296, 0.008332161, -0.16666655];
+const COS_COEFFICIENTS: [f32; 3] = [2.4433157e-5, -0.0013887316, 0.041666646];
+
+// 1.5 * 2^23, adding it rounds a float to an integer which is then held in the low bits of the significand.
+const ROUND_TO_INT: f32 = 12582912.0;
+
+impl Buffer {
+    fn get_const_ieee_f32(&mut self, value: f32) -> u32 {
+        self.get_const_int(32, false, value.to_bits())
+    }
+
+    fn insert_ieee_f32_polynomial(&mut self, x: u32, coefficients: &[f32]) -> u32 {
+        let ieee_f32_add = self.ieee_f32_add();
+        let ieee_f32_mul = self.ieee_f32_mul();
+
+        let u32 = self.get_op_type_int(32, false);
+
+        // uint p = c[0];
+        // p = Add(Mul(p, x), c[i]);
+        let mut p = self.get_const_ieee_f32(coefficients[0]);
+        for coefficient in &coefficients[1..] {
+            let mul = self.insert_op_function_call(u32, ieee_f32_mul, &[p, x]);
+            let temp = self.get_const_ieee_f32(*coefficient);
+            p = self.insert_op_function_call(u32, ieee_f32_add, &[mul, temp]);
+        }
+
+        p
+    }
+
+    fn insert_ieee_f32_round_to_int(&mut self, value: u32) -> (u32, u32) {
+        let ieee_f32_add = self.ieee_f32_add();
+        let ieee_f32_sub = self.ieee_f32_sub();
+
+        let u32 = self.get_op_type_int(32, false);
+        let i32 = self.get_op_type_int(32, true);
+
+        // uint t = Add(value, 1.5 * 2^23);
+        let magic = self.get_const_ieee_f32(ROUND_TO_INT);
+        let t = self.insert_op_function_call(u32, ieee_f32_add, &[value, magic]);
+
+        // int k = int(t & 0x7FFFFF) - 0x400000;
+        let temp = self.get_const_int(32, false, 0x7FFFFF);
+        let and = self.insert_op_bitwise_and(u32, t, temp);
+        let bitcast = self.insert_op_bitcast(i32, and);
+        let temp = self.get_const_int(32, true, 0x400000);
+        let k = self.insert_op_i_sub(i32, bitcast, temp);
+
+        // uint kf = Sub(t, 1.5 * 2^23);
+        let kf = self.insert_op_function_call(u32, ieee_f32_sub, &[t, magic]);
+
+        (k, kf)
+    }
+
+    fn insert_ieee_f32_scale(&mut self, value: u32, k: u32) -> u32 {
+        let ieee_f32_mul = self.ieee_f32_mul();
+
+        let u32 = self.get_op_type_int(32, false);
+        let i32 = self.get_op_type_int(32, true);
+
+        // 2^k is split in two factors which stay in the normal range, so only the last multiplication rounds.
+        // int k1 = k >> 1;
+        let one = self.get_const_int(32, true, 1);
+        let k1 = self.insert_op_shift_right_arithmetic(i32, k, one);
+        let k2 = self.insert_op_i_sub(i32, k, k1);
+
+        // return Mul(Mul(value, uint(k1 + 127) << 23), uint(k - k1 + 127) << 23);
+        let bias = self.get_const_int(32, true, 127);
+        let shift = self.get_const_int(32, false, 23);
+        let add = self.insert_op_i_add(i32, k1, bias);
+        let bitcast = self.insert_op_bitcast(u32, add);
+        let factor = self.insert_op_shift_left_logical(u32, bitcast, shift);
+        let mul = self.insert_op_function_call(u32, ieee_f32_mul, &[value, factor]);
+        let add = self.insert_op_i_add(i32, k2, bias);
+        let bitcast = self.insert_op_bitcast(u32, add);
+        let factor = self.insert_op_shift_left_logical(u32, bitcast, shift);
+        self.insert_op_function_call(u32, ieee_f32_mul, &[mul, factor])
+    }
+
+    fn insert_ieee_f32_from_int(&mut self, value: u32) -> u32 {
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+        let i32 = self.get_op_type_int(32, true);
+
+        // Exact for |value| < 2^24.
+        // uint magnitude = uint(abs(value));
+        // int msb = findMSB(magnitude);
+        let glsl_std_450 = self.get_ext_inst_import("GLSL.std.450");
+        let abs = self.insert_op_ext_inst(i32, glsl_std_450, 5, &[value]);
+        let magnitude = self.insert_op_bitcast(u32, abs);
+        let msb = self.insert_op_ext_inst(i32, glsl_std_450, 75, &[magnitude]);
+
+        // uint bits = (uint(value) & 0x80000000) | (uint(msb + 127) << 23) | ((magnitude << (23 - msb)) & 0x7FFFFF);
+        let bitcast = self.insert_op_bitcast(u32, value);
+        let temp = self.get_const_int(32, false, 0x80000000);
+        let sign = self.insert_op_bitwise_and(u32, bitcast, temp);
+        let temp = self.get_const_int(32, true, 127);
+        let add = self.insert_op_i_add(i32, msb, temp);
+        let bitcast = self.insert_op_bitcast(u32, add);
+        let temp = self.get_const_int(32, false, 23);
+        let exp = self.insert_op_shift_left_logical(u32, bitcast, temp);
+        let temp = self.get_const_int(32, true, 23);
+        let sub = self.insert_op_i_sub(i32, temp, msb);
+        let shift = self.insert_op_shift_left_logical(u32, magnitude, sub);
+        let temp = self.get_const_int(32, false, 0x7FFFFF);
+        let fraction = self.insert_op_bitwise_and(u32, shift, temp);
+        let or = self.insert_op_bitwise_or(u32, sign, exp);
+        let bits = self.insert_op_bitwise_or(u32, or, fraction);
+
+        // return value == 0 ? 0 : bits;
+        let zero = self.get_const_int(32, true, 0);
+        let cmp = self.insert_op_i_equal(bool, value, zero);
+        let zero = self.get_const_int(32, false, 0);
+        self.insert_op_select(u32, cmp, zero, bits)
+    }
+
+    fn insert_ieee_f32_unary_function(&mut self) -> (u32, u32) {
+        let u32 = self.get_op_type_int(32, false);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(u32, &[u32]);
+        let id = self.insert_op_function(u32, 0x8, op_type_function);
+        let a = self.insert_op_function_parameter(u32);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        (id, a)
+    }
+
+    pub(super) fn ieee_f32_sqrt(&mut self) -> u32 {
+        let name = "ieee_f32_sqrt".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let ieee_f32_round_pack = self.ieee_f32_round_pack();
+
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+        let i32 = self.get_op_type_int(32, true);
+        let u64 = self.get_op_type_int(64, false);
+        let pu64 = self.get_pointer_type(u64, 7);
+
+        let (id, a) = self.insert_ieee_f32_unary_function();
+
+        let rem = self.insert_op_variable(pu64, 7);
+        let res = self.insert_op_variable(pu64, 7);
+        let bit = self.insert_op_variable(pu64, 7);
+
+        // if (isnan(a)) return a | 0x400000;
+        let is_nan = self.insert_ieee_f32_is_nan(a);
+        let temp = self.get_const_int(32, false, 0x400000);
+        let or = self.insert_op_bitwise_or(u32, a, temp);
+        self.insert_early_return(is_nan, or);
+
+        // if (a == 0) return a;
+        let is_zero = self.insert_ieee_f32_is_zero(a);
+        self.insert_early_return(is_zero, a);
+
+        // if ((a >> 31) != 0) return 0x7FC00000;
+        let temp = self.get_const_int(32, false, 0x80000000);
+        let cmp = self.insert_op_u_less_than(bool, a, temp);
+        let true_label = self.get_next_id();
+        let false_label = self.get_next_id();
+        self.insert_op_selection_merge(true_label, 0);
+        self.insert_op_branch_conditional(cmp, true_label, false_label);
+        self.insert_op_label(false_label);
+        let temp = self.get_const_int(32, false, 0x7FC00000);
+        self.insert_op_return_value(temp);
+        self.insert_op_label(true_label);
+
+        // if (a == 0x7F800000) return a;
+        let is_inf = self.insert_ieee_f32_is_inf(a);
+        self.insert_early_return(is_inf, a);
+
+        // The exponent is made even, so it can be halved, and the significand is shifted far enough to give
+        // 29 result bits.
+        // int e = exp - 127;
+        // uint odd = uint(e) & 1;
+        let (exp, sig) = self.insert_ieee_f32_unpack(a);
+        let temp = self.get_const_int(32, true, 127);
+        let e = self.insert_op_i_sub(i32, exp, temp);
+        let bitcast = self.insert_op_bitcast(u32, e);
+        let temp = self.get_const_int(32, false, 1);
+        let odd = self.insert_op_bitwise_and(u32, bitcast, temp);
+
+        // rem = uint64_t(sig) << (33 + odd);
+        // res = 0;
+        // bit = uint64_t(1) << 56;
+        let convert = self.insert_op_u_convert(u64, sig);
+        let temp = self.get_const_int(32, false, 33);
+        let add = self.insert_op_i_add(u32, odd, temp);
+        let shift = self.insert_op_shift_left_logical(u64, convert, add);
+        self.insert_op_store(rem, shift);
+        let temp = self.get_const_int(64, false, 0);
+        self.insert_op_store(res, temp);
+        let temp = self.get_const_int(64, false, 1);
+        let temp2 = self.get_const_int(32, false, 56);
+        let shift = self.insert_op_shift_left_logical(u64, temp, temp2);
+        self.insert_op_store(bit, shift);
+
+        // for (; bit != 0; bit >>= 2) {
+        let loop_start = self.get_next_id();
+        self.insert_op_branch(loop_start);
+        self.insert_op_label(loop_start);
+
+        let loop_end = self.get_next_id();
+        let continue_target = self.get_next_id();
+        self.insert_op_loop_merge(loop_end, continue_target, 0);
+
+        let body = self.get_next_id();
+        self.insert_op_branch(body);
+        self.insert_op_label(body);
+
+        let loaded_bit = self.insert_op_load(u64, bit);
+        let temp = self.get_const_int(64, false, 0);
+        let cmp = self.insert_op_i_not_equal(bool, loaded_bit, temp);
+        let true_label = self.get_next_id();
+        self.insert_op_branch_conditional(cmp, true_label, loop_end);
+
+        // uint64_t sum = res + bit;
+        // bool lower = rem < sum;
+        self.insert_op_label(true_label);
+        let loaded_rem = self.insert_op_load(u64, rem);
+        let loaded_res = self.insert_op_load(u64, res);
+        let sum = self.insert_op_i_add(u64, loaded_res, loaded_bit);
+        let lower = self.insert_op_u_less_than(bool, loaded_rem, sum);
+
+        // rem = lower ? rem : rem - sum;
+        let sub = self.insert_op_i_sub(u64, loaded_rem, sum);
+        let select = self.insert_op_select(u64, lower, loaded_rem, sub);
+        self.insert_op_store(rem, select);
+
+        // res = lower ? res >> 1 : (res >> 1) + bit;
+        let temp = self.get_const_int(32, false, 1);
+        let shift = self.insert_op_shift_right_logical(u64, loaded_res, temp);
+        let add = self.insert_op_i_add(u64, shift, loaded_bit);
+        let select = self.insert_op_select(u64, lower, shift, add);
+        self.insert_op_store(res, select);
+
+        // Loop continuation
+        self.insert_op_branch(continue_target);
+        self.insert_op_label(continue_target);
+        let loaded_bit = self.insert_op_load(u64, bit);
+        let temp = self.get_const_int(32, false, 2);
+        let shift = self.insert_op_shift_right_logical(u64, loaded_bit, temp);
+        self.insert_op_store(bit, shift);
+        self.insert_op_branch(loop_start);
+
+        // uint sig = (uint(res) << 2) | uint(rem != 0);
+        self.insert_op_label(loop_end);
+        let loaded_res = self.insert_op_load(u64, res);
+        let convert = self.insert_op_u_convert(u32, loaded_res);
+        let temp = self.get_const_int(32, false, 2);
+        let shift = self.insert_op_shift_left_logical(u32, convert, temp);
+        let loaded_rem = self.insert_op_load(u64, rem);
+        let temp = self.get_const_int(64, false, 0);
+        let cmp = self.insert_op_i_not_equal(bool, loaded_rem, temp);
+        let sticky = self.insert_bool_to_uint(cmp);
+        let sig = self.insert_op_bitwise_or(u32, shift, sticky);
+
+        // return RoundPack(0, ((e - int(odd)) >> 1) + 126, sig);
+        let bitcast = self.insert_op_bitcast(i32, odd);
+        let sub = self.insert_op_i_sub(i32, e, bitcast);
+        let temp = self.get_const_int(32, true, 1);
+        let shift = self.insert_op_shift_right_arithmetic(i32, sub, temp);
+        let temp = self.get_const_int(32, true, 126);
+        let exp = self.insert_op_i_add(i32, shift, temp);
+        let temp = self.get_const_int(32, false, 0);
+        let call = self.insert_op_function_call(u32, ieee_f32_round_pack, &[temp, exp, sig]);
+        self.insert_op_return_value(call);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    pub(super) fn ieee_f32_inverse_sqrt(&mut self) -> u32 {
+        let name = "ieee_f32_inverse_sqrt".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let ieee_f32_sqrt = self.ieee_f32_sqrt();
+        let ieee_f32_div = self.ieee_f32_div();
+
+        let u32 = self.get_op_type_int(32, false);
+
+        let (id, a) = self.insert_ieee_f32_unary_function();
+
+        // return Div(1.0, Sqrt(a));
+        let call = self.insert_op_function_call(u32, ieee_f32_sqrt, &[a]);
+        let temp = self.get_const_ieee_f32(1.0);
+        let call = self.insert_op_function_call(u32, ieee_f32_div, &[temp, call]);
+        self.insert_op_return_value(call);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    pub(super) fn ieee_f32_fma(&mut self) -> u32 {
+        let name = "ieee_f32_fma".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let ieee_f32_propagate_nan = self.ieee_f32_propagate_nan();
+        let ieee_f32_round_pack = self.ieee_f32_round_pack();
+        let ieee_f32_add = self.ieee_f32_add();
+        let ieee_f32_mul = self.ieee_f32_mul();
+
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+        let i32 = self.get_op_type_int(32, true);
+        let u64 = self.get_op_type_int(64, false);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(u32, &[u32, u32, u32]);
+        let id = self.insert_op_function(u32, 0x8, op_type_function);
+        let a = self.insert_op_function_parameter(u32);
+        let b = self.insert_op_function_parameter(u32);
+        let c = self.insert_op_function_parameter(u32);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        // if (isnan(a) || isnan(b)) return PropagateNaN(a, b);
+        let is_nan_a = self.insert_ieee_f32_is_nan(a);
+        let is_nan_b = self.insert_ieee_f32_is_nan(b);
+        let cmp = self.insert_op_logical_or(bool, is_nan_a, is_nan_b);
+        self.insert_early_return_with(cmp, |s| s.insert_op_function_call(u32, ieee_f32_propagate_nan, &[a, b]));
+
+        // if (isnan(c)) return c | 0x400000;
+        let is_nan_c = self.insert_ieee_f32_is_nan(c);
+        let temp = self.get_const_int(32, false, 0x400000);
+        let or = self.insert_op_bitwise_or(u32, c, temp);
+        self.insert_early_return(is_nan_c, or);
+
+        // uint sign = (a ^ b) & 0x80000000;
+        let xor = self.insert_op_bitwise_xor(u32, a, b);
+        let sign_mask = self.get_const_int(32, false, 0x80000000);
+        let sign = self.insert_op_bitwise_and(u32, xor, sign_mask);
+        let sign_c = self.insert_op_bitwise_and(u32, c, sign_mask);
+
+        // if (isinf(a) || isinf(b))
+        //     return a == 0 || b == 0 || (isinf(c) && (c & 0x80000000) != sign) ? 0x7FC00000 : sign | 0x7F800000;
+        let is_inf_a = self.insert_ieee_f32_is_inf(a);
+        let is_inf_b = self.insert_ieee_f32_is_inf(b);
+        let is_inf_c = self.insert_ieee_f32_is_inf(c);
+        let is_inf = self.insert_op_logical_or(bool, is_inf_a, is_inf_b);
+        let is_zero_a = self.insert_ieee_f32_is_zero(a);
+        let is_zero_b = self.insert_ieee_f32_is_zero(b);
+        let is_zero = self.insert_op_logical_or(bool, is_zero_a, is_zero_b);
+        let cmp = self.insert_op_i_not_equal(bool, sign_c, sign);
+        let opposite_inf = self.insert_op_logical_and(bool, is_inf_c, cmp);
+        let invalid = self.insert_op_logical_or(bool, is_zero, opposite_inf);
+        let temp = self.get_const_int(32, false, 0x7F800000);
+        let infinity = self.insert_op_bitwise_or(u32, sign, temp);
+        let temp = self.get_const_int(32, false, 0x7FC00000);
+        let select = self.insert_op_select(u32, invalid, temp, infinity);
+        self.insert_early_return(is_inf, select);
+
+        // if (isinf(c)) return c;
+        self.insert_early_return(is_inf_c, c);
+
+        // The product is exactly zero, so only the sign of the sum of zeros needs care.
+        // if (a == 0 || b == 0) return Add(sign, c);
+        self.insert_early_return_with(is_zero, |s| s.insert_op_function_call(u32, ieee_f32_add, &[sign, c]));
+
+        // if (c == 0) return Mul(a, b);
+        let is_zero_c = self.insert_ieee_f32_is_zero(c);
+        self.insert_early_return_with(is_zero_c, |s| s.insert_op_function_call(u32, ieee_f32_mul, &[a, b]));
+
+        // The exact product and the addend are aligned in 64 bits, with enough guard bits for the sum to round
+        // correctly.
+        // uint64_t p = uint64_t(sigA) * uint64_t(sigB) << 14;
+        // uint64_t q = uint64_t(sigC) << 37;
+        let (exp_a, sig_a) = self.insert_ieee_f32_unpack(a);
+        let (exp_b, sig_b) = self.insert_ieee_f32_unpack(b);
+        let (exp_c, sig_c) = self.insert_ieee_f32_unpack(c);
+        let convert_a = self.insert_op_u_convert(u64, sig_a);
+        let convert_b = self.insert_op_u_convert(u64, sig_b);
+        let mul = self.insert_op_i_mul(u64, convert_a, convert_b);
+        let temp = self.get_const_int(32, false, 14);
+        let p = self.insert_op_shift_left_logical(u64, mul, temp);
+        let convert_c = self.insert_op_u_convert(u64, sig_c);
+        let temp = self.get_const_int(32, false, 37);
+        let q = self.insert_op_shift_left_logical(u64, convert_c, temp);
+
+        // int scaleP = expA + expB - 314;
+        // int scaleC = expC - 187;
+        let add = self.insert_op_i_add(i32, exp_a, exp_b);
+        let temp = self.get_const_int(32, true, 314);
+        let scale_p = self.insert_op_i_sub(i32, add, temp);
+        let temp = self.get_const_int(32, true, 187);
+        let scale_c = self.insert_op_i_sub(i32, exp_c, temp);
+
+        // bool productLarger = scaleP >= scaleC;
+        // p = productLarger ? p : ShiftRightJam64(p, uint(scaleC - scaleP));
+        // q = productLarger ? ShiftRightJam64(q, uint(scaleP - scaleC)) : q;
+        // int scale = productLarger ? scaleP : scaleC;
+        let product_larger = self.insert_op_s_greater_than_equal(bool, scale_p, scale_c);
+        let sub = self.insert_op_i_sub(i32, scale_c, scale_p);
+        let bitcast = self.insert_op_bitcast(u32, sub);
+        let jam = self.insert_ieee_u64_shift_right_jam(p, bitcast);
+        let p = self.insert_op_select(u64, product_larger, p, jam);
+        let sub = self.insert_op_i_sub(i32, scale_p, scale_c);
+        let bitcast = self.insert_op_bitcast(u32, sub);
+        let jam = self.insert_ieee_u64_shift_right_jam(q, bitcast);
+        let q = self.insert_op_select(u64, product_larger, jam, q);
+        let scale = self.insert_op_select(i32, product_larger, scale_p, scale_c);
+
+        // bool lower = p < q;
+        // uint64_t sum = signC == sign ? p + q : (lower ? q - p : p - q);
+        // uint signZ = lower ? signC : sign;
+        let lower = self.insert_op_u_less_than(bool, p, q);
+        let add = self.insert_op_i_add(u64, p, q);
+        let sub_qp = self.insert_op_i_sub(u64, q, p);
+        let sub_pq = self.insert_op_i_sub(u64, p, q);
+        let difference = self.insert_op_select(u64, lower, sub_qp, sub_pq);
+        let same_sign = self.insert_op_i_equal(bool, sign_c, sign);
+        let sum = self.insert_op_select(u64, same_sign, add, difference);
+        let sign_z = self.insert_op_select(u32, lower, sign_c, sign);
+
+        // if (sum == 0) return 0;
+        let temp = self.get_const_int(64, false, 0);
+        let cmp = self.insert_op_i_equal(bool, sum, temp);
+        let temp = self.get_const_int(32, false, 0);
+        self.insert_early_return(cmp, temp);
+
+        // int msb = FindMSB64(sum);
+        // uint64_t norm = sum << (62 - msb);
+        let msb = self.insert_ieee_u64_find_msb(sum);
+        let temp = self.get_const_int(32, true, 62);
+        let sub = self.insert_op_i_sub(i32, temp, msb);
+        let norm = self.insert_op_shift_left_logical(u64, sum, sub);
+
+        // uint sig = uint(norm >> 32) | uint(uint(norm) != 0);
+        let temp = self.get_const_int(64, false, 32);
+        let shift = self.insert_op_shift_right_logical(u64, norm, temp);
+        let high = self.insert_op_u_convert(u32, shift);
+        let low = self.insert_op_u_convert(u32, norm);
+        let temp = self.get_const_int(32, false, 0);
+        let cmp = self.insert_op_i_not_equal(bool, low, temp);
+        let sticky = self.insert_bool_to_uint(cmp);
+        let sig = self.insert_op_bitwise_or(u32, high, sticky);
+
+        // return RoundPack(signZ >> 31, scale + msb + 126, sig);
+        let temp = self.get_const_int(32, false, 31);
+        let sign_z = self.insert_op_shift_right_logical(u32, sign_z, temp);
+        let add = self.insert_op_i_add(i32, scale, msb);
+        let temp = self.get_const_int(32, true, 126);
+        let exp = self.insert_op_i_add(i32, add, temp);
+        let call = self.insert_op_function_call(u32, ieee_f32_round_pack, &[sign_z, exp, sig]);
+        self.insert_op_return_value(call);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    pub(super) fn ieee_f32_exp2(&mut self) -> u32 {
+        let name = "ieee_f32_exp2".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let ieee_f32_sub = self.ieee_f32_sub();
+
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+        let i32 = self.get_op_type_int(32, true);
+
+        let (id, a) = self.insert_ieee_f32_unary_function();
+
+        // if (isnan(a)) return a | 0x400000;
+        let is_nan = self.insert_ieee_f32_is_nan(a);
+        let temp = self.get_const_int(32, false, 0x400000);
+        let or = self.insert_op_bitwise_or(u32, a, temp);
+        self.insert_early_return(is_nan, or);
+
+        // if (a >= 128.0) return 0x7F800000;
+        let bitcast = self.insert_op_bitcast(i32, a);
+        let temp = self.get_const_int(32, true, 0x43000000);
+        let cmp = self.insert_op_s_greater_than_equal(bool, bitcast, temp);
+        let temp = self.get_const_int(32, false, 0x7F800000);
+        self.insert_early_return(cmp, temp);
+
+        // if (a <= -150.0) return 0;
+        let temp = self.get_const_int(32, false, 0xC315FFFF);
+        let cmp = self.insert_op_u_greater_than(bool, a, temp);
+        let temp = self.get_const_int(32, false, 0);
+        self.insert_early_return(cmp, temp);
+
+        // 2^a = 2^k * 2^(a - k), where k = round(a).
+        // return Scale(Polynomial(Sub(a, kf)), k);
+        let (k, kf) = self.insert_ieee_f32_round_to_int(a);
+        let sub = self.insert_op_function_call(u32, ieee_f32_sub, &[a, kf]);
+        let polynomial = self.insert_ieee_f32_polynomial(sub, &EXP2_COEFFICIENTS);
+        let scale = self.insert_ieee_f32_scale(polynomial, k);
+        self.insert_op_return_value(scale);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    pub(super) fn ieee_f32_exp(&mut self) -> u32 {
+        let name = "ieee_f32_exp".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let ieee_f32_sub = self.ieee_f32_sub();
+        let ieee_f32_mul = self.ieee_f32_mul();
+
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+        let i32 = self.get_op_type_int(32, true);
+
+        let (id, a) = self.insert_ieee_f32_unary_function();
+
+        // if (isnan(a)) return a | 0x400000;
+        let is_nan = self.insert_ieee_f32_is_nan(a);
+        let temp = self.get_const_int(32, false, 0x400000);
+        let or = self.insert_op_bitwise_or(u32, a, temp);
+        self.insert_early_return(is_nan, or);
+
+        // if (a >= 89.0) return 0x7F800000;
+        let bitcast = self.insert_op_bitcast(i32, a);
+        let temp = self.get_const_int(32, true, 0x42B20000);
+        let cmp = self.insert_op_s_greater_than_equal(bool, bitcast, temp);
+        let temp = self.get_const_int(32, false, 0x7F800000);
+        self.insert_early_return(cmp, temp);
+
+        // if (a <= -104.0) return 0;
+        let temp = self.get_const_int(32, false, 0xC2CFFFFF);
+        let cmp = self.insert_op_u_greater_than(bool, a, temp);
+        let temp = self.get_const_int(32, false, 0);
+        self.insert_early_return(cmp, temp);
+
+        // e^a = 2^k * e^r, where k = round(a * log2(e)) and r = a - k * ln(2).
+        // uint r = Sub(Sub(a, Mul(kf, LN_2_HI)), Mul(kf, LN_2_LO));
+        let temp = self.get_const_ieee_f32(LOG2_E);
+        let mul = self.insert_op_function_call(u32, ieee_f32_mul, &[a, temp]);
+        let (k, kf) = self.insert_ieee_f32_round_to_int(mul);
+        let temp = self.get_const_ieee_f32(LN_2_HI);
+        let mul = self.insert_op_function_call(u32, ieee_f32_mul, &[kf, temp]);
+        let r = self.insert_op_function_call(u32, ieee_f32_sub, &[a, mul]);
+        let temp = self.get_const_ieee_f32(LN_2_LO);
+        let mul = self.insert_op_function_call(u32, ieee_f32_mul, &[kf, temp]);
+        let r = self.insert_op_function_call(u32, ieee_f32_sub, &[r, mul]);
+
+        // return Scale(Polynomial(r), k);
+        let polynomial = self.insert_ieee_f32_polynomial(r, &EXP_COEFFICIENTS);
+        let scale = self.insert_ieee_f32_scale(polynomial, k);
+        self.insert_op_return_value(scale);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    fn insert_ieee_f32_log_special_cases(&mut self, a: u32) {
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+
+        // if (isnan(a)) return a | 0x400000;
+        let is_nan = self.insert_ieee_f32_is_nan(a);
+        let temp = self.get_const_int(32, false, 0x400000);
+        let or = self.insert_op_bitwise_or(u32, a, temp);
+        self.insert_early_return(is_nan, or);
+
+        // if (a == 0) return 0xFF800000;
+        let is_zero = self.insert_ieee_f32_is_zero(a);
+        let temp = self.get_const_int(32, false, 0xFF800000);
+        self.insert_early_return(is_zero, temp);
+
+        // if ((a >> 31) != 0) return 0x7FC00000;
+        let temp = self.get_const_int(32, false, 0x7FFFFFFF);
+        let cmp = self.insert_op_u_greater_than(bool, a, temp);
+        let temp = self.get_const_int(32, false, 0x7FC00000);
+        self.insert_early_return(cmp, temp);
+
+        // if (a == 0x7F800000) return a;
+        let is_inf = self.insert_ieee_f32_is_inf(a);
+        self.insert_early_return(is_inf, a);
+    }
+
+    /// Only for positive finite `a`, the other values are returned by `insert_ieee_f32_log_special_cases`.
+    fn insert_ieee_f32_log_reduce(&mut self, a: u32) -> (u32, u32, u32, u32) {
+        let ieee_f32_add = self.ieee_f32_add();
+        let ieee_f32_sub = self.ieee_f32_sub();
+        let ieee_f32_mul = self.ieee_f32_mul();
+        let ieee_f32_div = self.ieee_f32_div();
+
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+        let i32 = self.get_op_type_int(32, true);
+
+        // a = 2^e * m, where m is in [sqrt(2) / 2, sqrt(2)].
+        // uint m = (sig & 0x7FFFFF) | 0x3F800000;
+        // int e = exp - 127;
+        let (exp, sig) = self.insert_ieee_f32_unpack(a);
+        let temp = self.get_const_int(32, false, 0x7FFFFF);
+        let and = self.insert_op_bitwise_and(u32, sig, temp);
+        let temp = self.get_const_int(32, false, 0x3F800000);
+        let m = self.insert_op_bitwise_or(u32, and, temp);
+        let temp = self.get_const_int(32, true, 127);
+        let e = self.insert_op_i_sub(i32, exp, temp);
+
+        // bool above = m > sqrt(2);
+        // m = above ? m - 0x800000 : m;
+        // e += int(above);
+        let temp = self.get_const_int(32, false, 0x3FB504F3);
+        let above = self.insert_op_u_greater_than(bool, m, temp);
+        let temp = self.get_const_int(32, false, 0x800000);
+        let sub = self.insert_op_i_sub(u32, m, temp);
+        let m = self.insert_op_select(u32, above, sub, m);
+        let temp = self.insert_bool_to_uint(above);
+        let bitcast = self.insert_op_bitcast(i32, temp);
+        let e = self.insert_op_i_add(i32, e, bitcast);
+
+        // uint s = Div(Sub(m, 1.0), Add(m, 1.0));
+        // uint z = Mul(s, s);
+        let one = self.get_const_ieee_f32(1.0);
+        let sub = self.insert_op_function_call(u32, ieee_f32_sub, &[m, one]);
+        let add = self.insert_op_function_call(u32, ieee_f32_add, &[m, one]);
+        let s = self.insert_op_function_call(u32, ieee_f32_div, &[sub, add]);
+        let z = self.insert_op_function_call(u32, ieee_f32_mul, &[s, s]);
+
+        (e, m, s, z)
+    }
+
+    pub(super) fn ieee_f32_log2(&mut self) -> u32 {
+        let name = "ieee_f32_log2".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let ieee_f32_add = self.ieee_f32_add();
+        let ieee_f32_mul = self.ieee_f32_mul();
+
+        let u32 = self.get_op_type_int(32, false);
+
+        let (id, a) = self.insert_ieee_f32_unary_function();
+
+        self.insert_ieee_f32_log_special_cases(a);
+
+        // return Add(float(e), Mul(s, Polynomial(z)));
+        let (e, _, s, z) = self.insert_ieee_f32_log_reduce(a);
+        let polynomial = self.insert_ieee_f32_polynomial(z, &LOG2_COEFFICIENTS);
+        let mul = self.insert_op_function_call(u32, ieee_f32_mul, &[s, polynomial]);
+        let float = self.insert_ieee_f32_from_int(e);
+        let add = self.insert_op_function_call(u32, ieee_f32_add, &[float, mul]);
+        self.insert_op_return_value(add);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    pub(super) fn ieee_f32_log(&mut self) -> u32 {
+        let name = "ieee_f32_log".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let ieee_f32_add = self.ieee_f32_add();
+        let ieee_f32_mul = self.ieee_f32_mul();
+
+        let u32 = self.get_op_type_int(32, false);
+
+        let (id, a) = self.insert_ieee_f32_unary_function();
+
+        self.insert_ieee_f32_log_special_cases(a);
+
+        // uint ln = Mul(s, Polynomial(z));
+        let (e, _, s, z) = self.insert_ieee_f32_log_reduce(a);
+        let polynomial = self.insert_ieee_f32_polynomial(z, &LOG_COEFFICIENTS);
+        let ln = self.insert_op_function_call(u32, ieee_f32_mul, &[s, polynomial]);
+
+        // return Add(Add(Mul(float(e), LN_2_LO), ln), Mul(float(e), LN_2_HI));
+        let float = self.insert_ieee_f32_from_int(e);
+        let temp = self.get_const_ieee_f32(LN_2_LO);
+        let mul = self.insert_op_function_call(u32, ieee_f32_mul, &[float, temp]);
+        let add = self.insert_op_function_call(u32, ieee_f32_add, &[mul, ln]);
+        let temp = self.get_const_ieee_f32(LN_2_HI);
+        let mul = self.insert_op_function_call(u32, ieee_f32_mul, &[float, temp]);
+        let add = self.insert_op_function_call(u32, ieee_f32_add, &[add, mul]);
+        self.insert_op_return_value(add);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    pub(super) fn ieee_f32_pow(&mut self) -> u32 {
+        let name = "ieee_f32_pow".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let ieee_f32_propagate_nan = self.ieee_f32_propagate_nan();
+        let ieee_f32_add = self.ieee_f32_add();
+        let ieee_f32_sub = self.ieee_f32_sub();
+        let ieee_f32_mul = self.ieee_f32_mul();
+        let ieee_f32_div = self.ieee_f32_div();
+        let ieee_f32_fma = self.ieee_f32_fma();
+
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+        let i32 = self.get_op_type_int(32, true);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(u32, &[u32, u32]);
+        let id = self.insert_op_function(u32, 0x8, op_type_function);
+        let a = self.insert_op_function_parameter(u32);
+        let b = self.insert_op_function_parameter(u32);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        // if (b == 0 || a == 1.0) return 1.0;
+        let is_zero_b = self.insert_ieee_f32_is_zero(b);
+        let one = self.get_const_ieee_f32(1.0);
+        let cmp = self.insert_op_i_equal(bool, a, one);
+        let cmp = self.insert_op_logical_or(bool, is_zero_b, cmp);
+        self.insert_early_return(cmp, one);
+
+        // if (isnan(a) || isnan(b)) return PropagateNaN(a, b);
+        let is_nan_a = self.insert_ieee_f32_is_nan(a);
+        let is_nan_b = self.insert_ieee_f32_is_nan(b);
+        let cmp = self.insert_op_logical_or(bool, is_nan_a, is_nan_b);
+        self.insert_early_return_with(cmp, |s| s.insert_op_function_call(u32, ieee_f32_propagate_nan, &[a, b]));
+
+        // if (a == 0) return (b >> 31) == 0 ? 0 : 0x7F800000;
+        let is_zero_a = self.insert_ieee_f32_is_zero(a);
+        let temp = self.get_const_int(32, false, 0x80000000);
+        let cmp = self.insert_op_u_less_than(bool, b, temp);
+        let zero = self.get_const_int(32, false, 0);
+        let temp = self.get_const_int(32, false, 0x7F800000);
+        let select = self.insert_op_select(u32, cmp, zero, temp);
+        self.insert_early_return(is_zero_a, select);
+
+        // Negative bases are undefined in GLSL.
+        // if ((a >> 31) != 0) return 0x7FC00000;
+        let temp = self.get_const_int(32, false, 0x7FFFFFFF);
+        let cmp = self.insert_op_u_greater_than(bool, a, temp);
+        let temp = self.get_const_int(32, false, 0x7FC00000);
+        self.insert_early_return(cmp, temp);
+
+        // if (a == 0x7F800000) return (b >> 31) == 0 ? 0x7F800000 : 0;
+        let is_inf = self.insert_ieee_f32_is_inf(a);
+        let temp = self.get_const_int(32, false, 0x80000000);
+        let cmp = self.insert_op_u_less_than(bool, b, temp);
+        let temp = self.get_const_int(32, false, 0x7F800000);
+        let select = self.insert_op_select(u32, cmp, temp, zero);
+        self.insert_early_return(is_inf, select);
+
+        // pow(a, b) = 2^(b * log2(a)), where log2(a) is computed as the sum of two floats, because the error of a
+        // rounded log2(a) grows with b * log2(a).
+        // log2(a) = e + 2/ln(2) * atanh(s), with s = (m - 1) / (m + 1).
+        // uint sLo = Div(Sub(Fma(-s, Add(m, 1.0), Sub(m, 1.0)), Mul(s, Sub(m, Sub(Add(m, 1.0), 1.0)))), Add(m, 1.0));
+        let (e, m, s, z) = self.insert_ieee_f32_log_reduce(a);
+        let sign_mask = self.get_const_int(32, false, 0x80000000);
+        let numerator = self.insert_op_function_call(u32, ieee_f32_sub, &[m, one]);
+        let denominator = self.insert_op_function_call(u32, ieee_f32_add, &[m, one]);
+        let sub = self.insert_op_function_call(u32, ieee_f32_sub, &[denominator, one]);
+        let denominator_lo = self.insert_op_function_call(u32, ieee_f32_sub, &[m, sub]);
+        let negate = self.insert_op_bitwise_xor(u32, s, sign_mask);
+        let fma = self.insert_op_function_call(u32, ieee_f32_fma, &[negate, denominator, numerator]);
+        let mul = self.insert_op_function_call(u32, ieee_f32_mul, &[s, denominator_lo]);
+        let sub = self.insert_op_function_call(u32, ieee_f32_sub, &[fma, mul]);
+        let s_lo = self.insert_op_function_call(u32, ieee_f32_div, &[sub, denominator]);
+
+        // The s^3/3 term of atanh(s) is computed in two parts too.
+        // uint cube = Mul(z, s);
+        // uint cubeLo = Add(Fma(z, s, -cube), Mul(Fma(s, s, -z), s));
+        let cube = self.insert_op_function_call(u32, ieee_f32_mul, &[z, s]);
+        let negate = self.insert_op_bitwise_xor(u32, cube, sign_mask);
+        let fma = self.insert_op_function_call(u32, ieee_f32_fma, &[z, s, negate]);
+        let negate = self.insert_op_bitwise_xor(u32, z, sign_mask);
+        let temp = self.insert_op_function_call(u32, ieee_f32_fma, &[s, s, negate]);
+        let mul = self.insert_op_function_call(u32, ieee_f32_mul, &[temp, s]);
+        let cube_lo = self.insert_op_function_call(u32, ieee_f32_add, &[fma, mul]);
+
+        // uint third = Mul(cube, FRAC_1_3_HI);
+        // uint thirdLo = Add(Fma(cube, FRAC_1_3_HI, -third), Add(Mul(cube, FRAC_1_3_LO), Mul(cubeLo, FRAC_1_3_HI)));
+        let third_hi = self.get_const_ieee_f32(FRAC_1_3_HI);
+        let third = self.insert_op_function_call(u32, ieee_f32_mul, &[cube, third_hi]);
+        let negate = self.insert_op_bitwise_xor(u32, third, sign_mask);
+        let fma = self.insert_op_function_call(u32, ieee_f32_fma, &[cube, third_hi, negate]);
+        let temp = self.get_const_ieee_f32(FRAC_1_3_LO);
+        let mul = self.insert_op_function_call(u32, ieee_f32_mul, &[cube, temp]);
+        let mul2 = self.insert_op_function_call(u32, ieee_f32_mul, &[cube_lo, third_hi]);
+        let add = self.insert_op_function_call(u32, ieee_f32_add, &[mul, mul2]);
+        let third_lo = self.insert_op_function_call(u32, ieee_f32_add, &[fma, add]);
+
+        // The error of s is multiplied by the derivative of atanh, 1 / (1 - s^2).
+        // uint tail = Add(Add(thirdLo, Mul(Mul(Polynomial(z), z), cube)), Fma(sLo, z, sLo));
+        let polynomial = self.insert_ieee_f32_polynomial(z, &ATANH_TAIL_COEFFICIENTS);
+        let mul = self.insert_op_function_call(u32, ieee_f32_mul, &[polynomial, z]);
+        let mul = self.insert_op_function_call(u32, ieee_f32_mul, &[mul, cube]);
+        let add = self.insert_op_function_call(u32, ieee_f32_add, &[third_lo, mul]);
+        let fma = self.insert_op_function_call(u32, ieee_f32_fma, &[s_lo, z, s_lo]);
+        let tail = self.insert_op_function_call(u32, ieee_f32_add, &[add, fma]);
+
+        // atanh(s) = u + uLo, renormalized so uLo is below half an ulp of u.
+        // uint u = Add(s, third);
+        // uint uLo = Add(Add(Sub(s, u), third), tail);
+        // uint v = Add(u, uLo);
+        // uLo = Sub(uLo, Sub(v, u));
+        let u = self.insert_op_function_call(u32, ieee_f32_add, &[s, third]);
+        let sub = self.insert_op_function_call(u32, ieee_f32_sub, &[s, u]);
+        let add = self.insert_op_function_call(u32, ieee_f32_add, &[sub, third]);
+        let u_lo = self.insert_op_function_call(u32, ieee_f32_add, &[add, tail]);
+        let v = self.insert_op_function_call(u32, ieee_f32_add, &[u, u_lo]);
+        let sub = self.insert_op_function_call(u32, ieee_f32_sub, &[v, u]);
+        let u_lo = self.insert_op_function_call(u32, ieee_f32_sub, &[u_lo, sub]);
+
+        // uint p = Mul(FRAC_2_LN_2_HI, v);
+        // uint pLo = Add(Fma(FRAC_2_LN_2_HI, v, -p), Add(Mul(FRAC_2_LN_2_LO, v), Mul(FRAC_2_LN_2_HI, uLo)));
+        let k_hi = self.get_const_ieee_f32(FRAC_2_LN_2_HI);
+        let p = self.insert_op_function_call(u32, ieee_f32_mul, &[k_hi, v]);
+        let negate = self.insert_op_bitwise_xor(u32, p, sign_mask);
+        let fma = self.insert_op_function_call(u32, ieee_f32_fma, &[k_hi, v, negate]);
+        let temp = self.get_const_ieee_f32(FRAC_2_LN_2_LO);
+        let mul = self.insert_op_function_call(u32, ieee_f32_mul, &[temp, v]);
+        let mul2 = self.insert_op_function_call(u32, ieee_f32_mul, &[k_hi, u_lo]);
+        let add = self.insert_op_function_call(u32, ieee_f32_add, &[mul, mul2]);
+        let p_lo = self.insert_op_function_call(u32, ieee_f32_add, &[fma, add]);
+
+        // e is either 0 or larger than p, so the rounding error of the sum is exact.
+        // uint l = Add(float(e), p);
+        // uint lLo = Add(Add(Sub(float(e), l), p), pLo);
+        let float = self.insert_ieee_f32_from_int(e);
+        let l = self.insert_op_function_call(u32, ieee_f32_add, &[float, p]);
+        let sub = self.insert_op_function_call(u32, ieee_f32_sub, &[float, l]);
+        let add = self.insert_op_function_call(u32, ieee_f32_add, &[sub, p]);
+        let l_lo = self.insert_op_function_call(u32, ieee_f32_add, &[add, p_lo]);
+
+        // uint y = Mul(b, l);
+        let y = self.insert_op_function_call(u32, ieee_f32_mul, &[b, l]);
+
+        // if (y > 128.0) return 0x7F800000;
+        let bitcast = self.insert_op_bitcast(i32, y);
+        let temp = self.get_const_int(32, true, 0x43000001);
+        let cmp = self.insert_op_s_greater_than_equal(bool, bitcast, temp);
+        let temp = self.get_const_int(32, false, 0x7F800000);
+        self.insert_early_return(cmp, temp);
+
+        // if (y < -150.0) return 0;
+        let temp = self.get_const_int(32, false, 0xC3160000);
+        let cmp = self.insert_op_u_greater_than(bool, y, temp);
+        self.insert_early_return(cmp, zero);
+
+        // uint yLo = Add(Fma(b, l, -y), Mul(b, lLo));
+        let negate = self.insert_op_bitwise_xor(u32, y, sign_mask);
+        let fma = self.insert_op_function_call(u32, ieee_f32_fma, &[b, l, negate]);
+        let mul = self.insert_op_function_call(u32, ieee_f32_mul, &[b, l_lo]);
+        let y_lo = self.insert_op_function_call(u32, ieee_f32_add, &[fma, mul]);
+
+        // 2^y = 2^k * 2^(y - k + yLo), where k = round(y).
+        // return Scale(Polynomial(Add(Sub(y, kf), yLo)), k);
+        let (k, kf) = self.insert_ieee_f32_round_to_int(y);
+        let sub = self.insert_op_function_call(u32, ieee_f32_sub, &[y, kf]);
+        let add = self.insert_op_function_call(u32, ieee_f32_add, &[sub, y_lo]);
+        let polynomial = self.insert_ieee_f32_polynomial(add, &EXP2_COEFFICIENTS);
+        let scale = self.insert_ieee_f32_scale(polynomial, k);
+        self.insert_op_return_value(scale);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    fn insert_frac_2_pi_word(&mut self, index: u32) -> u32 {
+        let bool = self.get_op_type_bool();
+        let u64 = self.get_op_type_int(64, false);
+
+        // uint64_t(FRAC_2_PI_WORDS[index])
+        let mut word = self.get_const_u64(FRAC_2_PI_WORDS[0] as u64);
+        for (i, value) in FRAC_2_PI_WORDS.iter().enumerate().skip(1) {
+            let temp = self.get_const_int(32, false, i as u32);
+            let cmp = self.insert_op_i_equal(bool, index, temp);
+            let temp = self.get_const_u64(*value as u64);
+            word = self.insert_op_select(u64, cmp, temp, word);
+        }
+        word
+    }
+
+    /// Payne-Hanek reduction of a finite `a` with `|a| >= 2^-7`, returns k and r with a = k * pi/2 + r, where r is in
+    /// [-pi/4, pi/4].
+    fn insert_ieee_f32_reduce_pi_2(&mut self, a: u32) -> (u32, u32) {
+        let ieee_f32_round_pack = self.ieee_f32_round_pack();
+
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+        let i32 = self.get_op_type_int(32, true);
+        let u64 = self.get_op_type_int(64, false);
+
+        // a * 2/pi modulo 4 is computed in 2.62 fixed point. a = m * 2^(exp - 150), so the bits of 2/pi from
+        // 2^(152 - exp) up only add multiples of 4 and the 96 bits below them are enough for 62 fractional bits.
+        // uint64_t m = uint64_t((a & 0x7FFFFF) | 0x800000);
+        // uint offset = uint(exp - 120);
+        let temp = self.get_const_int(32, false, 0x7FFFFF);
+        let and = self.insert_op_bitwise_and(u32, a, temp);
+        let temp = self.get_const_int(32, false, 0x800000);
+        let or = self.insert_op_bitwise_or(u32, and, temp);
+        let m = self.insert_op_u_convert(u64, or);
+        let temp = self.get_const_int(32, false, 23);
+        let shift = self.insert_op_shift_right_logical(u32, a, temp);
+        let temp = self.get_const_int(32, false, 0xFF);
+        let exp = self.insert_op_bitwise_and(u32, shift, temp);
+        let temp = self.get_const_int(32, false, 120);
+        let offset = self.insert_op_i_sub(u32, exp, temp);
+
+        // uint n = offset >> 5;
+        // uint shift = 32 - (offset & 31);
+        // uint64_t q[i] = ((FRAC_2_PI_WORDS[n + i] << 32) | FRAC_2_PI_WORDS[n + i + 1]) >> shift & 0xFFFFFFFF;
+        let temp = self.get_const_int(32, false, 5);
+        let n = self.insert_op_shift_right_logical(u32, offset, temp);
+        let temp = self.get_const_int(32, false, 31);
+        let and = self.insert_op_bitwise_and(u32, offset, temp);
+        let temp = self.get_const_int(32, false, 32);
+        let shift = self.insert_op_i_sub(u32, temp, and);
+        let mut words = Vec::new();
+        for i in 0..4 {
+            let temp = self.get_const_int(32, false, i);
+            let index = self.insert_op_i_add(u32, n, temp);
+            words.push(self.insert_frac_2_pi_word(index));
+        }
+        let mut q = Vec::new();
+        for i in 0..3 {
+            let temp = self.get_const_int(64, false, 32);
+            let high = self.insert_op_shift_left_logical(u64, words[i], temp);
+            let or = self.insert_op_bitwise_or(u64, high, words[i + 1]);
+            let shift = self.insert_op_shift_right_logical(u64, or, shift);
+            let temp = self.get_const_u64(0xFFFFFFFF);
+            q.push(self.insert_op_bitwise_and(u64, shift, temp));
+        }
+
+        // uint64_t f = (m * q[2] >> 32) + m * q[1] + (m * q[0] << 32);
+        let temp = self.get_const_int(64, false, 32);
+        let mul = self.insert_op_i_mul(u64, m, q[2]);
+        let low = self.insert_op_shift_right_logical(u64, mul, temp);
+        let middle = self.insert_op_i_mul(u64, m, q[1]);
+        let mul = self.insert_op_i_mul(u64, m, q[0]);
+        let high = self.insert_op_shift_left_logical(u64, mul, temp);
+        let add = self.insert_op_i_add(u64, low, middle);
+        let f = self.insert_op_i_add(u64, add, high);
+
+        // uint64_t k = (f + (1 << 61)) >> 62;
+        // uint64_t fraction = f - (k << 62);
+        let temp = self.get_const_u64(1 << 61);
+        let add = self.insert_op_i_add(u64, f, temp);
+        let temp = self.get_const_int(64, false, 62);
+        let k = self.insert_op_shift_right_logical(u64, add, temp);
+        let shift = self.insert_op_shift_left_logical(u64, k, temp);
+        let fraction = self.insert_op_i_sub(u64, f, shift);
+
+        // bool negative = (a >> 31) != 0;
+        // int k = negative ? -int(k) : int(k);
+        // fraction = negative ? -fraction : fraction;
+        let temp = self.get_const_int(32, false, 0x7FFFFFFF);
+        let negative = self.insert_op_u_greater_than(bool, a, temp);
+        let convert = self.insert_op_u_convert(u32, k);
+        let k = self.insert_op_bitcast(i32, convert);
+        let negate = self.insert_op_s_negate(i32, k);
+        let k = self.insert_op_select(i32, negative, negate, k);
+        let zero = self.get_const_int(64, false, 0);
+        let negate = self.insert_op_i_sub(u64, zero, fraction);
+        let fraction = self.insert_op_select(u64, negative, negate, fraction);
+
+        // The fraction is never zero, the lowest bit only keeps FindMSB64 defined.
+        // uint sign = uint(fraction >> 63);
+        // uint64_t magnitude = (sign != 0 ? -fraction : fraction) | 1;
+        // int msb = FindMSB64(magnitude);
+        let temp = self.get_const_int(64, false, 63);
+        let shift = self.insert_op_shift_right_logical(u64, fraction, temp);
+        let sign = self.insert_op_u_convert(u32, shift);
+        let temp = self.get_const_int(32, false, 0);
+        let cmp = self.insert_op_i_not_equal(bool, sign, temp);
+        let negate = self.insert_op_i_sub(u64, zero, fraction);
+        let select = self.insert_op_select(u64, cmp, negate, fraction);
+        let temp = self.get_const_int(64, false, 1);
+        let magnitude = self.insert_op_bitwise_or(u64, select, temp);
+        let msb = self.insert_ieee_u64_find_msb(magnitude);
+
+        // r = fraction * 2^-62 * pi/2, from the leading 32 bits of the fraction.
+        // uint64_t product = (magnitude << (63 - msb) >> 32) * FRAC_PI_2_FIXED;
+        // int msbProduct = FindMSB64(product);
+        // uint sig = uint(ShiftRightJam64(product, uint(msbProduct - 30)));
+        let temp = self.get_const_int(32, true, 63);
+        let sub = self.insert_op_i_sub(i32, temp, msb);
+        let shift = self.insert_op_shift_left_logical(u64, magnitude, sub);
+        let temp = self.get_const_int(64, false, 32);
+        let leading = self.insert_op_shift_right_logical(u64, shift, temp);
+        let temp = self.get_const_u64(FRAC_PI_2_FIXED);
+        let product = self.insert_op_i_mul(u64, leading, temp);
+        let msb_product = self.insert_ieee_u64_find_msb(product);
+        let temp = self.get_const_int(32, true, 30);
+        let sub = self.insert_op_i_sub(i32, msb_product, temp);
+        let bitcast = self.insert_op_bitcast(u32, sub);
+        let jam = self.insert_ieee_u64_shift_right_jam(product, bitcast);
+        let sig = self.insert_op_u_convert(u32, jam);
+
+        // uint r = RoundPack(sign, msbProduct + msb + 2, sig);
+        let add = self.insert_op_i_add(i32, msb_product, msb);
+        let temp = self.get_const_int(32, true, 2);
+        let exp = self.insert_op_i_add(i32, add, temp);
+        let r = self.insert_op_function_call(u32, ieee_f32_round_pack, &[sign, exp, sig]);
+
+        (k, r)
+    }
+
+    pub(super) fn ieee_f32_sin(&mut self) -> u32 {
+        self.ieee_f32_sin_cos("ieee_f32_sin", 0)
+    }
+
+    pub(super) fn ieee_f32_cos(&mut self) -> u32 {
+        self.ieee_f32_sin_cos("ieee_f32_cos", 1)
+    }
+
+    fn ieee_f32_sin_cos(&mut self, name: &str, quadrant_offset: u32) -> u32 {
+        if let Some(id) = self.created.get(name) {
+            return *id;
+        }
+
+        let ieee_f32_add = self.ieee_f32_add();
+        let ieee_f32_sub = self.ieee_f32_sub();
+        let ieee_f32_mul = self.ieee_f32_mul();
+
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+        let i32 = self.get_op_type_int(32, true);
+
+        let (id, a) = self.insert_ieee_f32_unary_function();
+
+        // if (isnan(a)) return a | 0x400000;
+        let is_nan = self.insert_ieee_f32_is_nan(a);
+        let temp = self.get_const_int(32, false, 0x400000);
+        let or = self.insert_op_bitwise_or(u32, a, temp);
+        self.insert_early_return(is_nan, or);
+
+        // if (isinf(a)) return 0x7FC00000;
+        let is_inf = self.insert_ieee_f32_is_inf(a);
+        let temp = self.get_const_int(32, false, 0x7FC00000);
+        self.insert_early_return(is_inf, temp);
+
+        // Below 2^-12 the result rounds to a for sin and to 1.0 for cos.
+        // if (abs(a) < 2^-12) return Sin ? a : 1.0;
+        let temp = self.get_const_int(32, false, 0x7FFFFFFF);
+        let abs = self.insert_op_bitwise_and(u32, a, temp);
+        let temp = self.get_const_int(32, false, 0x39800000);
+        let cmp = self.insert_op_u_less_than(bool, abs, temp);
+        let tiny = match quadrant_offset {
+            0 => a,
+            _ => self.get_const_ieee_f32(1.0),
+        };
+        self.insert_early_return(cmp, tiny);
+
+        // a = k * pi/2 + r, where r is in [-pi/4, pi/4].
+        // bool small = abs(a) < pi/4;
+        // int k = small ? 0 : ReducePi2(a).k;
+        // uint r = small ? a : ReducePi2(a).r;
+        let temp = self.get_const_ieee_f32(FRAC_PI_4);
+        let small = self.insert_op_u_less_than(bool, abs, temp);
+        let (k, r) = self.insert_ieee_f32_reduce_pi_2(a);
+        let temp = self.get_const_int(32, true, 0);
+        let k = self.insert_op_select(i32, small, temp, k);
+        let r = self.insert_op_select(u32, small, a, r);
+
+        // cos(a) = sin(a + pi/2), so cos starts one quadrant later.
+        // uint quadrant = uint(k + offset) & 3;
+        // uint sign = (quadrant & 2) << 30;
+        let temp = self.get_const_int(32, true, quadrant_offset);
+        let add = self.insert_op_i_add(i32, k, temp);
+        let bitcast = self.insert_op_bitcast(u32, add);
+        let temp = self.get_const_int(32, false, 3);
+        let quadrant = self.insert_op_bitwise_and(u32, bitcast, temp);
+        let temp = self.get_const_int(32, false, 2);
+        let and = self.insert_op_bitwise_and(u32, quadrant, temp);
+        let temp = self.get_const_int(32, false, 30);
+        let sign = self.insert_op_shift_left_logical(u32, and, temp);
+
+        // uint z = Mul(r, r);
+        let z = self.insert_op_function_call(u32, ieee_f32_mul, &[r, r]);
+
+        // uint cosR = Add(Sub(Mul(Mul(Polynomial(z), z), z), Mul(0.5, z)), 1.0);
+        let polynomial = self.insert_ieee_f32_polynomial(z, &COS_COEFFICIENTS);
+        let mul = self.insert_op_function_call(u32, ieee_f32_mul, &[polynomial, z]);
+        let mul = self.insert_op_function_call(u32, ieee_f32_mul, &[mul, z]);
+        let temp = self.get_const_ieee_f32(0.5);
+        let half = self.insert_op_function_call(u32, ieee_f32_mul, &[temp, z]);
+        let sub = self.insert_op_function_call(u32, ieee_f32_sub, &[mul, half]);
+        let temp = self.get_const_ieee_f32(1.0);
+        let cos_r = self.insert_op_function_call(u32, ieee_f32_add, &[sub, temp]);
+
+        // uint sinR = Add(Mul(Mul(Polynomial(z), z), r), r);
+        let polynomial = self.insert_ieee_f32_polynomial(z, &SIN_COEFFICIENTS);
+        let mul = self.insert_op_function_call(u32, ieee_f32_mul, &[polynomial, z]);
+        let mul = self.insert_op_function_call(u32, ieee_f32_mul, &[mul, r]);
+        let sin_r = self.insert_op_function_call(u32, ieee_f32_add, &[mul, r]);
+
+        // return sign ^ ((quadrant & 1) != 0 ? cosR : sinR);
+        let temp = self.get_const_int(32, false, 1);
+        let and = self.insert_op_bitwise_and(u32, quadrant, temp);
+        let temp = self.get_const_int(32, false, 0);
+        let cmp = self.insert_op_i_not_equal(bool, and, temp);
+        let select = self.insert_op_select(u32, cmp, cos_r, sin_r);
+        let xor = self.insert_op_bitwise_xor(u32, sign, select);
+        self.insert_op_return_value(xor);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name.to_owned(), id);
+        id
+    }
+}
@@ -0,0 +1,631 @@
+use super::Buffer;
+
+impl Buffer {
+    pub(super) fn insert_early_return(&mut self, condition: u32, value: u32) {
+        self.insert_early_return_with(condition, |_| value);
+    }
+
+    /// Like `insert_early_return`, but instructions of the value are inserted into the taken branch, so function calls
+    /// are not evaluated when the condition does not hold.
+    pub(super) fn insert_early_return_with(&mut self, condition: u32, value: impl FnOnce(&mut Self) -> u32) {
+        // if (condition) return value;
+        let false_label = self.get_next_id();
+        self.insert_op_selection_merge(false_label, 0);
+        let true_label = self.get_next_id();
+        self.insert_op_branch_conditional(condition, true_label, false_label);
+
+        self.insert_op_label(true_label);
+        let value = value(self);
+        self.insert_op_return_value(value);
+
+        self.insert_op_label(false_label);
+    }
+
+    pub(super) fn insert_bool_to_uint(&mut self, condition: u32) -> u32 {
+        let u32 = self.get_op_type_int(32, false);
+
+        // uint(condition)
+        let one = self.get_const_int(32, false, 1);
+        let zero = self.get_const_int(32, false, 0);
+        self.insert_op_select(u32, condition, one, zero)
+    }
+
+    pub(super) fn insert_ieee_f32_is_nan(&mut self, value: u32) -> u32 {
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+
+        // (value & 0x7FFFFFFF) > 0x7F800000
+        let temp = self.get_const_int(32, false, 0x7FFFFFFF);
+        let and = self.insert_op_bitwise_and(u32, value, temp);
+        let temp = self.get_const_int(32, false, 0x7F800000);
+        self.insert_op_u_greater_than(bool, and, temp)
+    }
+
+    pub(super) fn insert_ieee_f32_is_inf(&mut self, value: u32) -> u32 {
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+
+        // (value & 0x7FFFFFFF) == 0x7F800000
+        let temp = self.get_const_int(32, false, 0x7FFFFFFF);
+        let and = self.insert_op_bitwise_and(u32, value, temp);
+        let temp = self.get_const_int(32, false, 0x7F800000);
+        self.insert_op_i_equal(bool, and, temp)
+    }
+
+    pub(super) fn insert_ieee_f32_is_zero(&mut self, value: u32) -> u32 {
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+
+        // (value & 0x7FFFFFFF) == 0
+        let temp = self.get_const_int(32, false, 0x7FFFFFFF);
+        let and = self.insert_op_bitwise_and(u32, value, temp);
+        let temp = self.get_const_int(32, false, 0);
+        self.insert_op_i_equal(bool, and, temp)
+    }
+
+    pub(super) fn insert_ieee_f32_unpack(&mut self, value: u32) -> (u32, u32) {
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+        let i32 = self.get_op_type_int(32, true);
+
+        // int exp = int((value >> 23) & 0xFF);
+        let temp = self.get_const_int(32, false, 23);
+        let shift = self.insert_op_shift_right_logical(u32, value, temp);
+        let temp = self.get_const_int(32, false, 0xFF);
+        let and = self.insert_op_bitwise_and(u32, shift, temp);
+        let exp = self.insert_op_bitcast(i32, and);
+
+        // uint fraction = value & 0x7FFFFF;
+        let temp = self.get_const_int(32, false, 0x7FFFFF);
+        let fraction = self.insert_op_bitwise_and(u32, value, temp);
+
+        // Subnormals are normalized so the hidden bit is always at bit 23.
+        // bool subnormal = exp == 0;
+        // int shift = subnormal ? 23 - findMSB(fraction) : 0;
+        let temp = self.get_const_int(32, true, 0);
+        let subnormal = self.insert_op_i_equal(bool, exp, temp);
+        let glsl_std_450 = self.get_ext_inst_import("GLSL.std.450");
+        let msb = self.insert_op_ext_inst(i32, glsl_std_450, 75, &[fraction]);
+        let temp = self.get_const_int(32, true, 23);
+        let sub = self.insert_op_i_sub(i32, temp, msb);
+        let temp = self.get_const_int(32, true, 0);
+        let shift = self.insert_op_select(i32, subnormal, sub, temp);
+
+        // exp = subnormal ? 1 - shift : exp;
+        let temp = self.get_const_int(32, true, 1);
+        let sub = self.insert_op_i_sub(i32, temp, shift);
+        let exp = self.insert_op_select(i32, subnormal, sub, exp);
+
+        // uint sig = subnormal ? fraction << shift : fraction | 0x800000;
+        let shifted = self.insert_op_shift_left_logical(u32, fraction, shift);
+        let temp = self.get_const_int(32, false, 0x800000);
+        let or = self.insert_op_bitwise_or(u32, fraction, temp);
+        let sig = self.insert_op_select(u32, subnormal, shifted, or);
+
+        (exp, sig)
+    }
+
+    pub(super) fn ieee_f32_propagate_nan(&mut self) -> u32 {
+        let name = "ieee_f32_propagate_nan".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let u32 = self.get_op_type_int(32, false);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(u32, &[u32, u32]);
+        let id = self.insert_op_function(u32, 0x8, op_type_function);
+        let a = self.insert_op_function_parameter(u32);
+        let b = self.insert_op_function_parameter(u32);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        // return (isnan(a) ? a : b) | 0x400000;
+        let is_nan = self.insert_ieee_f32_is_nan(a);
+        let select = self.insert_op_select(u32, is_nan, a, b);
+        let temp = self.get_const_int(32, false, 0x400000);
+        let or = self.insert_op_bitwise_or(u32, select, temp);
+        self.insert_op_return_value(or);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    fn ieee_f32_shift_right_jam(&mut self) -> u32 {
+        let name = "ieee_f32_shift_right_jam".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(u32, &[u32, u32]);
+        let id = self.insert_op_function(u32, 0x8, op_type_function);
+        let a = self.insert_op_function_parameter(u32);
+        let dist = self.insert_op_function_parameter(u32);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        // uint d = min(dist, 31);
+        let temp = self.get_const_int(32, false, 31);
+        let glsl_std_450 = self.get_ext_inst_import("GLSL.std.450");
+        let d = self.insert_op_ext_inst(u32, glsl_std_450, 38, &[dist, temp]);
+
+        // return (a >> d) | uint((a & ((1 << d) - 1)) != 0);
+        let one = self.get_const_int(32, false, 1);
+        let shift = self.insert_op_shift_left_logical(u32, one, d);
+        let mask = self.insert_op_i_sub(u32, shift, one);
+        let and = self.insert_op_bitwise_and(u32, a, mask);
+        let temp = self.get_const_int(32, false, 0);
+        let cmp = self.insert_op_i_not_equal(bool, and, temp);
+        let sticky = self.insert_bool_to_uint(cmp);
+        let shift = self.insert_op_shift_right_logical(u32, a, d);
+        let or = self.insert_op_bitwise_or(u32, shift, sticky);
+        self.insert_op_return_value(or);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    pub(super) fn ieee_f32_round_pack(&mut self) -> u32 {
+        let name = "ieee_f32_round_pack".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let ieee_f32_shift_right_jam = self.ieee_f32_shift_right_jam();
+
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+        let i32 = self.get_op_type_int(32, true);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(u32, &[u32, i32, u32]);
+        let id = self.insert_op_function(u32, 0x8, op_type_function);
+        let sign = self.insert_op_function_parameter(u32);
+        let exp = self.insert_op_function_parameter(i32);
+        let sig = self.insert_op_function_parameter(u32);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        // if (exp > 0xFD) return (sign << 31) | 0x7F800000;
+        let temp = self.get_const_int(32, false, 31);
+        let sign_bits = self.insert_op_shift_left_logical(u32, sign, temp);
+        let temp = self.get_const_int(32, false, 0x7F800000);
+        let infinity = self.insert_op_bitwise_or(u32, sign_bits, temp);
+        let temp = self.get_const_int(32, true, 0xFD);
+        let cmp = self.insert_op_sless_than(bool, temp, exp);
+        self.insert_early_return(cmp, infinity);
+
+        // if (exp < 0) { sig = ShiftRightJam(sig, uint(-exp)); exp = 0; }
+        let temp = self.get_const_int(32, true, 0);
+        let underflow = self.insert_op_sless_than(bool, exp, temp);
+        let negate = self.insert_op_s_negate(i32, exp);
+        let bitcast = self.insert_op_bitcast(u32, negate);
+        let call = self.insert_op_function_call(u32, ieee_f32_shift_right_jam, &[sig, bitcast]);
+        let sig = self.insert_op_select(u32, underflow, call, sig);
+        let exp = self.insert_op_select(i32, underflow, temp, exp);
+
+        // uint roundBits = sig & 0x7F;
+        let temp = self.get_const_int(32, false, 0x7F);
+        let round_bits = self.insert_op_bitwise_and(u32, sig, temp);
+
+        // sig = (sig + 0x40) >> 7;
+        let temp = self.get_const_int(32, false, 0x40);
+        let add = self.insert_op_i_add(u32, sig, temp);
+        let temp = self.get_const_int(32, false, 7);
+        let shift = self.insert_op_shift_right_logical(u32, add, temp);
+
+        // sig &= ~uint(roundBits == 0x40);
+        let temp = self.get_const_int(32, false, 0x40);
+        let cmp = self.insert_op_i_equal(bool, round_bits, temp);
+        let temp = self.get_const_int(32, false, 0xFFFFFFFE);
+        let temp2 = self.get_const_int(32, false, 0xFFFFFFFF);
+        let mask = self.insert_op_select(u32, cmp, temp, temp2);
+        let rounded = self.insert_op_bitwise_and(u32, shift, mask);
+
+        // A carry out of the significand increments the exponent, which also rounds up to infinity or to the
+        // smallest normal number.
+        // return (sign << 31) + (uint(exp) << 23) + sig;
+        let bitcast = self.insert_op_bitcast(u32, exp);
+        let temp = self.get_const_int(32, false, 23);
+        let exp_bits = self.insert_op_shift_left_logical(u32, bitcast, temp);
+        let add = self.insert_op_i_add(u32, sign_bits, exp_bits);
+        let add = self.insert_op_i_add(u32, add, rounded);
+        self.insert_op_return_value(add);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    pub(super) fn ieee_f32_add(&mut self) -> u32 {
+        let name = "ieee_f32_add".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let ieee_f32_propagate_nan = self.ieee_f32_propagate_nan();
+        let ieee_f32_shift_right_jam = self.ieee_f32_shift_right_jam();
+        let ieee_f32_round_pack = self.ieee_f32_round_pack();
+
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+        let i32 = self.get_op_type_int(32, true);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(u32, &[u32, u32]);
+        let id = self.insert_op_function(u32, 0x8, op_type_function);
+        let a = self.insert_op_function_parameter(u32);
+        let b = self.insert_op_function_parameter(u32);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        // if (isnan(a) || isnan(b)) return PropagateNaN(a, b);
+        let is_nan_a = self.insert_ieee_f32_is_nan(a);
+        let is_nan_b = self.insert_ieee_f32_is_nan(b);
+        let cmp = self.insert_op_logical_or(bool, is_nan_a, is_nan_b);
+        self.insert_early_return_with(cmp, |s| s.insert_op_function_call(u32, ieee_f32_propagate_nan, &[a, b]));
+
+        // if (isinf(a)) return isinf(b) && ((a ^ b) >> 31) != 0 ? 0x7FC00000 : a;
+        let is_inf_a = self.insert_ieee_f32_is_inf(a);
+        let is_inf_b = self.insert_ieee_f32_is_inf(b);
+        let xor = self.insert_op_bitwise_xor(u32, a, b);
+        let temp = self.get_const_int(32, false, 0x80000000);
+        let cmp = self.insert_op_u_less_than(bool, xor, temp);
+        let temp = self.get_const_int(32, false, 0x7FC00000);
+        let select = self.insert_op_select(u32, cmp, a, temp);
+        let select = self.insert_op_select(u32, is_inf_b, select, a);
+        self.insert_early_return(is_inf_a, select);
+
+        // if (isinf(b)) return b;
+        self.insert_early_return(is_inf_b, b);
+
+        // if (b == 0) return a == 0 ? a & b : a;
+        let is_zero_a = self.insert_ieee_f32_is_zero(a);
+        let is_zero_b = self.insert_ieee_f32_is_zero(b);
+        let and = self.insert_op_bitwise_and(u32, a, b);
+        let select = self.insert_op_select(u32, is_zero_a, and, a);
+        self.insert_early_return(is_zero_b, select);
+
+        // if (a == 0) return b;
+        self.insert_early_return(is_zero_a, b);
+
+        // bool swap = (a & 0x7FFFFFFF) < (b & 0x7FFFFFFF);
+        let temp = self.get_const_int(32, false, 0x7FFFFFFF);
+        let abs_a = self.insert_op_bitwise_and(u32, a, temp);
+        let abs_b = self.insert_op_bitwise_and(u32, b, temp);
+        let swap = self.insert_op_u_less_than(bool, abs_a, abs_b);
+
+        // uint x = swap ? b : a;
+        // uint y = swap ? a : b;
+        let x = self.insert_op_select(u32, swap, b, a);
+        let y = self.insert_op_select(u32, swap, a, b);
+
+        // uint sigX = Unpack(x, expX) << 6;
+        let (exp_x, sig_x) = self.insert_ieee_f32_unpack(x);
+        let temp = self.get_const_int(32, false, 6);
+        let sig_x = self.insert_op_shift_left_logical(u32, sig_x, temp);
+
+        // uint sigY = ShiftRightJam(Unpack(y, expY) << 6, uint(expX - expY));
+        let (exp_y, sig_y) = self.insert_ieee_f32_unpack(y);
+        let sig_y = self.insert_op_shift_left_logical(u32, sig_y, temp);
+        let sub = self.insert_op_i_sub(i32, exp_x, exp_y);
+        let bitcast = self.insert_op_bitcast(u32, sub);
+        let sig_y = self.insert_op_function_call(u32, ieee_f32_shift_right_jam, &[sig_y, bitcast]);
+
+        // uint sig = ((x ^ y) >> 31) == 0 ? sigX + sigY : sigX - sigY;
+        let xor = self.insert_op_bitwise_xor(u32, x, y);
+        let temp = self.get_const_int(32, false, 31);
+        let shift = self.insert_op_shift_right_logical(u32, xor, temp);
+        let temp = self.get_const_int(32, false, 0);
+        let cmp = self.insert_op_i_equal(bool, shift, temp);
+        let add = self.insert_op_i_add(u32, sig_x, sig_y);
+        let sub = self.insert_op_i_sub(u32, sig_x, sig_y);
+        let sig = self.insert_op_select(u32, cmp, add, sub);
+
+        // if (sig == 0) return 0;
+        let temp = self.get_const_int(32, false, 0);
+        let cmp = self.insert_op_i_equal(bool, sig, temp);
+        self.insert_early_return(cmp, temp);
+
+        // int shift = 30 - findMSB(sig);
+        let glsl_std_450 = self.get_ext_inst_import("GLSL.std.450");
+        let msb = self.insert_op_ext_inst(i32, glsl_std_450, 75, &[sig]);
+        let temp = self.get_const_int(32, true, 30);
+        let shift = self.insert_op_i_sub(i32, temp, msb);
+
+        // return RoundPack(x >> 31, expX - shift, sig << shift);
+        let temp = self.get_const_int(32, false, 31);
+        let sign = self.insert_op_shift_right_logical(u32, x, temp);
+        let exp = self.insert_op_i_sub(i32, exp_x, shift);
+        let sig = self.insert_op_shift_left_logical(u32, sig, shift);
+        let call = self.insert_op_function_call(u32, ieee_f32_round_pack, &[sign, exp, sig]);
+        self.insert_op_return_value(call);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    pub(super) fn ieee_f32_sub(&mut self) -> u32 {
+        let name = "ieee_f32_sub".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let ieee_f32_add = self.ieee_f32_add();
+
+        let u32 = self.get_op_type_int(32, false);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(u32, &[u32, u32]);
+        let id = self.insert_op_function(u32, 0x8, op_type_function);
+        let a = self.insert_op_function_parameter(u32);
+        let b = self.insert_op_function_parameter(u32);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        // A NaN operand is propagated with its sign untouched.
+        // return Add(a, isnan(b) ? b : b ^ 0x80000000);
+        let is_nan = self.insert_ieee_f32_is_nan(b);
+        let temp = self.get_const_int(32, false, 0x80000000);
+        let xor = self.insert_op_bitwise_xor(u32, b, temp);
+        let select = self.insert_op_select(u32, is_nan, b, xor);
+        let call = self.insert_op_function_call(u32, ieee_f32_add, &[a, select]);
+        self.insert_op_return_value(call);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    pub(super) fn ieee_f32_mul(&mut self) -> u32 {
+        let name = "ieee_f32_mul".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let ieee_f32_propagate_nan = self.ieee_f32_propagate_nan();
+        let ieee_f32_round_pack = self.ieee_f32_round_pack();
+
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+        let i32 = self.get_op_type_int(32, true);
+        let u64 = self.get_op_type_int(64, false);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(u32, &[u32, u32]);
+        let id = self.insert_op_function(u32, 0x8, op_type_function);
+        let a = self.insert_op_function_parameter(u32);
+        let b = self.insert_op_function_parameter(u32);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        // uint sign = (a ^ b) >> 31;
+        let xor = self.insert_op_bitwise_xor(u32, a, b);
+        let temp = self.get_const_int(32, false, 31);
+        let sign = self.insert_op_shift_right_logical(u32, xor, temp);
+        let sign_bits = self.insert_op_shift_left_logical(u32, sign, temp);
+
+        // if (isnan(a) || isnan(b)) return PropagateNaN(a, b);
+        let is_nan_a = self.insert_ieee_f32_is_nan(a);
+        let is_nan_b = self.insert_ieee_f32_is_nan(b);
+        let cmp = self.insert_op_logical_or(bool, is_nan_a, is_nan_b);
+        self.insert_early_return_with(cmp, |s| s.insert_op_function_call(u32, ieee_f32_propagate_nan, &[a, b]));
+
+        // if (isinf(a) || isinf(b)) return a == 0 || b == 0 ? 0x7FC00000 : (sign << 31) | 0x7F800000;
+        let is_inf_a = self.insert_ieee_f32_is_inf(a);
+        let is_inf_b = self.insert_ieee_f32_is_inf(b);
+        let is_inf = self.insert_op_logical_or(bool, is_inf_a, is_inf_b);
+        let is_zero_a = self.insert_ieee_f32_is_zero(a);
+        let is_zero_b = self.insert_ieee_f32_is_zero(b);
+        let is_zero = self.insert_op_logical_or(bool, is_zero_a, is_zero_b);
+        let temp = self.get_const_int(32, false, 0x7F800000);
+        let infinity = self.insert_op_bitwise_or(u32, sign_bits, temp);
+        let temp = self.get_const_int(32, false, 0x7FC00000);
+        let select = self.insert_op_select(u32, is_zero, temp, infinity);
+        self.insert_early_return(is_inf, select);
+
+        // if (a == 0 || b == 0) return sign << 31;
+        self.insert_early_return(is_zero, sign_bits);
+
+        // int exp = expA + expB - 0x7F;
+        let (exp_a, sig_a) = self.insert_ieee_f32_unpack(a);
+        let (exp_b, sig_b) = self.insert_ieee_f32_unpack(b);
+        let add = self.insert_op_i_add(i32, exp_a, exp_b);
+        let temp = self.get_const_int(32, true, 0x7F);
+        let exp = self.insert_op_i_sub(i32, add, temp);
+
+        // uint64_t product = uint64_t(sigA << 7) * uint64_t(sigB << 8);
+        let temp = self.get_const_int(32, false, 7);
+        let sig_a = self.insert_op_shift_left_logical(u32, sig_a, temp);
+        let sig_a = self.insert_op_u_convert(u64, sig_a);
+        let temp = self.get_const_int(32, false, 8);
+        let sig_b = self.insert_op_shift_left_logical(u32, sig_b, temp);
+        let sig_b = self.insert_op_u_convert(u64, sig_b);
+        let product = self.insert_op_i_mul(u64, sig_a, sig_b);
+
+        // uint sig = uint(product >> 32) | uint(uint(product) != 0);
+        let temp = self.get_const_int(64, false, 32);
+        let shift = self.insert_op_shift_right_logical(u64, product, temp);
+        let high = self.insert_op_u_convert(u32, shift);
+        let low = self.insert_op_u_convert(u32, product);
+        let temp = self.get_const_int(32, false, 0);
+        let cmp = self.insert_op_i_not_equal(bool, low, temp);
+        let sticky = self.insert_bool_to_uint(cmp);
+        let sig = self.insert_op_bitwise_or(u32, high, sticky);
+
+        // if (sig < 0x40000000) { exp--; sig <<= 1; }
+        let temp = self.get_const_int(32, false, 0x40000000);
+        let cmp = self.insert_op_u_less_than(bool, sig, temp);
+        let shift = self.insert_bool_to_uint(cmp);
+        let bitcast = self.insert_op_bitcast(i32, shift);
+        let exp = self.insert_op_i_sub(i32, exp, bitcast);
+        let sig = self.insert_op_shift_left_logical(u32, sig, shift);
+
+        // return RoundPack(sign, exp, sig);
+        let call = self.insert_op_function_call(u32, ieee_f32_round_pack, &[sign, exp, sig]);
+        self.insert_op_return_value(call);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    pub(super) fn ieee_f32_div(&mut self) -> u32 {
+        let name = "ieee_f32_div".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let ieee_f32_propagate_nan = self.ieee_f32_propagate_nan();
+        let ieee_f32_round_pack = self.ieee_f32_round_pack();
+
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+        let i32 = self.get_op_type_int(32, true);
+        let u64 = self.get_op_type_int(64, false);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(u32, &[u32, u32]);
+        let id = self.insert_op_function(u32, 0x8, op_type_function);
+        let a = self.insert_op_function_parameter(u32);
+        let b = self.insert_op_function_parameter(u32);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        // uint sign = (a ^ b) >> 31;
+        let xor = self.insert_op_bitwise_xor(u32, a, b);
+        let temp = self.get_const_int(32, false, 31);
+        let sign = self.insert_op_shift_right_logical(u32, xor, temp);
+        let sign_bits = self.insert_op_shift_left_logical(u32, sign, temp);
+        let temp = self.get_const_int(32, false, 0x7F800000);
+        let infinity = self.insert_op_bitwise_or(u32, sign_bits, temp);
+        let default_nan = self.get_const_int(32, false, 0x7FC00000);
+
+        // if (isnan(a) || isnan(b)) return PropagateNaN(a, b);
+        let is_nan_a = self.insert_ieee_f32_is_nan(a);
+        let is_nan_b = self.insert_ieee_f32_is_nan(b);
+        let cmp = self.insert_op_logical_or(bool, is_nan_a, is_nan_b);
+        self.insert_early_return_with(cmp, |s| s.insert_op_function_call(u32, ieee_f32_propagate_nan, &[a, b]));
+
+        // if (isinf(a)) return isinf(b) ? 0x7FC00000 : (sign << 31) | 0x7F800000;
+        let is_inf_a = self.insert_ieee_f32_is_inf(a);
+        let is_inf_b = self.insert_ieee_f32_is_inf(b);
+        let select = self.insert_op_select(u32, is_inf_b, default_nan, infinity);
+        self.insert_early_return(is_inf_a, select);
+
+        // if (isinf(b)) return sign << 31;
+        self.insert_early_return(is_inf_b, sign_bits);
+
+        // if (b == 0) return a == 0 ? 0x7FC00000 : (sign << 31) | 0x7F800000;
+        let is_zero_a = self.insert_ieee_f32_is_zero(a);
+        let is_zero_b = self.insert_ieee_f32_is_zero(b);
+        let select = self.insert_op_select(u32, is_zero_a, default_nan, infinity);
+        self.insert_early_return(is_zero_b, select);
+
+        // if (a == 0) return sign << 31;
+        self.insert_early_return(is_zero_a, sign_bits);
+
+        // int exp = expA - expB + 0x7E;
+        let (exp_a, sig_a) = self.insert_ieee_f32_unpack(a);
+        let (exp_b, sig_b) = self.insert_ieee_f32_unpack(b);
+        let sub = self.insert_op_i_sub(i32, exp_a, exp_b);
+        let temp = self.get_const_int(32, true, 0x7E);
+        let exp = self.insert_op_i_add(i32, sub, temp);
+
+        // bool lower = sigA < sigB;
+        // exp -= int(lower);
+        let lower = self.insert_op_u_less_than(bool, sig_a, sig_b);
+        let temp = self.insert_bool_to_uint(lower);
+        let bitcast = self.insert_op_bitcast(i32, temp);
+        let exp = self.insert_op_i_sub(i32, exp, bitcast);
+
+        // uint64_t sig64A = uint64_t(sigA) << (lower ? 31 : 30);
+        let temp = self.get_const_int(32, false, 31);
+        let temp2 = self.get_const_int(32, false, 30);
+        let shift = self.insert_op_select(u32, lower, temp, temp2);
+        let convert = self.insert_op_u_convert(u64, sig_a);
+        let sig64_a = self.insert_op_shift_left_logical(u64, convert, shift);
+
+        // uint64_t sig64Z = sig64A / uint64_t(sigB);
+        let sig64_b = self.insert_op_u_convert(u64, sig_b);
+        let sig64_z = self.insert_op_u_div(u64, sig64_a, sig64_b);
+
+        // uint sig = uint(sig64Z) | uint(sig64Z * uint64_t(sigB) != sig64A);
+        let mul = self.insert_op_i_mul(u64, sig64_z, sig64_b);
+        let cmp = self.insert_op_i_not_equal(bool, mul, sig64_a);
+        let sticky = self.insert_bool_to_uint(cmp);
+        let convert = self.insert_op_u_convert(u32, sig64_z);
+        let sig = self.insert_op_bitwise_or(u32, convert, sticky);
+
+        // return RoundPack(sign, exp, sig);
+        let call = self.insert_op_function_call(u32, ieee_f32_round_pack, &[sign, exp, sig]);
+        self.insert_op_return_value(call);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    pub(super) fn f32_ieee_binary(&mut self, name: &str, ieee_operation: u32) -> u32 {
+        if let Some(id) = self.created.get(name) {
+            return *id;
+        }
+
+        let u32 = self.get_op_type_int(32, false);
+        let f32 = self.get_op_type_float(32);
+        let pf32 = self.get_pointer_type(f32, 7);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(f32, &[pf32, pf32]);
+        let id = self.insert_op_function(f32, 0x8, op_type_function);
+        let lhs = self.insert_op_function_parameter(pf32);
+        let rhs = self.insert_op_function_parameter(pf32);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        // return uintBitsToFloat(Operation(floatBitsToUint(lhs), floatBitsToUint(rhs)));
+        let loaded_lhs = self.insert_op_load(f32, lhs);
+        let bitcast_lhs = self.insert_op_bitcast(u32, loaded_lhs);
+        let loaded_rhs = self.insert_op_load(f32, rhs);
+        let bitcast_rhs = self.insert_op_bitcast(u32, loaded_rhs);
+        let call = self.insert_op_function_call(u32, ieee_operation, &[bitcast_lhs, bitcast_rhs]);
+        let bitcast = self.insert_op_bitcast(f32, call);
+        self.insert_op_return_value(bitcast);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name.to_owned(), id);
+        id
+    }
+
+    pub(super) fn insert_ieee_f32_is_special(&mut self, value: u32) -> u32 {
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+
+        // Zero, subnormal, infinity and NaN have an exponent of either 0 or 0xFF.
+        // (((value >> 23) + 1) & 0xFF) < 2
+        let temp = self.get_const_int(32, false, 23);
+        let shift = self.insert_op_shift_right_logical(u32, value, temp);
+        let temp = self.get_const_int(32, false, 1);
+        let add = self.insert_op_i_add(u32, shift, temp);
+        let temp = self.get_const_int(32, false, 0xFF);
+        let and = self.insert_op_bitwise_and(u32, add, temp);
+        let temp = self.get_const_int(32, false, 2);
+        self.insert_op_u_less_than(bool, and, temp)
+    }
+}
@@ -0,0 +1,245 @@
+use super::Buffer;
+
+// Halves are held in the low 16 bits of a uint. Arithmetic is done in f32 and rounded back, which gives the correctly
+// rounded f16 result because f32 has more than twice the precision of f16.
+impl Buffer {
+    pub(super) fn ieee_f16_to_f32(&mut self) -> u32 {
+        let name = "ieee_f16_to_f32".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+        let i32 = self.get_op_type_int(32, true);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(u32, &[u32]);
+        let id = self.insert_op_function(u32, 0x8, op_type_function);
+        let h = self.insert_op_function_parameter(u32);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        // uint sign = (h & 0x8000) << 16;
+        // uint exp = (h >> 10) & 0x1F;
+        // uint fraction = h & 0x3FF;
+        let temp = self.get_const_int(32, false, 0x8000);
+        let and = self.insert_op_bitwise_and(u32, h, temp);
+        let temp = self.get_const_int(32, false, 16);
+        let sign = self.insert_op_shift_left_logical(u32, and, temp);
+        let temp = self.get_const_int(32, false, 10);
+        let shift = self.insert_op_shift_right_logical(u32, h, temp);
+        let temp = self.get_const_int(32, false, 0x1F);
+        let exp = self.insert_op_bitwise_and(u32, shift, temp);
+        let temp = self.get_const_int(32, false, 0x3FF);
+        let fraction = self.insert_op_bitwise_and(u32, h, temp);
+
+        // if (exp == 0x1F) return sign | 0x7F800000 | (fraction << 13);
+        let temp = self.get_const_int(32, false, 13);
+        let shifted_fraction = self.insert_op_shift_left_logical(u32, fraction, temp);
+        let temp = self.get_const_int(32, false, 0x7F800000);
+        let or = self.insert_op_bitwise_or(u32, sign, temp);
+        let or = self.insert_op_bitwise_or(u32, or, shifted_fraction);
+        let temp = self.get_const_int(32, false, 0x1F);
+        let cmp = self.insert_op_i_equal(bool, exp, temp);
+        self.insert_early_return(cmp, or);
+
+        // if (exp != 0) return sign | ((exp + 112) << 23) | (fraction << 13);
+        let temp = self.get_const_int(32, false, 112);
+        let add = self.insert_op_i_add(u32, exp, temp);
+        let temp = self.get_const_int(32, false, 23);
+        let exp_bits = self.insert_op_shift_left_logical(u32, add, temp);
+        let or = self.insert_op_bitwise_or(u32, sign, exp_bits);
+        let or = self.insert_op_bitwise_or(u32, or, shifted_fraction);
+        let temp = self.get_const_int(32, false, 0);
+        let cmp = self.insert_op_i_not_equal(bool, exp, temp);
+        self.insert_early_return(cmp, or);
+
+        // if (fraction == 0) return sign;
+        let temp = self.get_const_int(32, false, 0);
+        let cmp = self.insert_op_i_equal(bool, fraction, temp);
+        self.insert_early_return(cmp, sign);
+
+        // Subnormals are fraction * 2^-24, which is normal in f32.
+        // int msb = findMSB(fraction);
+        // return sign | (uint(msb + 103) << 23) | ((fraction << (23 - msb)) & 0x7FFFFF);
+        let glsl_std_450 = self.get_ext_inst_import("GLSL.std.450");
+        let msb = self.insert_op_ext_inst(i32, glsl_std_450, 75, &[fraction]);
+        let temp = self.get_const_int(32, true, 103);
+        let add = self.insert_op_i_add(i32, msb, temp);
+        let bitcast = self.insert_op_bitcast(u32, add);
+        let temp = self.get_const_int(32, false, 23);
+        let exp_bits = self.insert_op_shift_left_logical(u32, bitcast, temp);
+        let temp = self.get_const_int(32, true, 23);
+        let sub = self.insert_op_i_sub(i32, temp, msb);
+        let shift = self.insert_op_shift_left_logical(u32, fraction, sub);
+        let temp = self.get_const_int(32, false, 0x7FFFFF);
+        let and = self.insert_op_bitwise_and(u32, shift, temp);
+        let or = self.insert_op_bitwise_or(u32, sign, exp_bits);
+        let or = self.insert_op_bitwise_or(u32, or, and);
+        self.insert_op_return_value(or);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    pub(super) fn ieee_f32_to_f16(&mut self) -> u32 {
+        let name = "ieee_f32_to_f16".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(u32, &[u32]);
+        let id = self.insert_op_function(u32, 0x8, op_type_function);
+        let f = self.insert_op_function_parameter(u32);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        // uint sign = (f >> 16) & 0x8000;
+        // uint abs = f & 0x7FFFFFFF;
+        let temp = self.get_const_int(32, false, 16);
+        let shift = self.insert_op_shift_right_logical(u32, f, temp);
+        let temp = self.get_const_int(32, false, 0x8000);
+        let sign = self.insert_op_bitwise_and(u32, shift, temp);
+        let temp = self.get_const_int(32, false, 0x7FFFFFFF);
+        let abs = self.insert_op_bitwise_and(u32, f, temp);
+
+        // if (abs > 0x7F800000) return sign | 0x7E00 | ((abs >> 13) & 0x3FF);
+        let temp = self.get_const_int(32, false, 13);
+        let shift = self.insert_op_shift_right_logical(u32, abs, temp);
+        let temp = self.get_const_int(32, false, 0x3FF);
+        let and = self.insert_op_bitwise_and(u32, shift, temp);
+        let temp = self.get_const_int(32, false, 0x7E00);
+        let or = self.insert_op_bitwise_or(u32, sign, temp);
+        let or = self.insert_op_bitwise_or(u32, or, and);
+        let temp = self.get_const_int(32, false, 0x7F800000);
+        let cmp = self.insert_op_u_greater_than(bool, abs, temp);
+        self.insert_early_return(cmp, or);
+
+        // if (abs >= 0x47800000) return sign | 0x7C00;
+        let temp = self.get_const_int(32, false, 0x7C00);
+        let or = self.insert_op_bitwise_or(u32, sign, temp);
+        let temp = self.get_const_int(32, false, 0x47800000);
+        let cmp = self.insert_op_u_greater_than_equal(bool, abs, temp);
+        self.insert_early_return(cmp, or);
+
+        // Normal halves keep 10 fraction bits, subnormal halves are counted in units of 2^-24.
+        // uint exp = abs >> 23;
+        // uint sig = (abs & 0x7FFFFF) | 0x800000;
+        // bool normal = exp >= 113;
+        // uint shift = normal ? 13 : min(126 - exp, 31);
+        let temp = self.get_const_int(32, false, 23);
+        let exp = self.insert_op_shift_right_logical(u32, abs, temp);
+        let temp = self.get_const_int(32, false, 0x7FFFFF);
+        let and = self.insert_op_bitwise_and(u32, abs, temp);
+        let temp = self.get_const_int(32, false, 0x800000);
+        let sig = self.insert_op_bitwise_or(u32, and, temp);
+        let temp = self.get_const_int(32, false, 113);
+        let normal = self.insert_op_u_greater_than_equal(bool, exp, temp);
+        let temp = self.get_const_int(32, false, 126);
+        let sub = self.insert_op_i_sub(u32, temp, exp);
+        let temp = self.get_const_int(32, false, 31);
+        let glsl_std_450 = self.get_ext_inst_import("GLSL.std.450");
+        let min = self.insert_op_ext_inst(u32, glsl_std_450, 38, &[sub, temp]);
+        let temp = self.get_const_int(32, false, 13);
+        let shift = self.insert_op_select(u32, normal, temp, min);
+
+        // The hidden bit is carried into the exponent field, so normal halves get exp - 113 added on top.
+        // uint h = (sig >> shift) + (normal ? (exp - 113) << 10 : 0);
+        let shifted = self.insert_op_shift_right_logical(u32, sig, shift);
+        let temp = self.get_const_int(32, false, 113);
+        let sub = self.insert_op_i_sub(u32, exp, temp);
+        let temp = self.get_const_int(32, false, 10);
+        let exp_bits = self.insert_op_shift_left_logical(u32, sub, temp);
+        let temp = self.get_const_int(32, false, 0);
+        let select = self.insert_op_select(u32, normal, exp_bits, temp);
+        let h = self.insert_op_i_add(u32, shifted, select);
+
+        // uint rest = sig & ((1 << shift) - 1);
+        // uint half = 1 << (shift - 1);
+        let one = self.get_const_int(32, false, 1);
+        let shift_one = self.insert_op_shift_left_logical(u32, one, shift);
+        let mask = self.insert_op_i_sub(u32, shift_one, one);
+        let rest = self.insert_op_bitwise_and(u32, sig, mask);
+        let sub = self.insert_op_i_sub(u32, shift, one);
+        let half = self.insert_op_shift_left_logical(u32, one, sub);
+
+        // A carry out of the fraction rounds up to the next exponent or to infinity.
+        // h += uint(rest > half || (rest == half && (h & 1) != 0));
+        let above = self.insert_op_u_greater_than(bool, rest, half);
+        let tie = self.insert_op_i_equal(bool, rest, half);
+        let odd = self.insert_op_bitwise_and(u32, h, one);
+        let temp = self.get_const_int(32, false, 0);
+        let is_odd = self.insert_op_i_not_equal(bool, odd, temp);
+        let tie_odd = self.insert_op_logical_and(bool, tie, is_odd);
+        let round_up = self.insert_op_logical_or(bool, above, tie_odd);
+        let round = self.insert_bool_to_uint(round_up);
+        let h = self.insert_op_i_add(u32, h, round);
+
+        // return sign | h;
+        let or = self.insert_op_bitwise_or(u32, sign, h);
+        self.insert_op_return_value(or);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    fn ieee_f16_binary(&mut self, name: &str, ieee_f32_operation: u32) -> u32 {
+        if let Some(id) = self.created.get(name) {
+            return *id;
+        }
+
+        let ieee_f16_to_f32 = self.ieee_f16_to_f32();
+        let ieee_f32_to_f16 = self.ieee_f32_to_f16();
+
+        let u32 = self.get_op_type_int(32, false);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(u32, &[u32, u32]);
+        let id = self.insert_op_function(u32, 0x8, op_type_function);
+        let a = self.insert_op_function_parameter(u32);
+        let b = self.insert_op_function_parameter(u32);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        // return F32ToF16(Operation(F16ToF32(a), F16ToF32(b)));
+        let call_a = self.insert_op_function_call(u32, ieee_f16_to_f32, &[a]);
+        let call_b = self.insert_op_function_call(u32, ieee_f16_to_f32, &[b]);
+        let call = self.insert_op_function_call(u32, ieee_f32_operation, &[call_a, call_b]);
+        let call = self.insert_op_function_call(u32, ieee_f32_to_f16, &[call]);
+        self.insert_op_return_value(call);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name.to_owned(), id);
+        id
+    }
+
+    pub(super) fn ieee_f16_add(&mut self) -> u32 {
+        let ieee_f32_add = self.ieee_f32_add();
+        self.ieee_f16_binary("ieee_f16_add", ieee_f32_add)
+    }
+
+    pub(super) fn ieee_f16_sub(&mut self) -> u32 {
+        let ieee_f32_sub = self.ieee_f32_sub();
+        self.ieee_f16_binary("ieee_f16_sub", ieee_f32_sub)
+    }
+
+    pub(super) fn ieee_f16_mul(&mut self) -> u32 {
+        let ieee_f32_mul = self.ieee_f32_mul();
+        self.ieee_f16_binary("ieee_f16_mul", ieee_f32_mul)
+    }
+
+    pub(super) fn ieee_f16_div(&mut self) -> u32 {
+        let ieee_f32_div = self.ieee_f32_div();
+        self.ieee_f16_binary("ieee_f16_div", ieee_f32_div)
+    }
+}
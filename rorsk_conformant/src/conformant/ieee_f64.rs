@@ -0,0 +1,646 @@
+use super::Buffer;
+
+impl Buffer {
+    pub(super) fn insert_ieee_u64_shift_right_jam(&mut self, value: u32, distance: u32) -> u32 {
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+        let u64 = self.get_op_type_int(64, false);
+
+        // uint d = min(distance, 63);
+        let temp = self.get_const_int(32, false, 63);
+        let glsl_std_450 = self.get_ext_inst_import("GLSL.std.450");
+        let d = self.insert_op_ext_inst(u32, glsl_std_450, 38, &[distance, temp]);
+
+        // return (value >> d) | uint64_t((value & ((uint64_t(1) << d) - 1)) != 0);
+        let one = self.get_const_int(64, false, 1);
+        let shift = self.insert_op_shift_left_logical(u64, one, d);
+        let mask = self.insert_op_i_sub(u64, shift, one);
+        let and = self.insert_op_bitwise_and(u64, value, mask);
+        let zero = self.get_const_int(64, false, 0);
+        let cmp = self.insert_op_i_not_equal(bool, and, zero);
+        let sticky = self.insert_op_select(u64, cmp, one, zero);
+        let shift = self.insert_op_shift_right_logical(u64, value, d);
+        self.insert_op_bitwise_or(u64, shift, sticky)
+    }
+
+    pub(super) fn insert_ieee_u64_find_msb(&mut self, value: u32) -> u32 {
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+        let i32 = self.get_op_type_int(32, true);
+        let u64 = self.get_op_type_int(64, false);
+
+        // uint high = uint(value >> 32);
+        let temp = self.get_const_int(64, false, 32);
+        let shift = self.insert_op_shift_right_logical(u64, value, temp);
+        let high = self.insert_op_u_convert(u32, shift);
+        let low = self.insert_op_u_convert(u32, value);
+
+        // return high != 0 ? 32 + findMSB(high) : findMSB(uint(value));
+        let glsl_std_450 = self.get_ext_inst_import("GLSL.std.450");
+        let msb_high = self.insert_op_ext_inst(i32, glsl_std_450, 75, &[high]);
+        let temp = self.get_const_int(32, true, 32);
+        let msb_high = self.insert_op_i_add(i32, msb_high, temp);
+        let msb_low = self.insert_op_ext_inst(i32, glsl_std_450, 75, &[low]);
+        let temp = self.get_const_int(32, false, 0);
+        let cmp = self.insert_op_i_not_equal(bool, high, temp);
+        self.insert_op_select(i32, cmp, msb_high, msb_low)
+    }
+
+    fn insert_ieee_f64_is_nan(&mut self, value: u32) -> u32 {
+        let bool = self.get_op_type_bool();
+        let u64 = self.get_op_type_int(64, false);
+
+        // (value & 0x7FFFFFFFFFFFFFFF) > 0x7FF0000000000000
+        let temp = self.get_const_u64(0x7FFFFFFFFFFFFFFF);
+        let and = self.insert_op_bitwise_and(u64, value, temp);
+        let temp = self.get_const_u64(0x7FF0000000000000);
+        self.insert_op_u_greater_than(bool, and, temp)
+    }
+
+    fn insert_ieee_f64_is_inf(&mut self, value: u32) -> u32 {
+        let bool = self.get_op_type_bool();
+        let u64 = self.get_op_type_int(64, false);
+
+        // (value & 0x7FFFFFFFFFFFFFFF) == 0x7FF0000000000000
+        let temp = self.get_const_u64(0x7FFFFFFFFFFFFFFF);
+        let and = self.insert_op_bitwise_and(u64, value, temp);
+        let temp = self.get_const_u64(0x7FF0000000000000);
+        self.insert_op_i_equal(bool, and, temp)
+    }
+
+    fn insert_ieee_f64_is_zero(&mut self, value: u32) -> u32 {
+        let bool = self.get_op_type_bool();
+        let u64 = self.get_op_type_int(64, false);
+
+        // (value & 0x7FFFFFFFFFFFFFFF) == 0
+        let temp = self.get_const_u64(0x7FFFFFFFFFFFFFFF);
+        let and = self.insert_op_bitwise_and(u64, value, temp);
+        let temp = self.get_const_int(64, false, 0);
+        self.insert_op_i_equal(bool, and, temp)
+    }
+
+    fn insert_ieee_f64_sign(&mut self, value: u32) -> u32 {
+        let u32 = self.get_op_type_int(32, false);
+        let u64 = self.get_op_type_int(64, false);
+
+        // uint(value >> 63)
+        let temp = self.get_const_int(64, false, 63);
+        let shift = self.insert_op_shift_right_logical(u64, value, temp);
+        self.insert_op_u_convert(u32, shift)
+    }
+
+    fn insert_ieee_f64_unpack(&mut self, value: u32) -> (u32, u32) {
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+        let i32 = self.get_op_type_int(32, true);
+        let u64 = self.get_op_type_int(64, false);
+
+        // int exp = int((value >> 52) & 0x7FF);
+        let temp = self.get_const_int(64, false, 52);
+        let shift = self.insert_op_shift_right_logical(u64, value, temp);
+        let convert = self.insert_op_u_convert(u32, shift);
+        let temp = self.get_const_int(32, false, 0x7FF);
+        let and = self.insert_op_bitwise_and(u32, convert, temp);
+        let exp = self.insert_op_bitcast(i32, and);
+
+        // uint64_t fraction = value & 0xFFFFFFFFFFFFF;
+        let temp = self.get_const_u64(0xFFFFFFFFFFFFF);
+        let fraction = self.insert_op_bitwise_and(u64, value, temp);
+
+        // Subnormals are normalized so the hidden bit is always at bit 52.
+        // bool subnormal = exp == 0;
+        // int shift = subnormal ? 52 - FindMSB64(fraction) : 0;
+        let temp = self.get_const_int(32, true, 0);
+        let subnormal = self.insert_op_i_equal(bool, exp, temp);
+        let msb = self.insert_ieee_u64_find_msb(fraction);
+        let temp = self.get_const_int(32, true, 52);
+        let sub = self.insert_op_i_sub(i32, temp, msb);
+        let temp = self.get_const_int(32, true, 0);
+        let shift = self.insert_op_select(i32, subnormal, sub, temp);
+
+        // exp = subnormal ? 1 - shift : exp;
+        let temp = self.get_const_int(32, true, 1);
+        let sub = self.insert_op_i_sub(i32, temp, shift);
+        let exp = self.insert_op_select(i32, subnormal, sub, exp);
+
+        // uint64_t sig = subnormal ? fraction << shift : fraction | 0x10000000000000;
+        let shifted = self.insert_op_shift_left_logical(u64, fraction, shift);
+        let temp = self.get_const_u64(0x10000000000000);
+        let or = self.insert_op_bitwise_or(u64, fraction, temp);
+        let sig = self.insert_op_select(u64, subnormal, shifted, or);
+
+        (exp, sig)
+    }
+
+    fn insert_ieee_u64_mul_high(&mut self, a: u32, b: u32) -> (u32, u32) {
+        let u64 = self.get_op_type_int(64, false);
+
+        // The 128 bit product is assembled from 32 bit halves, no partial product can overflow.
+        // uint64_t aH = a >> 32, aL = a & 0xFFFFFFFF, bH = b >> 32, bL = b & 0xFFFFFFFF;
+        let shift = self.get_const_int(64, false, 32);
+        let mask = self.get_const_int(64, false, 0xFFFFFFFF);
+        let a_high = self.insert_op_shift_right_logical(u64, a, shift);
+        let a_low = self.insert_op_bitwise_and(u64, a, mask);
+        let b_high = self.insert_op_shift_right_logical(u64, b, shift);
+        let b_low = self.insert_op_bitwise_and(u64, b, mask);
+
+        // uint64_t ll = aL * bL, lh = aL * bH, hl = aH * bL, hh = aH * bH;
+        let ll = self.insert_op_i_mul(u64, a_low, b_low);
+        let lh = self.insert_op_i_mul(u64, a_low, b_high);
+        let hl = self.insert_op_i_mul(u64, a_high, b_low);
+        let hh = self.insert_op_i_mul(u64, a_high, b_high);
+
+        // uint64_t middle = (ll >> 32) + (lh & 0xFFFFFFFF) + (hl & 0xFFFFFFFF);
+        let ll_high = self.insert_op_shift_right_logical(u64, ll, shift);
+        let lh_low = self.insert_op_bitwise_and(u64, lh, mask);
+        let hl_low = self.insert_op_bitwise_and(u64, hl, mask);
+        let add = self.insert_op_i_add(u64, ll_high, lh_low);
+        let middle = self.insert_op_i_add(u64, add, hl_low);
+
+        // uint64_t high = hh + (lh >> 32) + (hl >> 32) + (middle >> 32);
+        let lh_high = self.insert_op_shift_right_logical(u64, lh, shift);
+        let hl_high = self.insert_op_shift_right_logical(u64, hl, shift);
+        let middle_high = self.insert_op_shift_right_logical(u64, middle, shift);
+        let add = self.insert_op_i_add(u64, hh, lh_high);
+        let add = self.insert_op_i_add(u64, add, hl_high);
+        let high = self.insert_op_i_add(u64, add, middle_high);
+
+        // uint64_t low = (middle << 32) | (ll & 0xFFFFFFFF);
+        let middle_low = self.insert_op_shift_left_logical(u64, middle, shift);
+        let ll_low = self.insert_op_bitwise_and(u64, ll, mask);
+        let low = self.insert_op_bitwise_or(u64, middle_low, ll_low);
+
+        (high, low)
+    }
+
+    fn ieee_f64_propagate_nan(&mut self) -> u32 {
+        let name = "ieee_f64_propagate_nan".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let u64 = self.get_op_type_int(64, false);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(u64, &[u64, u64]);
+        let id = self.insert_op_function(u64, 0x8, op_type_function);
+        let a = self.insert_op_function_parameter(u64);
+        let b = self.insert_op_function_parameter(u64);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        // return (isnan(a) ? a : b) | 0x8000000000000;
+        let is_nan = self.insert_ieee_f64_is_nan(a);
+        let select = self.insert_op_select(u64, is_nan, a, b);
+        let temp = self.get_const_u64(0x8000000000000);
+        let or = self.insert_op_bitwise_or(u64, select, temp);
+        self.insert_op_return_value(or);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    fn ieee_f64_round_pack(&mut self) -> u32 {
+        let name = "ieee_f64_round_pack".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+        let i32 = self.get_op_type_int(32, true);
+        let u64 = self.get_op_type_int(64, false);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(u64, &[u32, i32, u64]);
+        let id = self.insert_op_function(u64, 0x8, op_type_function);
+        let sign = self.insert_op_function_parameter(u32);
+        let exp = self.insert_op_function_parameter(i32);
+        let sig = self.insert_op_function_parameter(u64);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        // if (exp > 0x7FD) return (uint64_t(sign) << 63) | 0x7FF0000000000000;
+        let convert = self.insert_op_u_convert(u64, sign);
+        let temp = self.get_const_int(64, false, 63);
+        let sign_bits = self.insert_op_shift_left_logical(u64, convert, temp);
+        let temp = self.get_const_u64(0x7FF0000000000000);
+        let infinity = self.insert_op_bitwise_or(u64, sign_bits, temp);
+        let temp = self.get_const_int(32, true, 0x7FD);
+        let cmp = self.insert_op_sless_than(bool, temp, exp);
+        self.insert_early_return(cmp, infinity);
+
+        // if (exp < 0) { sig = ShiftRightJam64(sig, uint(-exp)); exp = 0; }
+        let temp = self.get_const_int(32, true, 0);
+        let underflow = self.insert_op_sless_than(bool, exp, temp);
+        let negate = self.insert_op_s_negate(i32, exp);
+        let bitcast = self.insert_op_bitcast(u32, negate);
+        let jam = self.insert_ieee_u64_shift_right_jam(sig, bitcast);
+        let sig = self.insert_op_select(u64, underflow, jam, sig);
+        let exp = self.insert_op_select(i32, underflow, temp, exp);
+
+        // uint64_t roundBits = sig & 0x3FF;
+        let temp = self.get_const_int(64, false, 0x3FF);
+        let round_bits = self.insert_op_bitwise_and(u64, sig, temp);
+
+        // sig = (sig + 0x200) >> 10;
+        let temp = self.get_const_int(64, false, 0x200);
+        let add = self.insert_op_i_add(u64, sig, temp);
+        let temp = self.get_const_int(64, false, 10);
+        let shift = self.insert_op_shift_right_logical(u64, add, temp);
+
+        // sig &= ~uint64_t(roundBits == 0x200);
+        let temp = self.get_const_int(64, false, 0x200);
+        let cmp = self.insert_op_i_equal(bool, round_bits, temp);
+        let temp = self.get_const_u64(0xFFFFFFFFFFFFFFFE);
+        let temp2 = self.get_const_u64(0xFFFFFFFFFFFFFFFF);
+        let mask = self.insert_op_select(u64, cmp, temp, temp2);
+        let rounded = self.insert_op_bitwise_and(u64, shift, mask);
+
+        // Like in the f32 version a carry out of the significand increments the exponent.
+        // return (uint64_t(sign) << 63) + (uint64_t(exp) << 52) + sig;
+        let bitcast = self.insert_op_bitcast(u32, exp);
+        let convert = self.insert_op_u_convert(u64, bitcast);
+        let temp = self.get_const_int(64, false, 52);
+        let exp_bits = self.insert_op_shift_left_logical(u64, convert, temp);
+        let add = self.insert_op_i_add(u64, sign_bits, exp_bits);
+        let add = self.insert_op_i_add(u64, add, rounded);
+        self.insert_op_return_value(add);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    fn insert_ieee_f64_binary_function(&mut self) -> (u32, u32, u32) {
+        let u64 = self.get_op_type_int(64, false);
+
+        // OpFunction
+        let op_type_function = self.get_type_function(u64, &[u64, u64]);
+        let id = self.insert_op_function(u64, 0x8, op_type_function);
+        let a = self.insert_op_function_parameter(u64);
+        let b = self.insert_op_function_parameter(u64);
+        let temp = self.get_next_id();
+        self.insert_op_label(temp);
+
+        (id, a, b)
+    }
+
+    pub(super) fn ieee_f64_add(&mut self) -> u32 {
+        let name = "ieee_f64_add".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let ieee_f64_propagate_nan = self.ieee_f64_propagate_nan();
+        let ieee_f64_round_pack = self.ieee_f64_round_pack();
+
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+        let i32 = self.get_op_type_int(32, true);
+        let u64 = self.get_op_type_int(64, false);
+
+        let (id, a, b) = self.insert_ieee_f64_binary_function();
+
+        // if (isnan(a) || isnan(b)) return PropagateNaN(a, b);
+        let is_nan_a = self.insert_ieee_f64_is_nan(a);
+        let is_nan_b = self.insert_ieee_f64_is_nan(b);
+        let cmp = self.insert_op_logical_or(bool, is_nan_a, is_nan_b);
+        self.insert_early_return_with(cmp, |s| s.insert_op_function_call(u64, ieee_f64_propagate_nan, &[a, b]));
+
+        // if (isinf(a)) return isinf(b) && ((a ^ b) >> 63) != 0 ? 0x7FF8000000000000 : a;
+        let is_inf_a = self.insert_ieee_f64_is_inf(a);
+        let is_inf_b = self.insert_ieee_f64_is_inf(b);
+        let xor = self.insert_op_bitwise_xor(u64, a, b);
+        let temp = self.get_const_u64(0x8000000000000000);
+        let cmp = self.insert_op_u_less_than(bool, xor, temp);
+        let temp = self.get_const_u64(0x7FF8000000000000);
+        let select = self.insert_op_select(u64, cmp, a, temp);
+        let select = self.insert_op_select(u64, is_inf_b, select, a);
+        self.insert_early_return(is_inf_a, select);
+
+        // if (isinf(b)) return b;
+        self.insert_early_return(is_inf_b, b);
+
+        // if (b == 0) return a == 0 ? a & b : a;
+        let is_zero_a = self.insert_ieee_f64_is_zero(a);
+        let is_zero_b = self.insert_ieee_f64_is_zero(b);
+        let and = self.insert_op_bitwise_and(u64, a, b);
+        let select = self.insert_op_select(u64, is_zero_a, and, a);
+        self.insert_early_return(is_zero_b, select);
+
+        // if (a == 0) return b;
+        self.insert_early_return(is_zero_a, b);
+
+        // bool swap = (a & 0x7FFFFFFFFFFFFFFF) < (b & 0x7FFFFFFFFFFFFFFF);
+        let temp = self.get_const_u64(0x7FFFFFFFFFFFFFFF);
+        let abs_a = self.insert_op_bitwise_and(u64, a, temp);
+        let abs_b = self.insert_op_bitwise_and(u64, b, temp);
+        let swap = self.insert_op_u_less_than(bool, abs_a, abs_b);
+
+        // uint64_t x = swap ? b : a;
+        // uint64_t y = swap ? a : b;
+        let x = self.insert_op_select(u64, swap, b, a);
+        let y = self.insert_op_select(u64, swap, a, b);
+
+        // uint64_t sigX = Unpack(x, expX) << 9;
+        let (exp_x, sig_x) = self.insert_ieee_f64_unpack(x);
+        let temp = self.get_const_int(64, false, 9);
+        let sig_x = self.insert_op_shift_left_logical(u64, sig_x, temp);
+
+        // uint64_t sigY = ShiftRightJam64(Unpack(y, expY) << 9, uint(expX - expY));
+        let (exp_y, sig_y) = self.insert_ieee_f64_unpack(y);
+        let sig_y = self.insert_op_shift_left_logical(u64, sig_y, temp);
+        let sub = self.insert_op_i_sub(i32, exp_x, exp_y);
+        let bitcast = self.insert_op_bitcast(u32, sub);
+        let sig_y = self.insert_ieee_u64_shift_right_jam(sig_y, bitcast);
+
+        // uint64_t sig = ((x ^ y) >> 63) == 0 ? sigX + sigY : sigX - sigY;
+        let xor = self.insert_op_bitwise_xor(u64, x, y);
+        let temp = self.get_const_u64(0x8000000000000000);
+        let cmp = self.insert_op_u_less_than(bool, xor, temp);
+        let add = self.insert_op_i_add(u64, sig_x, sig_y);
+        let sub = self.insert_op_i_sub(u64, sig_x, sig_y);
+        let sig = self.insert_op_select(u64, cmp, add, sub);
+
+        // if (sig == 0) return 0;
+        let temp = self.get_const_int(64, false, 0);
+        let cmp = self.insert_op_i_equal(bool, sig, temp);
+        self.insert_early_return(cmp, temp);
+
+        // int shift = 62 - FindMSB64(sig);
+        let msb = self.insert_ieee_u64_find_msb(sig);
+        let temp = self.get_const_int(32, true, 62);
+        let shift = self.insert_op_i_sub(i32, temp, msb);
+
+        // return RoundPack64(x >> 63, expX - shift, sig << shift);
+        let sign = self.insert_ieee_f64_sign(x);
+        let exp = self.insert_op_i_sub(i32, exp_x, shift);
+        let sig = self.insert_op_shift_left_logical(u64, sig, shift);
+        let call = self.insert_op_function_call(u64, ieee_f64_round_pack, &[sign, exp, sig]);
+        self.insert_op_return_value(call);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    pub(super) fn ieee_f64_sub(&mut self) -> u32 {
+        let name = "ieee_f64_sub".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let ieee_f64_add = self.ieee_f64_add();
+
+        let u64 = self.get_op_type_int(64, false);
+
+        let (id, a, b) = self.insert_ieee_f64_binary_function();
+
+        // A NaN operand is propagated with its sign untouched.
+        // return Add64(a, isnan(b) ? b : b ^ 0x8000000000000000);
+        let is_nan = self.insert_ieee_f64_is_nan(b);
+        let temp = self.get_const_u64(0x8000000000000000);
+        let xor = self.insert_op_bitwise_xor(u64, b, temp);
+        let select = self.insert_op_select(u64, is_nan, b, xor);
+        let call = self.insert_op_function_call(u64, ieee_f64_add, &[a, select]);
+        self.insert_op_return_value(call);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    pub(super) fn ieee_f64_mul(&mut self) -> u32 {
+        let name = "ieee_f64_mul".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let ieee_f64_propagate_nan = self.ieee_f64_propagate_nan();
+        let ieee_f64_round_pack = self.ieee_f64_round_pack();
+
+        let bool = self.get_op_type_bool();
+        let i32 = self.get_op_type_int(32, true);
+        let u64 = self.get_op_type_int(64, false);
+
+        let (id, a, b) = self.insert_ieee_f64_binary_function();
+
+        // uint sign = uint((a ^ b) >> 63);
+        let xor = self.insert_op_bitwise_xor(u64, a, b);
+        let sign = self.insert_ieee_f64_sign(xor);
+        let temp = self.get_const_u64(0x8000000000000000);
+        let sign_bits = self.insert_op_bitwise_and(u64, xor, temp);
+
+        // if (isnan(a) || isnan(b)) return PropagateNaN(a, b);
+        let is_nan_a = self.insert_ieee_f64_is_nan(a);
+        let is_nan_b = self.insert_ieee_f64_is_nan(b);
+        let cmp = self.insert_op_logical_or(bool, is_nan_a, is_nan_b);
+        self.insert_early_return_with(cmp, |s| s.insert_op_function_call(u64, ieee_f64_propagate_nan, &[a, b]));
+
+        // if (isinf(a) || isinf(b)) return a == 0 || b == 0 ? 0x7FF8000000000000 : signBits | 0x7FF0000000000000;
+        let is_inf_a = self.insert_ieee_f64_is_inf(a);
+        let is_inf_b = self.insert_ieee_f64_is_inf(b);
+        let is_inf = self.insert_op_logical_or(bool, is_inf_a, is_inf_b);
+        let is_zero_a = self.insert_ieee_f64_is_zero(a);
+        let is_zero_b = self.insert_ieee_f64_is_zero(b);
+        let is_zero = self.insert_op_logical_or(bool, is_zero_a, is_zero_b);
+        let temp = self.get_const_u64(0x7FF0000000000000);
+        let infinity = self.insert_op_bitwise_or(u64, sign_bits, temp);
+        let temp = self.get_const_u64(0x7FF8000000000000);
+        let select = self.insert_op_select(u64, is_zero, temp, infinity);
+        self.insert_early_return(is_inf, select);
+
+        // if (a == 0 || b == 0) return signBits;
+        self.insert_early_return(is_zero, sign_bits);
+
+        // int exp = expA + expB - 0x3FF;
+        let (exp_a, sig_a) = self.insert_ieee_f64_unpack(a);
+        let (exp_b, sig_b) = self.insert_ieee_f64_unpack(b);
+        let add = self.insert_op_i_add(i32, exp_a, exp_b);
+        let temp = self.get_const_int(32, true, 0x3FF);
+        let exp = self.insert_op_i_sub(i32, add, temp);
+
+        // uint64_t high, low = Mul128(sigA << 10, sigB << 11);
+        let temp = self.get_const_int(64, false, 10);
+        let sig_a = self.insert_op_shift_left_logical(u64, sig_a, temp);
+        let temp = self.get_const_int(64, false, 11);
+        let sig_b = self.insert_op_shift_left_logical(u64, sig_b, temp);
+        let (high, low) = self.insert_ieee_u64_mul_high(sig_a, sig_b);
+
+        // uint64_t sig = high | uint64_t(low != 0);
+        let zero = self.get_const_int(64, false, 0);
+        let one = self.get_const_int(64, false, 1);
+        let cmp = self.insert_op_i_not_equal(bool, low, zero);
+        let sticky = self.insert_op_select(u64, cmp, one, zero);
+        let sig = self.insert_op_bitwise_or(u64, high, sticky);
+
+        // if (sig < 0x4000000000000000) { exp--; sig <<= 1; }
+        let temp = self.get_const_u64(0x4000000000000000);
+        let cmp = self.insert_op_u_less_than(bool, sig, temp);
+        let shift = self.insert_bool_to_uint(cmp);
+        let bitcast = self.insert_op_bitcast(i32, shift);
+        let exp = self.insert_op_i_sub(i32, exp, bitcast);
+        let sig = self.insert_op_shift_left_logical(u64, sig, shift);
+
+        // return RoundPack64(sign, exp, sig);
+        let call = self.insert_op_function_call(u64, ieee_f64_round_pack, &[sign, exp, sig]);
+        self.insert_op_return_value(call);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+
+    pub(super) fn ieee_f64_div(&mut self) -> u32 {
+        let name = "ieee_f64_div".to_owned();
+        if let Some(id) = self.created.get(&name) {
+            return *id;
+        }
+
+        let ieee_f64_propagate_nan = self.ieee_f64_propagate_nan();
+        let ieee_f64_round_pack = self.ieee_f64_round_pack();
+
+        let bool = self.get_op_type_bool();
+        let u32 = self.get_op_type_int(32, false);
+        let pu32 = self.get_pointer_type(u32, 7);
+        let i32 = self.get_op_type_int(32, true);
+        let u64 = self.get_op_type_int(64, false);
+        let pu64 = self.get_pointer_type(u64, 7);
+
+        let (id, a, b) = self.insert_ieee_f64_binary_function();
+
+        let rem = self.insert_op_variable(pu64, 7);
+        let quotient = self.insert_op_variable(pu64, 7);
+        let i = self.insert_op_variable(pu32, 7);
+
+        // uint sign = uint((a ^ b) >> 63);
+        let xor = self.insert_op_bitwise_xor(u64, a, b);
+        let sign = self.insert_ieee_f64_sign(xor);
+        let temp = self.get_const_u64(0x8000000000000000);
+        let sign_bits = self.insert_op_bitwise_and(u64, xor, temp);
+        let temp = self.get_const_u64(0x7FF0000000000000);
+        let infinity = self.insert_op_bitwise_or(u64, sign_bits, temp);
+        let default_nan = self.get_const_u64(0x7FF8000000000000);
+
+        // if (isnan(a) || isnan(b)) return PropagateNaN(a, b);
+        let is_nan_a = self.insert_ieee_f64_is_nan(a);
+        let is_nan_b = self.insert_ieee_f64_is_nan(b);
+        let cmp = self.insert_op_logical_or(bool, is_nan_a, is_nan_b);
+        self.insert_early_return_with(cmp, |s| s.insert_op_function_call(u64, ieee_f64_propagate_nan, &[a, b]));
+
+        // if (isinf(a)) return isinf(b) ? 0x7FF8000000000000 : signBits | 0x7FF0000000000000;
+        let is_inf_a = self.insert_ieee_f64_is_inf(a);
+        let is_inf_b = self.insert_ieee_f64_is_inf(b);
+        let select = self.insert_op_select(u64, is_inf_b, default_nan, infinity);
+        self.insert_early_return(is_inf_a, select);
+
+        // if (isinf(b)) return signBits;
+        self.insert_early_return(is_inf_b, sign_bits);
+
+        // if (b == 0) return a == 0 ? 0x7FF8000000000000 : signBits | 0x7FF0000000000000;
+        let is_zero_a = self.insert_ieee_f64_is_zero(a);
+        let is_zero_b = self.insert_ieee_f64_is_zero(b);
+        let select = self.insert_op_select(u64, is_zero_a, default_nan, infinity);
+        self.insert_early_return(is_zero_b, select);
+
+        // if (a == 0) return signBits;
+        self.insert_early_return(is_zero_a, sign_bits);
+
+        // int exp = expA - expB + 0x3FE;
+        let (exp_a, sig_a) = self.insert_ieee_f64_unpack(a);
+        let (exp_b, sig_b) = self.insert_ieee_f64_unpack(b);
+        let sub = self.insert_op_i_sub(i32, exp_a, exp_b);
+        let temp = self.get_const_int(32, true, 0x3FE);
+        let exp = self.insert_op_i_add(i32, sub, temp);
+
+        // bool lower = sigA < sigB;
+        // exp -= int(lower);
+        let lower = self.insert_op_u_less_than(bool, sig_a, sig_b);
+        let shift = self.insert_bool_to_uint(lower);
+        let bitcast = self.insert_op_bitcast(i32, shift);
+        let exp = self.insert_op_i_sub(i32, exp, bitcast);
+
+        // There is no 128 bit division, so the 63 quotient bits are computed one at a time.
+        // rem = sigA << uint(lower);
+        // quotient = 0;
+        let shifted = self.insert_op_shift_left_logical(u64, sig_a, shift);
+        self.insert_op_store(rem, shifted);
+        let temp = self.get_const_int(64, false, 0);
+        self.insert_op_store(quotient, temp);
+        let temp = self.get_const_int(32, false, 0);
+        self.insert_op_store(i, temp);
+
+        // for (uint i = 0; i < 63; i++) {
+        let loop_start = self.get_next_id();
+        self.insert_op_branch(loop_start);
+        self.insert_op_label(loop_start);
+
+        let loop_end = self.get_next_id();
+        let continue_target = self.get_next_id();
+        self.insert_op_loop_merge(loop_end, continue_target, 0);
+
+        let body = self.get_next_id();
+        self.insert_op_branch(body);
+        self.insert_op_label(body);
+
+        let loaded_i = self.insert_op_load(u32, i);
+        let temp = self.get_const_int(32, false, 63);
+        let cmp = self.insert_op_u_less_than(bool, loaded_i, temp);
+        let true_label = self.get_next_id();
+        self.insert_op_branch_conditional(cmp, true_label, loop_end);
+
+        // bool lower = rem < sigB;
+        // quotient = (quotient << 1) | uint64_t(!lower);
+        // rem = (lower ? rem : rem - sigB) << 1;
+        self.insert_op_label(true_label);
+        let loaded_rem = self.insert_op_load(u64, rem);
+        let lower = self.insert_op_u_less_than(bool, loaded_rem, sig_b);
+        let zero = self.get_const_int(64, false, 0);
+        let one = self.get_const_int(64, false, 1);
+        let bit = self.insert_op_select(u64, lower, zero, one);
+        let loaded_quotient = self.insert_op_load(u64, quotient);
+        let temp = self.get_const_int(32, false, 1);
+        let shift = self.insert_op_shift_left_logical(u64, loaded_quotient, temp);
+        let or = self.insert_op_bitwise_or(u64, shift, bit);
+        self.insert_op_store(quotient, or);
+        let sub = self.insert_op_i_sub(u64, loaded_rem, sig_b);
+        let select = self.insert_op_select(u64, lower, loaded_rem, sub);
+        let shift = self.insert_op_shift_left_logical(u64, select, temp);
+        self.insert_op_store(rem, shift);
+
+        // Loop continuation
+        self.insert_op_branch(continue_target);
+        self.insert_op_label(continue_target);
+        let loaded_i = self.insert_op_load(u32, i);
+        let temp = self.get_const_int(32, false, 1);
+        let add = self.insert_op_i_add(u32, loaded_i, temp);
+        self.insert_op_store(i, add);
+        self.insert_op_branch(loop_start);
+
+        // uint64_t sig = quotient | uint64_t(rem != 0);
+        self.insert_op_label(loop_end);
+        let loaded_quotient = self.insert_op_load(u64, quotient);
+        let loaded_rem = self.insert_op_load(u64, rem);
+        let zero = self.get_const_int(64, false, 0);
+        let one = self.get_const_int(64, false, 1);
+        let cmp = self.insert_op_i_not_equal(bool, loaded_rem, zero);
+        let sticky = self.insert_op_select(u64, cmp, one, zero);
+        let sig = self.insert_op_bitwise_or(u64, loaded_quotient, sticky);
+
+        // return RoundPack64(sign, exp, sig);
+        let call = self.insert_op_function_call(u64, ieee_f64_round_pack, &[sign, exp, sig]);
+        self.insert_op_return_value(call);
+
+        self.insert_op_function_end();
+
+        self.created.insert(name, id);
+        id
+    }
+}
@@ -0,0 +1,645 @@
+use std::collections::{HashMap, HashSet};
+
+use rorsk_core::f16::f16_to_f32;
+
+use crate::spirv::{result_layout, Function, Instruction, Module};
+
+/// Names ids after their OpName and after the keys of the `created` map of the conformant rewriter, like
+/// `%sf32_div` or `%float_32`. Ids without a name are printed as numbers.
+pub fn names(module: &Module, created: &HashMap<String, u32>) -> HashMap<u32, String> {
+    let mut sorted_created = created.iter().collect::<Vec<_>>();
+    sorted_created.sort();
+
+    let debug_names = module.debug.iter()
+        // OpName
+        .filter(|i| i.opcode == 5)
+        .map(|i| (i.operands[0], i.string(1)));
+    let candidates = debug_names.chain(sorted_created.into_iter().map(|(name, id)| (*id, name.clone())));
+
+    let mut names = HashMap::new();
+    let mut used = HashSet::new();
+    for (id, name) in candidates {
+        if names.contains_key(&id) {
+            continue;
+        }
+
+        let name = sanitize(&name);
+        if name.is_empty() {
+            continue;
+        }
+
+        let name = if used.contains(&name) { format!("{name}_{id}") } else { name };
+        used.insert(name.clone());
+        names.insert(id, name);
+    }
+
+    names
+}
+
+/// Returns the name with every run of characters other than ASCII letters and digits replaced by `_`.
+fn sanitize(name: &str) -> String {
+    let mut sanitized = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            sanitized.push(c);
+        } else if !sanitized.is_empty() && !sanitized.ends_with('_') {
+            sanitized.push('_');
+        }
+    }
+
+    sanitized.trim_end_matches('_').to_owned()
+}
+
+/// Returns the module in the text format of `spirv-dis`, one instruction per line.
+pub fn disassemble(module: &Module, names: &HashMap<u32, String>) -> String {
+    let disassembler = Disassembler::new(module, names);
+    let mut text = disassembler.globals();
+    for function in &module.functions {
+        text.push_str(&disassembler.function(function));
+    }
+
+    text
+}
+
+/// Returns a line diff of the disassembly of the original and the rewritten module, in which removed lines start with
+/// `-` and added lines with `+`. It begins with a summary of functions injected by the rewrite.
+pub fn diff(original: &Module, rewritten: &Module, names: &HashMap<u32, String>) -> String {
+    let before = Disassembler::new(original, names);
+    let after = Disassembler::new(rewritten, names);
+    let function_id = |function: &Function| function.definition.operands[1];
+
+    // Functions are compared with their counterparts one by one, so the cost depends on the size of the changes of
+    // every function instead of the size of the module.
+    let mut lines = diff_lines(&before.globals(), &after.globals());
+    for function in &rewritten.functions {
+        let original_function = original.functions.iter().find(|f| function_id(f) == function_id(function));
+        let a = original_function.map_or(String::new(), |f| before.function(f));
+        lines.extend(diff_lines(&a, &after.function(function)));
+    }
+    for function in &original.functions {
+        if !rewritten.functions.iter().any(|f| function_id(f) == function_id(function)) {
+            lines.extend(diff_lines(&before.function(function), ""));
+        }
+    }
+
+    let original_functions = original.functions.iter().map(function_id).collect::<HashSet<_>>();
+    let injected = rewritten.functions.iter()
+        .map(function_id)
+        .filter(|id| !original_functions.contains(id))
+        .map(|id| id_name(names, id))
+        .collect::<Vec<_>>();
+    let removed = lines.iter().filter(|l| l.starts_with('-')).count();
+    let added = lines.iter().filter(|l| l.starts_with('+')).count();
+
+    let mut text = format!(
+        "; Injected functions: {}\n; Removed lines: {removed}, added lines: {added}\n",
+        if injected.is_empty() { "none".to_owned() } else { injected.join(", ") }
+    );
+    for line in lines {
+        text.push_str(&line);
+        text.push('\n');
+    }
+
+    text
+}
+
+/// Returns the shortest line diff with the greedy algorithm of Myers, whose memory grows with the square of the number
+/// of changed lines only.
+fn diff_lines(before: &str, after: &str) -> Vec<String> {
+    let a = before.lines().collect::<Vec<_>>();
+    let b = after.lines().collect::<Vec<_>>();
+    let (n, m) = (a.len() as isize, b.len() as isize);
+
+    // `v[k]` is the furthest `x` reached on the diagonal `k = x - y`, `trace[d]` holds the diagonals -d..=d of `v`
+    // after `d` edits.
+    let max = n + m;
+    let mut v = vec![0isize; 2 * max as usize + 2];
+    let index = |k: isize| (k + max) as usize;
+    let mut trace = Vec::new();
+    'search: for d in 0..=max {
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) {
+                v[index(k + 1)]
+            } else {
+                v[index(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[index(k)] = x;
+
+            if x >= n && y >= m {
+                trace.push(v[index(-d)..=index(d)].to_vec());
+                break 'search;
+            }
+        }
+        trace.push(v[index(-d)..=index(d)].to_vec());
+    }
+
+    // Walks the edits back from the end, every step is a deletion or an insertion followed by equal lines.
+    let mut lines = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for d in (0..trace.len() as isize).rev() {
+        let k = x - y;
+        let (previous_x, previous_y) = if d == 0 {
+            (0, 0)
+        } else {
+            let previous = &trace[d as usize - 1];
+            let at = |k: isize| previous[(k + d - 1) as usize];
+            let previous_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) { k + 1 } else { k - 1 };
+            (at(previous_k), at(previous_k) - previous_k)
+        };
+
+        while x > previous_x && y > previous_y {
+            x -= 1;
+            y -= 1;
+            lines.push(format!("  {}", a[x as usize]));
+        }
+        if d > 0 {
+            if x == previous_x {
+                y -= 1;
+                lines.push(format!("+ {}", b[y as usize]));
+            } else {
+                x -= 1;
+                lines.push(format!("- {}", a[x as usize]));
+            }
+        }
+    }
+
+    lines.reverse();
+    lines
+}
+
+fn id_name(names: &HashMap<u32, String>, id: u32) -> String {
+    match names.get(&id) {
+        Some(name) => format!("%{name}"),
+        None => format!("%{id}"),
+    }
+}
+
+struct Disassembler<'a> {
+    module: &'a Module,
+    names: &'a HashMap<u32, String>,
+    glsl_std_450: Option<u32>,
+}
+
+impl<'a> Disassembler<'a> {
+    fn new(module: &'a Module, names: &'a HashMap<u32, String>) -> Self {
+        let glsl_std_450 = module.ext_inst_imports.iter()
+            .find(|i| i.string(1) == "GLSL.std.450")
+            .map(|i| i.operands[0]);
+        Disassembler { module, names, glsl_std_450 }
+    }
+
+    /// Returns the header and every instruction outside of functions.
+    fn globals(&self) -> String {
+        let header = &self.module.header;
+        let mut text = format!(
+            "; SPIR-V\n; Version: {}.{}\n; Generator: {:#010x}\n; Bound: {}\n; Schema: {}\n",
+            (header.version >> 16) & 0xFF, (header.version >> 8) & 0xFF, header.generator, header.bound, header.schema
+        );
+
+        let module = self.module;
+        let sections = module.capabilities.iter()
+            .chain(&module.extensions)
+            .chain(&module.ext_inst_imports)
+            .chain(&module.memory_model)
+            .chain(&module.entry_points)
+            .chain(&module.execution_modes)
+            .chain(&module.debug)
+            .chain(&module.annotations)
+            .chain(&module.types_global_values);
+        for instruction in sections {
+            self.line(&mut text, instruction);
+        }
+
+        text
+    }
+
+    /// Returns the function preceded by an empty line.
+    fn function(&self, function: &Function) -> String {
+        let mut text = String::from("\n");
+        self.line(&mut text, &function.definition);
+        for parameter in &function.parameters {
+            self.line(&mut text, parameter);
+        }
+        for block in &function.blocks {
+            // OpLabel
+            self.line(&mut text, &Instruction::new(248, &[block.label]));
+            for instruction in &block.instructions {
+                self.line(&mut text, instruction);
+            }
+        }
+        // OpFunctionEnd
+        self.line(&mut text, &Instruction::new(56, &[]));
+
+        text
+    }
+
+    fn line(&self, text: &mut String, instruction: &Instruction) {
+        let (has_type, has_result) = result_layout(instruction.opcode);
+        let result = if has_result { instruction.operands.get(has_type as usize).copied() } else { None };
+
+        // Results are right aligned, so opcodes start in the same column.
+        match result {
+            Some(id) => text.push_str(&format!("{:>24} = ", id_name(self.names, id))),
+            None => text.push_str(&" ".repeat(27)),
+        }
+
+        let (name, _) = opcode_info(instruction.opcode);
+        match name {
+            Some(name) => text.push_str(name),
+            None => text.push_str(&format!("Op{}", instruction.opcode)),
+        }
+
+        for operand in self.operands(instruction) {
+            text.push(' ');
+            text.push_str(&operand);
+        }
+        text.push('\n');
+    }
+
+    fn operands(&self, instruction: &Instruction) -> Vec<String> {
+        let o = &instruction.operands;
+        let (has_type, has_result) = result_layout(instruction.opcode);
+        let mut operands = Vec::new();
+        if has_type {
+            operands.push(id_name(self.names, o[0]));
+        }
+
+        // OpConstant
+        if instruction.opcode == 43 {
+            if let Some(value) = self.constant(instruction) {
+                operands.push(value);
+                return operands;
+            }
+        }
+
+        let (_, layout) = opcode_info(instruction.opcode);
+        let layout = layout.as_bytes();
+        let mut kind_index = 0;
+        let mut kind = b'l';
+        let mut index = has_type as usize + has_result as usize;
+        while index < o.len() {
+            // Operands past the layout are printed as literals.
+            match layout.get(kind_index) {
+                Some(b'*') => {},
+                Some(next) => {
+                    kind = *next;
+                    kind_index += 1;
+                },
+                None => kind = b'l',
+            }
+
+            match kind {
+                b'i' => operands.push(id_name(self.names, o[index])),
+                b's' => {
+                    let string = instruction.string(index);
+                    index += string.len() / 4;
+                    operands.push(format!("{string:?}"));
+                },
+                // BuiltIn decorations are followed by the built-in variable.
+                b'D' if o[index] == 11 && index + 1 < o.len() => {
+                    operands.push(enum_name(b'D', o[index]));
+                    index += 1;
+                    operands.push(enum_name(b'B', o[index]));
+                },
+                b'A'..=b'Z' => operands.push(enum_name(kind, o[index])),
+                // OpExtInst
+                _ if instruction.opcode == 12 && index == 3 && Some(o[2]) == self.glsl_std_450 => {
+                    match glsl_std_450_name(o[3]) {
+                        Some(name) => operands.push(name.to_owned()),
+                        None => operands.push(o[3].to_string()),
+                    }
+                },
+                _ => operands.push(o[index].to_string()),
+            }
+            index += 1;
+        }
+
+        operands
+    }
+
+    /// Returns the value of a scalar constant, which is easier to read than its words.
+    fn constant(&self, instruction: &Instruction) -> Option<String> {
+        let o = &instruction.operands;
+        let t = self.module.global(o[0])?;
+        let bits = match o.get(3) {
+            Some(high) => o[2] as u64 | (*high as u64) << 32,
+            None => o[2] as u64,
+        };
+
+        // OpTypeInt, OpTypeFloat
+        match (t.opcode, t.operands[1], t.operands.get(2)) {
+            (21, 64, Some(1)) => Some((bits as i64).to_string()),
+            (21, 32, Some(1)) => Some((bits as i32).to_string()),
+            (21, _, _) => Some(bits.to_string()),
+            (22, 16, _) => Some(format!("{:?}", f16_to_f32(bits as u16))),
+            (22, 32, _) => Some(format!("{:?}", f32::from_bits(bits as u32))),
+            (22, 64, _) => Some(format!("{:?}", f64::from_bits(bits))),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the name and the layout of operands which follow the result type and the result id, in which `i` is an id,
+/// `l` a literal, `s` a string, a capital letter an enumerant named by `enum_name` and `*` repeats the previous kind
+/// until the end of the instruction.
+fn opcode_info(opcode: u16) -> (Option<&'static str>, &'static str) {
+    let (name, layout) = match opcode {
+        0 => ("OpNop", ""),
+        1 => ("OpUndef", ""),
+        3 => ("OpSource", "Gli"),
+        4 => ("OpSourceExtension", "s"),
+        5 => ("OpName", "is"),
+        6 => ("OpMemberName", "ils"),
+        7 => ("OpString", "s"),
+        8 => ("OpLine", "ill"),
+        10 => ("OpExtension", "s"),
+        11 => ("OpExtInstImport", "s"),
+        12 => ("OpExtInst", "ili*"),
+        14 => ("OpMemoryModel", "AM"),
+        15 => ("OpEntryPoint", "Xisi*"),
+        16 => ("OpExecutionMode", "iEl*"),
+        17 => ("OpCapability", "C"),
+        19 => ("OpTypeVoid", ""),
+        20 => ("OpTypeBool", ""),
+        21 => ("OpTypeInt", "ll"),
+        22 => ("OpTypeFloat", "l"),
+        23 => ("OpTypeVector", "il"),
+        24 => ("OpTypeMatrix", "il"),
+        28 => ("OpTypeArray", "ii"),
+        29 => ("OpTypeRuntimeArray", "i"),
+        30 => ("OpTypeStruct", "i*"),
+        32 => ("OpTypePointer", "Pi"),
+        33 => ("OpTypeFunction", "i*"),
+        41 => ("OpConstantTrue", ""),
+        42 => ("OpConstantFalse", ""),
+        43 => ("OpConstant", "l*"),
+        44 => ("OpConstantComposite", "i*"),
+        46 => ("OpConstantNull", ""),
+        48 => ("OpSpecConstantTrue", ""),
+        49 => ("OpSpecConstantFalse", ""),
+        50 => ("OpSpecConstant", "l*"),
+        51 => ("OpSpecConstantComposite", "i*"),
+        54 => ("OpFunction", "Fi"),
+        55 => ("OpFunctionParameter", ""),
+        56 => ("OpFunctionEnd", ""),
+        57 => ("OpFunctionCall", "i*"),
+        59 => ("OpVariable", "Pi"),
+        61 => ("OpLoad", "iKl*"),
+        62 => ("OpStore", "iiKl*"),
+        63 => ("OpCopyMemory", "iiKl*"),
+        65 => ("OpAccessChain", "i*"),
+        66 => ("OpInBoundsAccessChain", "i*"),
+        68 => ("OpArrayLength", "il"),
+        71 => ("OpDecorate", "iDl*"),
+        72 => ("OpMemberDecorate", "ilDl*"),
+        77 => ("OpVectorExtractDynamic", "ii"),
+        78 => ("OpVectorInsertDynamic", "iii"),
+        79 => ("OpVectorShuffle", "iil*"),
+        80 => ("OpCompositeConstruct", "i*"),
+        81 => ("OpCompositeExtract", "il*"),
+        82 => ("OpCompositeInsert", "iil*"),
+        83 => ("OpCopyObject", "i"),
+        109 => ("OpConvertFToU", "i"),
+        110 => ("OpConvertFToS", "i"),
+        111 => ("OpConvertSToF", "i"),
+        112 => ("OpConvertUToF", "i"),
+        113 => ("OpUConvert", "i"),
+        114 => ("OpSConvert", "i"),
+        115 => ("OpFConvert", "i"),
+        124 => ("OpBitcast", "i"),
+        126 => ("OpSNegate", "i"),
+        127 => ("OpFNegate", "i"),
+        128 => ("OpIAdd", "ii"),
+        129 => ("OpFAdd", "ii"),
+        130 => ("OpISub", "ii"),
+        131 => ("OpFSub", "ii"),
+        132 => ("OpIMul", "ii"),
+        133 => ("OpFMul", "ii"),
+        134 => ("OpUDiv", "ii"),
+        135 => ("OpSDiv", "ii"),
+        136 => ("OpFDiv", "ii"),
+        137 => ("OpUMod", "ii"),
+        138 => ("OpSRem", "ii"),
+        139 => ("OpSMod", "ii"),
+        140 => ("OpFRem", "ii"),
+        141 => ("OpFMod", "ii"),
+        142 => ("OpVectorTimesScalar", "ii"),
+        143 => ("OpMatrixTimesScalar", "ii"),
+        144 => ("OpVectorTimesMatrix", "ii"),
+        145 => ("OpMatrixTimesVector", "ii"),
+        146 => ("OpMatrixTimesMatrix", "ii"),
+        147 => ("OpOuterProduct", "ii"),
+        148 => ("OpDot", "ii"),
+        154 => ("OpAny", "i"),
+        155 => ("OpAll", "i"),
+        156 => ("OpIsNan", "i"),
+        157 => ("OpIsInf", "i"),
+        164 => ("OpLogicalEqual", "ii"),
+        165 => ("OpLogicalNotEqual", "ii"),
+        166 => ("OpLogicalOr", "ii"),
+        167 => ("OpLogicalAnd", "ii"),
+        168 => ("OpLogicalNot", "i"),
+        169 => ("OpSelect", "iii"),
+        170 => ("OpIEqual", "ii"),
+        171 => ("OpINotEqual", "ii"),
+        172 => ("OpUGreaterThan", "ii"),
+        173 => ("OpSGreaterThan", "ii"),
+        174 => ("OpUGreaterThanEqual", "ii"),
+        175 => ("OpSGreaterThanEqual", "ii"),
+        176 => ("OpULessThan", "ii"),
+        177 => ("OpSLessThan", "ii"),
+        178 => ("OpULessThanEqual", "ii"),
+        179 => ("OpSLessThanEqual", "ii"),
+        180 => ("OpFOrdEqual", "ii"),
+        181 => ("OpFUnordEqual", "ii"),
+        182 => ("OpFOrdNotEqual", "ii"),
+        183 => ("OpFUnordNotEqual", "ii"),
+        184 => ("OpFOrdLessThan", "ii"),
+        185 => ("OpFUnordLessThan", "ii"),
+        186 => ("OpFOrdGreaterThan", "ii"),
+        187 => ("OpFUnordGreaterThan", "ii"),
+        188 => ("OpFOrdLessThanEqual", "ii"),
+        189 => ("OpFUnordLessThanEqual", "ii"),
+        190 => ("OpFOrdGreaterThanEqual", "ii"),
+        191 => ("OpFUnordGreaterThanEqual", "ii"),
+        194 => ("OpShiftRightLogical", "ii"),
+        195 => ("OpShiftRightArithmetic", "ii"),
+        196 => ("OpShiftLeftLogical", "ii"),
+        197 => ("OpBitwiseOr", "ii"),
+        198 => ("OpBitwiseXor", "ii"),
+        199 => ("OpBitwiseAnd", "ii"),
+        200 => ("OpNot", "i"),
+        224 => ("OpControlBarrier", "iii"),
+        225 => ("OpMemoryBarrier", "ii"),
+        245 => ("OpPhi", "i*"),
+        246 => ("OpLoopMerge", "iiLl*"),
+        247 => ("OpSelectionMerge", "iS"),
+        248 => ("OpLabel", ""),
+        249 => ("OpBranch", "i"),
+        250 => ("OpBranchConditional", "iiil*"),
+        251 => ("OpSwitch", "iili*"),
+        252 => ("OpKill", ""),
+        253 => ("OpReturn", ""),
+        254 => ("OpReturnValue", "i"),
+        255 => ("OpUnreachable", ""),
+        317 => ("OpNoLine", ""),
+        4416 => ("OpTerminateInvocation", ""),
+        _ => return (None, "l*"),
+    };
+
+    (Some(name), layout)
+}
+
+/// Returns the name of an enumerant, like `Shader` for the capability 1, or of every bit of a mask joined by `|`.
+/// Unknown values are printed as numbers.
+fn enum_name(kind: u8, value: u32) -> String {
+    let names: &[(u32, &str)] = match kind {
+        // Capability
+        b'C' => &[
+            (0, "Matrix"), (1, "Shader"), (2, "Geometry"), (3, "Tessellation"), (4, "Addresses"), (5, "Linkage"),
+            (6, "Kernel"), (9, "Float16"), (10, "Float64"), (11, "Int64"), (12, "Int64Atomics"), (22, "Int16"),
+            (39, "Int8"), (61, "GroupNonUniform"), (4433, "StorageBuffer16BitAccess"),
+            (4434, "UniformAndStorageBuffer16BitAccess"), (4435, "StoragePushConstant16"),
+            (4436, "StorageInputOutput16"), (4448, "StorageBuffer8BitAccess"),
+            (4449, "UniformAndStorageBuffer8BitAccess"), (4450, "StoragePushConstant8"), (4464, "DenormPreserve"),
+            (4465, "DenormFlushToZero"), (4466, "SignedZeroInfNanPreserve"), (4467, "RoundingModeRTE"),
+            (4468, "RoundingModeRTZ"), (5345, "VulkanMemoryModel"),
+        ],
+        // AddressingModel
+        b'A' => &[(0, "Logical"), (1, "Physical32"), (2, "Physical64"), (5348, "PhysicalStorageBuffer64")],
+        // MemoryModel
+        b'M' => &[(0, "Simple"), (1, "GLSL450"), (2, "OpenCL"), (3, "Vulkan")],
+        // ExecutionModel
+        b'X' => &[
+            (0, "Vertex"), (1, "TessellationControl"), (2, "TessellationEvaluation"), (3, "Geometry"),
+            (4, "Fragment"), (5, "GLCompute"), (6, "Kernel"),
+        ],
+        // ExecutionMode
+        b'E' => &[
+            (7, "OriginUpperLeft"), (17, "LocalSize"), (38, "LocalSizeId"), (4459, "DenormPreserve"),
+            (4460, "DenormFlushToZero"), (4461, "SignedZeroInfNanPreserve"), (4462, "RoundingModeRTE"),
+            (4463, "RoundingModeRTZ"),
+        ],
+        // Decoration
+        b'D' => &[
+            (0, "RelaxedPrecision"), (1, "SpecId"), (2, "Block"), (3, "BufferBlock"), (4, "RowMajor"),
+            (5, "ColMajor"), (6, "ArrayStride"), (7, "MatrixStride"), (11, "BuiltIn"), (13, "NoPerspective"),
+            (14, "Flat"), (19, "Restrict"), (20, "Aliased"), (21, "Volatile"), (23, "Coherent"), (24, "NonWritable"),
+            (25, "NonReadable"), (30, "Location"), (33, "Binding"), (34, "DescriptorSet"), (35, "Offset"),
+            (42, "NoContraction"),
+        ],
+        // BuiltIn
+        b'B' => &[
+            (0, "Position"), (24, "NumWorkgroups"), (25, "WorkgroupSize"), (26, "WorkgroupId"),
+            (27, "LocalInvocationId"), (28, "GlobalInvocationId"), (29, "LocalInvocationIndex"),
+            (36, "SubgroupSize"), (41, "SubgroupLocalInvocationId"),
+        ],
+        // StorageClass
+        b'P' => &[
+            (0, "UniformConstant"), (1, "Input"), (2, "Uniform"), (3, "Output"), (4, "Workgroup"),
+            (5, "CrossWorkgroup"), (6, "Private"), (7, "Function"), (8, "Generic"), (9, "PushConstant"),
+            (12, "StorageBuffer"),
+        ],
+        // SourceLanguage
+        b'G' => &[(0, "Unknown"), (1, "ESSL"), (2, "GLSL"), (3, "OpenCL_C"), (4, "OpenCL_CPP"), (5, "HLSL")],
+        // FunctionControl, SelectionControl, LoopControl and MemoryAccess masks
+        b'F' => &[(1, "Inline"), (2, "DontInline"), (4, "Pure"), (8, "Const")],
+        b'S' => &[(1, "Flatten"), (2, "DontFlatten")],
+        b'L' => &[(1, "Unroll"), (2, "DontUnroll"), (4, "DependencyInfinite"), (8, "DependencyLength")],
+        b'K' => &[(1, "Volatile"), (2, "Aligned"), (4, "Nontemporal")],
+        _ => &[],
+    };
+
+    if !matches!(kind, b'F' | b'S' | b'L' | b'K') {
+        return names.iter().find(|(v, _)| *v == value).map_or(value.to_string(), |(_, name)| (*name).to_owned());
+    }
+    if value == 0 {
+        return "None".to_owned();
+    }
+
+    let mut bits = Vec::new();
+    let mut unknown = value;
+    for (bit, name) in names {
+        if value & bit != 0 {
+            bits.push((*name).to_owned());
+            unknown &= !bit;
+        }
+    }
+    if unknown != 0 {
+        bits.push(unknown.to_string());
+    }
+    bits.join("|")
+}
+
+pub(crate) fn opcode_name(opcode: u16) -> Option<&'static str> {
+    opcode_info(opcode).0
+}
+
+pub(crate) fn glsl_std_450_name(instruction: u32) -> Option<&'static str> {
+    let name = match instruction {
+        1 => "Round",
+        2 => "RoundEven",
+        3 => "Trunc",
+        4 => "FAbs",
+        5 => "SAbs",
+        6 => "FSign",
+        7 => "SSign",
+        8 => "Floor",
+        9 => "Ceil",
+        10 => "Fract",
+        13 => "Sin",
+        14 => "Cos",
+        15 => "Tan",
+        16 => "Asin",
+        17 => "Acos",
+        18 => "Atan",
+        19 => "Sinh",
+        20 => "Cosh",
+        21 => "Tanh",
+        22 => "Asinh",
+        23 => "Acosh",
+        24 => "Atanh",
+        25 => "Atan2",
+        26 => "Pow",
+        27 => "Exp",
+        28 => "Log",
+        29 => "Exp2",
+        30 => "Log2",
+        31 => "Sqrt",
+        32 => "InverseSqrt",
+        33 => "Determinant",
+        34 => "MatrixInverse",
+        37 => "FMin",
+        38 => "UMin",
+        39 => "SMin",
+        40 => "FMax",
+        41 => "UMax",
+        42 => "SMax",
+        43 => "FClamp",
+        44 => "UClamp",
+        45 => "SClamp",
+        46 => "FMix",
+        50 => "Fma",
+        66 => "Length",
+        67 => "Distance",
+        68 => "Cross",
+        69 => "Normalize",
+        70 => "FaceForward",
+        71 => "Reflect",
+        72 => "Refract",
+        73 => "FindILsb",
+        74 => "FindSMsb",
+        75 => "FindUMsb",
+        79 => "NMin",
+        80 => "NMax",
+        81 => "NClamp",
+        _ => return None,
+    };
+
+    Some(name)
+}
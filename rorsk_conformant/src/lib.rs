@@ -0,0 +1,9 @@
+//! Rewrites float instructions of SPIR-V modules into software implementations, so their results do not depend on the
+//! Vulkan driver.
+
+mod conformant;
+pub mod disassembler;
+pub mod spirv;
+pub mod validator;
+
+pub use conformant::{process, rewrite, Error, Mode, OpSet, Options, Processed};
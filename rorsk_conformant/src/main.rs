@@ -0,0 +1,98 @@
+use std::{fs, path::{Path, PathBuf}, process::ExitCode};
+
+use clap::Parser;
+use rorsk_conformant::{disassembler, spirv, Mode, OpSet, Options};
+
+/// Rewrites float instructions of a SPIR-V module into deterministic software implementations.
+#[derive(Parser)]
+#[command(version)]
+struct Cli {
+    /// SPIR-V module to rewrite.
+    input: PathBuf,
+
+    /// Path of the rewritten module. Defaults to the input path with the `.conformant.spv` extension.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Software float used for f32 arithmetic, `sf32` or `ieee`.
+    #[arg(short, long, default_value = "sf32")]
+    mode: Mode,
+
+    /// Op sets to rewrite, separated by commas: `arithmetic`, `sqrt`, `fma`, `exponential` or `trigonometric`. Every
+    /// op set is rewritten when omitted.
+    #[arg(long = "ops", value_name = "SETS", value_delimiter = ',')]
+    op_sets: Vec<OpSet>,
+
+    /// Saves the disassembly of the original and the rewritten module next to the output, together with a diff
+    /// between them.
+    #[arg(long)]
+    disassemble: bool,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let bytes = match fs::read(&cli.input) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            eprintln!("Failed to read `{}`: {e}.", cli.input.display());
+            return ExitCode::from(2);
+        }
+    };
+    if !bytes.len().is_multiple_of(4) {
+        eprintln!("`{}` is not a SPIR-V module, its size is not a multiple of 4 bytes.", cli.input.display());
+        return ExitCode::from(2);
+    }
+
+    let options = Options {
+        mode: cli.mode,
+        op_sets: if cli.op_sets.is_empty() { OpSet::ALL.to_vec() } else { cli.op_sets.clone() },
+    };
+    let processed = match rorsk_conformant::rewrite(&spirv::words_from_bytes(&bytes), options) {
+        Ok(processed) => processed,
+        Err(e) => {
+            eprintln!("Failed to rewrite `{}`: {e}.", cli.input.display());
+            return ExitCode::from(1);
+        }
+    };
+
+    let output = cli.output.unwrap_or_else(|| cli.input.with_extension("conformant.spv"));
+    let mut files = vec![(output.clone(), spirv::words_to_bytes(&processed.module.emit()))];
+
+    if cli.disassemble {
+        // Named after the input next to the output, so `a.spv --output a.bin` does not save both disassemblies to
+        // `a.spvasm`.
+        let stem = cli.input.file_stem().unwrap_or_default().to_string_lossy();
+        let path = |suffix: &str| output.with_file_name(format!("{stem}.{suffix}"));
+        let (original, module) = (&processed.original, &processed.module);
+        let names = disassembler::names(original, &processed.created);
+        files.push((path("original.spvasm"), disassembler::disassemble(original, &names).into_bytes()));
+        files.push((path("conformant.spvasm"), disassembler::disassemble(module, &names).into_bytes()));
+        files.push((path("conformant.diff"), disassembler::diff(original, module, &names).into_bytes()));
+    }
+
+    for (i, (path, _)) in files.iter().enumerate() {
+        // The rewritten module may replace the input, but no file may overwrite another one it saved.
+        if files[..i].iter().any(|(p, _)| p == path) || (i > 0 && *path == cli.input) {
+            eprintln!("`{}` would be saved twice, choose another output path.", path.display());
+            return ExitCode::from(2);
+        }
+    }
+
+    for (path, contents) in files {
+        if let Err(e) = save(&path, &contents) {
+            eprintln!("Failed to save `{}`: {e}.", path.display());
+            return ExitCode::from(2);
+        }
+        println!("Saved `{}`.", path.display());
+    }
+
+    ExitCode::SUCCESS
+}
+
+fn save(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)
+}
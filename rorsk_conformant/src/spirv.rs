@@ -0,0 +1,329 @@
+use std::fmt;
+
+pub const MAGIC_NUMBER: u32 = 0x07230203;
+
+#[derive(Debug)]
+pub enum ParseError {
+    InvalidMagicNumber(u32),
+    UnexpectedEnd,
+    InvalidWordCount(usize),
+    InstructionOutsideBlock(usize),
+    UnterminatedFunction,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidMagicNumber(magic) => write!(f, "invalid magic number {magic:#010x}"),
+            ParseError::UnexpectedEnd => write!(f, "unexpected end of module"),
+            ParseError::InvalidWordCount(index) => write!(f, "invalid word count of instruction at word {index}"),
+            ParseError::InstructionOutsideBlock(index) => {
+                write!(f, "instruction at word {index} is outside of a block")
+            },
+            ParseError::UnterminatedFunction => write!(f, "function is not terminated by OpFunctionEnd"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Header {
+    pub version: u32,
+    pub generator: u32,
+    pub bound: u32,
+    pub schema: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Instruction {
+    pub opcode: u16,
+    pub operands: Vec<u32>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Block {
+    pub label: u32,
+    pub instructions: Vec<Instruction>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Function {
+    pub definition: Instruction,
+    /// OpFunctionParameter instructions, together with OpLine and OpNoLine which precede the first block.
+    pub parameters: Vec<Instruction>,
+    pub blocks: Vec<Block>,
+}
+
+impl Function {
+    /// Returns the OpFunctionParameter instructions without debug lines.
+    pub fn function_parameters(&self) -> impl Iterator<Item = &Instruction> {
+        self.parameters.iter().filter(|i| i.opcode == 55)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Module {
+    pub header: Header,
+    pub capabilities: Vec<Instruction>,
+    pub extensions: Vec<Instruction>,
+    pub ext_inst_imports: Vec<Instruction>,
+    pub memory_model: Option<Instruction>,
+    pub entry_points: Vec<Instruction>,
+    pub execution_modes: Vec<Instruction>,
+    pub debug: Vec<Instruction>,
+    pub annotations: Vec<Instruction>,
+    pub types_global_values: Vec<Instruction>,
+    pub functions: Vec<Function>,
+}
+
+impl Instruction {
+    pub fn new(opcode: u16, operands: &[u32]) -> Self {
+        Instruction {
+            opcode,
+            operands: operands.to_vec(),
+        }
+    }
+
+    pub fn result_type(&self) -> Option<u32> {
+        match result_layout(self.opcode) {
+            (true, true) => self.operands.first().copied(),
+            _ => None,
+        }
+    }
+
+    pub fn result_id(&self) -> Option<u32> {
+        match result_layout(self.opcode) {
+            (true, true) => self.operands.get(1).copied(),
+            (false, true) => self.operands.first().copied(),
+            _ => None,
+        }
+    }
+
+    /// Decodes a literal string operand, which starts at the given operand index.
+    pub fn string(&self, index: usize) -> String {
+        let mut bytes = Vec::new();
+        for word in self.operands.iter().skip(index) {
+            for byte in word.to_le_bytes() {
+                if byte == 0 {
+                    return String::from_utf8_lossy(&bytes).into_owned();
+                }
+                bytes.push(byte);
+            }
+        }
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+
+    fn emit(&self, words: &mut Vec<u32>) {
+        words.push(self.opcode as u32 | ((self.operands.len() as u32 + 1) << 16));
+        words.extend_from_slice(&self.operands);
+    }
+}
+
+impl Module {
+    pub fn parse(words: &[u32]) -> Result<Self, ParseError> {
+        if words.len() < 5 {
+            return Err(ParseError::UnexpectedEnd);
+        }
+        if words[0] != MAGIC_NUMBER {
+            return Err(ParseError::InvalidMagicNumber(words[0]));
+        }
+
+        let mut module = Module {
+            header: Header {
+                version: words[1],
+                generator: words[2],
+                bound: words[3],
+                schema: words[4],
+            },
+            capabilities: Vec::new(),
+            extensions: Vec::new(),
+            ext_inst_imports: Vec::new(),
+            memory_model: None,
+            entry_points: Vec::new(),
+            execution_modes: Vec::new(),
+            debug: Vec::new(),
+            annotations: Vec::new(),
+            types_global_values: Vec::new(),
+            functions: Vec::new(),
+        };
+
+        let mut function: Option<Function> = None;
+
+        let mut i = 5;
+        while i < words.len() {
+            let word_count = (words[i] >> 16) as usize;
+            let opcode = (words[i] & 0xFFFF) as u16;
+
+            if word_count == 0 {
+                return Err(ParseError::InvalidWordCount(i));
+            }
+            if i + word_count > words.len() {
+                return Err(ParseError::UnexpectedEnd);
+            }
+
+            let instruction = Instruction::new(opcode, &words[i + 1..i + word_count]);
+
+            if let Some(f) = function.as_mut() {
+                match opcode {
+                    // OpFunctionParameter
+                    55 => f.parameters.push(instruction),
+                    // OpLine, OpNoLine
+                    8 | 317 if f.blocks.is_empty() => f.parameters.push(instruction),
+                    // OpLabel
+                    248 if instruction.operands.is_empty() => return Err(ParseError::InvalidWordCount(i)),
+                    248 => f.blocks.push(Block {
+                        label: instruction.operands[0],
+                        instructions: Vec::new(),
+                    }),
+                    // OpFunctionEnd
+                    56 => module.functions.push(function.take().unwrap()),
+                    _ => match f.blocks.last_mut() {
+                        Some(block) => block.instructions.push(instruction),
+                        None => return Err(ParseError::InstructionOutsideBlock(i)),
+                    },
+                }
+            } else {
+                match opcode {
+                    // OpCapability
+                    17 => module.capabilities.push(instruction),
+                    // OpExtension
+                    10 => module.extensions.push(instruction),
+                    // OpExtInstImport
+                    11 => module.ext_inst_imports.push(instruction),
+                    // OpMemoryModel
+                    14 => module.memory_model = Some(instruction),
+                    // OpEntryPoint
+                    15 => module.entry_points.push(instruction),
+                    // OpExecutionMode, OpExecutionModeId
+                    16 | 331 => module.execution_modes.push(instruction),
+                    // OpSourceContinued, OpSource, OpSourceExtension, OpName, OpMemberName, OpString, OpModuleProcessed
+                    2..=7 | 330 => module.debug.push(instruction),
+                    // OpDecorate, OpMemberDecorate, OpDecorationGroup, OpGroupDecorate, OpGroupMemberDecorate,
+                    // OpDecorateId, OpDecorateString, OpMemberDecorateString
+                    71..=75 | 332 | 5632 | 5633 => module.annotations.push(instruction),
+                    // OpFunction
+                    54 => {
+                        function = Some(Function {
+                            definition: instruction,
+                            parameters: Vec::new(),
+                            blocks: Vec::new(),
+                        })
+                    },
+                    _ => module.types_global_values.push(instruction),
+                }
+            }
+
+            i += word_count;
+        }
+
+        if function.is_some() {
+            return Err(ParseError::UnterminatedFunction);
+        }
+
+        Ok(module)
+    }
+
+    pub fn emit(&self) -> Vec<u32> {
+        let mut words = vec![
+            MAGIC_NUMBER,
+            self.header.version,
+            self.header.generator,
+            self.header.bound,
+            self.header.schema,
+        ];
+
+        for instruction in self
+            .capabilities
+            .iter()
+            .chain(&self.extensions)
+            .chain(&self.ext_inst_imports)
+            .chain(&self.memory_model)
+            .chain(&self.entry_points)
+            .chain(&self.execution_modes)
+            .chain(&self.debug)
+            .chain(&self.annotations)
+            .chain(&self.types_global_values)
+        {
+            instruction.emit(&mut words);
+        }
+
+        for function in &self.functions {
+            function.definition.emit(&mut words);
+            for parameter in &function.parameters {
+                parameter.emit(&mut words);
+            }
+            for block in &function.blocks {
+                Instruction::new(248, &[block.label]).emit(&mut words);
+                for instruction in &block.instructions {
+                    instruction.emit(&mut words);
+                }
+            }
+            Instruction::new(56, &[]).emit(&mut words);
+        }
+
+        words
+    }
+
+    /// Returns the instruction from the types, constants and global variables section which defines the given id.
+    pub fn global(&self, id: u32) -> Option<&Instruction> {
+        self.types_global_values.iter().find(|i| i.result_id() == Some(id))
+    }
+}
+
+/// Returns the words of a module stored in little endian bytes, like in `.spv` files. Trailing bytes are ignored.
+pub fn words_from_bytes(bytes: &[u8]) -> Vec<u32> {
+    bytes.chunks_exact(4).map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]])).collect()
+}
+
+pub fn words_to_bytes(words: &[u32]) -> Vec<u8> {
+    words.iter().flat_map(|w| w.to_le_bytes()).collect()
+}
+
+/// Returns whether instructions with the given opcode have a result type and a result id.
+pub fn result_layout(opcode: u16) -> (bool, bool) {
+    match opcode {
+        // OpUndef, OpExtInst
+        1 | 12 => (true, true),
+        // OpString, OpExtInstImport
+        7 | 11 => (false, true),
+        // OpType*, without OpTypeForwardPointer
+        19..=38 => (false, true),
+        // OpConstant*, OpSpecConstant*
+        41..=46 | 48..=52 => (true, true),
+        // OpFunction, OpFunctionParameter, OpFunctionCall
+        54 | 55 | 57 => (true, true),
+        // Memory instructions, without OpStore, OpCopyMemory and OpCopyMemorySized
+        59..=61 | 65..=70 => (true, true),
+        // OpDecorationGroup
+        73 => (false, true),
+        // Composite instructions
+        77..=84 => (true, true),
+        // Image instructions, without OpImageWrite
+        86..=98 | 100..=107 => (true, true),
+        // Conversion instructions
+        109..=124 => (true, true),
+        // Arithmetic instructions
+        126..=152 => (true, true),
+        // Relational and logical instructions
+        154..=191 => (true, true),
+        // Bit instructions
+        194..=205 => (true, true),
+        // Derivative instructions
+        207..=215 => (true, true),
+        // Atomic instructions, without OpAtomicStore
+        227 | 229..=242 => (true, true),
+        // OpPhi
+        245 => (true, true),
+        // OpLabel
+        248 => (false, true),
+        // Group instructions, without OpGroupWaitEvents
+        259 | 261..=270 => (true, true),
+        // OpGroupNonUniform*
+        333..=366 => (true, true),
+        // OpCopyLogical, OpPtrEqual, OpPtrNotEqual, OpPtrDiff
+        400..=403 => (true, true),
+        _ => (false, false),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,74 @@
+use crate::{process, validator, Options};
+
+use super::{Module, MAGIC_NUMBER};
+
+/// Returns a module whose function `%7 = float f(float a, float b)` has OpLine and OpNoLine among its parameters.
+fn module_with_lines() -> Vec<u32> {
+    let mut words = vec![MAGIC_NUMBER, 0x0001_0000, 0, 13, 0];
+    let mut instruction = |opcode: u32, operands: &[u32]| {
+        words.push(opcode | ((operands.len() as u32 + 1) << 16));
+        words.extend_from_slice(operands);
+    };
+
+    // OpCapability Shader, OpMemoryModel Logical GLSL450, OpEntryPoint GLCompute %1 "main"
+    instruction(17, &[1]);
+    instruction(14, &[0, 1]);
+    instruction(15, &[5, 1, u32::from_le_bytes(*b"main"), 0]);
+    // OpExecutionMode %1 LocalSize 1 1 1
+    instruction(16, &[1, 17, 1, 1, 1]);
+    // %2 = OpString "a"
+    instruction(7, &[2, u32::from_le_bytes(*b"a\0\0\0")]);
+    // %3 = OpTypeVoid, %4 = OpTypeFloat 32, %5 = OpTypeFunction %3, %6 = OpTypeFunction %4 %4 %4
+    instruction(19, &[3]);
+    instruction(22, &[4, 32]);
+    instruction(33, &[5, 3]);
+    instruction(33, &[6, 4, 4, 4]);
+
+    // %7 = OpFunction %4 None %6
+    instruction(54, &[4, 7, 0, 6]);
+    // OpLine %2 1 1, %8 = OpFunctionParameter %4, OpLine %2 1 9, %9 = OpFunctionParameter %4, OpNoLine
+    instruction(8, &[2, 1, 1]);
+    instruction(55, &[4, 8]);
+    instruction(8, &[2, 1, 9]);
+    instruction(55, &[4, 9]);
+    instruction(317, &[]);
+    // %10 = OpLabel, OpLine %2 2 1, %11 = OpFAdd %4 %8 %9, OpReturnValue %11, OpFunctionEnd
+    instruction(248, &[10]);
+    instruction(8, &[2, 2, 1]);
+    instruction(129, &[4, 11, 8, 9]);
+    instruction(254, &[11]);
+    instruction(56, &[]);
+
+    // %1 = OpFunction %3 None %5, OpLine %2 3 1, %12 = OpLabel, OpReturn, OpFunctionEnd
+    instruction(54, &[3, 1, 0, 5]);
+    instruction(8, &[2, 3, 1]);
+    instruction(248, &[12]);
+    instruction(253, &[]);
+    instruction(56, &[]);
+
+    words
+}
+
+#[test]
+fn parse_emit_round_trip() {
+    let words = module_with_lines();
+    let module = Module::parse(&words).unwrap();
+
+    let function = &module.functions[0];
+    assert_eq!(function.parameters.iter().map(|i| i.opcode).collect::<Vec<_>>(), [8, 55, 8, 55, 317]);
+    assert_eq!(function.function_parameters().map(|i| i.operands[1]).collect::<Vec<_>>(), [8, 9]);
+    assert_eq!(module.functions[1].parameters.iter().map(|i| i.opcode).collect::<Vec<_>>(), [8]);
+
+    assert_eq!(module.emit(), words);
+}
+
+#[test]
+fn lines_before_first_block_are_valid() {
+    let words = module_with_lines();
+    validator::validate(&Module::parse(&words).unwrap()).unwrap();
+
+    // The lines stay in front of the parameters after the rewrite.
+    let rewritten = Module::parse(&process(&words, Options::default()).unwrap()).unwrap();
+    let function = rewritten.functions.iter().find(|f| f.definition.operands[1] == 7).unwrap();
+    assert_eq!(function.parameters.iter().map(|i| i.opcode).collect::<Vec<_>>(), [8, 55, 8, 55, 317]);
+}
@@ -0,0 +1,830 @@
+use std::{collections::{HashMap, HashSet}, fmt};
+
+use crate::spirv::{result_layout, Function, Instruction, Module};
+
+#[derive(Debug)]
+pub enum ValidationError {
+    IdOutOfBound { id: u32, bound: u32 },
+    DuplicateId(u32),
+    MissingOperands { opcode: u16, result: Option<u32> },
+    UndefinedId { opcode: u16, result: Option<u32>, id: u32 },
+    InvalidOperand { opcode: u16, result: Option<u32>, id: u32, reason: &'static str },
+    InvalidType { id: u32, reason: &'static str },
+    InvalidFunction { function: u32, reason: &'static str },
+    InvalidBlock { label: u32, reason: &'static str },
+    MissingCapability { id: u32, capability: &'static str },
+    MissingMemoryModel,
+    MissingEntryPoint,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let instruction = |opcode: &u16, result: &Option<u32>| match result {
+            Some(result) => format!("instruction {opcode} defining %{result}"),
+            None => format!("instruction {opcode}"),
+        };
+
+        match self {
+            ValidationError::IdOutOfBound { id, bound } => write!(f, "%{id} is not below the id bound {bound}"),
+            ValidationError::DuplicateId(id) => write!(f, "%{id} is defined more than once"),
+            ValidationError::MissingOperands { opcode, result } => {
+                write!(f, "{} has too few operands", instruction(opcode, result))
+            },
+            ValidationError::UndefinedId { opcode, result, id } => {
+                write!(f, "{} uses %{id}, which is not defined before it", instruction(opcode, result))
+            },
+            ValidationError::InvalidOperand { opcode, result, id, reason } => {
+                write!(f, "operand %{id} of {} {reason}", instruction(opcode, result))
+            },
+            ValidationError::InvalidType { id, reason } => write!(f, "type %{id} {reason}"),
+            ValidationError::InvalidFunction { function, reason } => write!(f, "function %{function} {reason}"),
+            ValidationError::InvalidBlock { label, reason } => write!(f, "block %{label} {reason}"),
+            ValidationError::MissingCapability { id, capability } => {
+                write!(f, "%{id} requires the {capability} capability")
+            },
+            ValidationError::MissingMemoryModel => write!(f, "module has no OpMemoryModel"),
+            ValidationError::MissingEntryPoint => write!(f, "module has no OpEntryPoint"),
+        }
+    }
+}
+
+/// Checks the structural rules of the SPIR-V specification which a broken rewrite is likely to violate. Instructions
+/// whose operand layout is not known here are only checked for their result type and result id.
+pub fn validate(module: &Module) -> Result<(), ValidationError> {
+    let mut validator = Validator {
+        module,
+        all: HashSet::new(),
+        visible: HashSet::new(),
+        types: HashMap::new(),
+        unique_types: HashSet::new(),
+        value_types: HashMap::new(),
+        functions: HashMap::new(),
+    };
+
+    validator.check_ids()?;
+    validator.check_module()?;
+    validator.check_capabilities()?;
+    for instruction in &module.types_global_values {
+        validator.check_global(instruction)?;
+    }
+    for function in &module.functions {
+        validator.check_function(function)?;
+    }
+
+    Ok(())
+}
+
+struct Validator<'a> {
+    module: &'a Module,
+    /// Every id defined anywhere in the module.
+    all: HashSet<u32>,
+    /// Ids defined before the instruction which is checked.
+    visible: HashSet<u32>,
+    types: HashMap<u32, &'a Instruction>,
+    /// Opcodes and operands of types which must not be declared twice.
+    unique_types: HashSet<(u16, &'a [u32])>,
+    value_types: HashMap<u32, u32>,
+    functions: HashMap<u32, &'a Function>,
+}
+
+impl<'a> Validator<'a> {
+    fn check_ids(&mut self) -> Result<(), ValidationError> {
+        let module = self.module;
+        let sections = module.capabilities.iter()
+            .chain(&module.extensions)
+            .chain(&module.ext_inst_imports)
+            .chain(&module.memory_model)
+            .chain(&module.entry_points)
+            .chain(&module.execution_modes)
+            .chain(&module.debug)
+            .chain(&module.annotations)
+            .chain(&module.types_global_values);
+        let mut ids = Vec::new();
+        let mut instructions = Vec::new();
+        for instruction in sections {
+            ids.extend(instruction.result_id());
+            instructions.push(instruction);
+        }
+
+        for function in &module.functions {
+            instructions.push(&function.definition);
+            instructions.extend(function_instructions(function));
+        }
+
+        // Later checks and the rewriter index operands, so their count is checked first.
+        for instruction in instructions {
+            let count = instruction.operands.len();
+            // OpPhi has pairs of a value and a parent block.
+            if count < minimum_operand_count(instruction.opcode) || (instruction.opcode == 245 && count % 2 != 0) {
+                return Err(ValidationError::MissingOperands {
+                    opcode: instruction.opcode,
+                    result: instruction.result_id(),
+                });
+            }
+        }
+
+        for function in &module.functions {
+            self.functions.insert(function.definition.operands[1], function);
+            for instruction in function_instructions(function) {
+                if let (Some(result_type), Some(id)) = (instruction.result_type(), instruction.result_id()) {
+                    self.value_types.insert(id, result_type);
+                }
+                ids.extend(instruction.result_id());
+            }
+            ids.extend(function.definition.result_id());
+            ids.extend(function.blocks.iter().map(|b| b.label));
+        }
+
+        for instruction in &module.types_global_values {
+            if let (Some(result_type), Some(id)) = (instruction.result_type(), instruction.result_id()) {
+                self.value_types.insert(id, result_type);
+            }
+        }
+
+        for id in ids {
+            if id == 0 || id >= module.header.bound {
+                return Err(ValidationError::IdOutOfBound { id, bound: module.header.bound });
+            }
+            if !self.all.insert(id) {
+                return Err(ValidationError::DuplicateId(id));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks module level instructions, which may refer to ids defined later in the module.
+    fn check_module(&mut self) -> Result<(), ValidationError> {
+        let module = self.module;
+        if module.memory_model.is_none() {
+            return Err(ValidationError::MissingMemoryModel);
+        }
+        if module.entry_points.is_empty() {
+            return Err(ValidationError::MissingEntryPoint);
+        }
+
+        for instruction in &module.entry_points {
+            // Interface ids follow the name, which is a nul terminated string padded to whole words.
+            let name_words = instruction.string(2).len() / 4 + 1;
+            let interface = instruction.operands.iter().skip(2 + name_words);
+            for id in std::iter::once(&instruction.operands[1]).chain(interface) {
+                self.check_exists(instruction, *id)?;
+            }
+
+            if !self.functions.contains_key(&instruction.operands[1]) {
+                return Err(self.invalid_operand(instruction, instruction.operands[1], "is not a function"));
+            }
+        }
+
+        let named = module.execution_modes.iter().chain(&module.debug).chain(&module.annotations);
+        for instruction in named {
+            match instruction.opcode {
+                // OpExecutionMode, OpName, OpMemberName, OpDecorate, OpMemberDecorate
+                16 | 5 | 6 | 71 | 72 => self.check_exists(instruction, instruction.operands[0])?,
+                _ => {},
+            }
+        }
+
+        for instruction in &module.ext_inst_imports {
+            self.visible.insert(instruction.operands[0]);
+        }
+        // OpString, the file of OpLine
+        for instruction in module.debug.iter().filter(|i| i.opcode == 7) {
+            self.visible.insert(instruction.operands[0]);
+        }
+        // Functions may be called before they are defined.
+        self.visible.extend(self.functions.keys());
+
+        Ok(())
+    }
+
+    fn check_capabilities(&self) -> Result<(), ValidationError> {
+        let mut declared = self.module.capabilities.iter().map(|i| i.operands[0]).collect::<HashSet<_>>();
+        // Int64Atomics implicitly declares Int64.
+        if declared.contains(&12) {
+            declared.insert(11);
+        }
+
+        // 16 and 8 bit types are also allowed by the capabilities for storing them in buffers.
+        const STORAGE_16: [u32; 4] = [4433, 4434, 4435, 4436];
+        const STORAGE_8: [u32; 3] = [4448, 4449, 4450];
+
+        for instruction in &self.module.types_global_values {
+            let width = instruction.operands.get(1).copied();
+            let required: Option<(&'static str, &[u32], &[u32])> = match (instruction.opcode, width) {
+                // OpTypeInt
+                (21, Some(8)) => Some(("Int8", &[39], &STORAGE_8)),
+                (21, Some(16)) => Some(("Int16", &[22], &STORAGE_16)),
+                (21, Some(64)) => Some(("Int64", &[11], &[])),
+                // OpTypeFloat
+                (22, Some(16)) => Some(("Float16", &[9], &STORAGE_16)),
+                (22, Some(64)) => Some(("Float64", &[10], &[])),
+                _ => None,
+            };
+
+            if let Some((capability, arithmetic, storage)) = required {
+                if !arithmetic.iter().chain(storage).any(|c| declared.contains(c)) {
+                    return Err(ValidationError::MissingCapability { id: instruction.operands[0], capability });
+                }
+            }
+        }
+
+        for instruction in &self.module.entry_points {
+            // GLCompute needs Shader.
+            if instruction.operands[0] == 5 && !declared.contains(&1) {
+                return Err(ValidationError::MissingCapability { id: instruction.operands[1], capability: "Shader" });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_global(&mut self, instruction: &'a Instruction) -> Result<(), ValidationError> {
+        self.check_defined(instruction, &self.visible)?;
+
+        match instruction.opcode {
+            // OpTypeInt
+            21 if !matches!(instruction.operands[1], 8 | 16 | 32 | 64) => {
+                return Err(ValidationError::InvalidType {
+                    id: instruction.operands[0],
+                    reason: "has a width other than 8, 16, 32 or 64",
+                });
+            },
+            // OpTypeFloat
+            22 if !matches!(instruction.operands[1], 16 | 32 | 64) => {
+                return Err(ValidationError::InvalidType {
+                    id: instruction.operands[0],
+                    reason: "has a width other than 16, 32 or 64",
+                });
+            },
+            // OpType*
+            19..=38 => {
+                // Non-aggregate types other than pointers are unique, OpTypeVoid .. OpTypeSampledImage, OpTypeFunction
+                let unique = matches!(instruction.opcode, 19..=27 | 33);
+                if unique && !self.unique_types.insert((instruction.opcode, &instruction.operands[1..])) {
+                    return Err(ValidationError::InvalidType {
+                        id: instruction.operands[0],
+                        reason: "is declared more than once",
+                    });
+                }
+                self.types.insert(instruction.operands[0], instruction);
+            },
+            // OpVariable
+            59 if instruction.operands[2] == 7 => {
+                return Err(self.invalid_operand(
+                    instruction, instruction.operands[1], "is a global variable with the Function storage class"
+                ));
+            },
+            _ => self.check_types(instruction, None)?,
+        }
+
+        if let Some(id) = instruction.result_id() {
+            self.visible.insert(id);
+        }
+        Ok(())
+    }
+
+    fn check_function(&mut self, function: &'a Function) -> Result<(), ValidationError> {
+        let definition = &function.definition;
+        let (return_type, id) = (definition.operands[0], definition.operands[1]);
+        self.check_defined(definition, &self.visible)?;
+
+        let function_type = match self.types.get(&definition.operands[3]) {
+            // OpTypeFunction
+            Some(t) if t.opcode == 33 => *t,
+            _ => return Err(self.invalid_operand(definition, definition.operands[3], "is not a function type")),
+        };
+        if function_type.operands[1] != return_type {
+            return Err(ValidationError::InvalidFunction {
+                function: id,
+                reason: "has a different return type than its type",
+            });
+        }
+        if function_type.operands.len() - 2 != function.function_parameters().count() {
+            return Err(ValidationError::InvalidFunction {
+                function: id,
+                reason: "has a different number of parameters than its type",
+            });
+        }
+        if function.blocks.is_empty() {
+            return Err(ValidationError::InvalidFunction { function: id, reason: "has no blocks" });
+        }
+
+        let labels = function.blocks.iter().map(|b| b.label).collect::<HashSet<_>>();
+        let function_ids = function_instructions(function).filter_map(|i| i.result_id()).collect::<HashSet<_>>();
+
+        // Ids of a function are not visible in other functions.
+        let mut visible = self.visible.clone();
+        visible.extend(&labels);
+
+        for (parameter, parameter_type) in function.function_parameters().zip(&function_type.operands[2..]) {
+            self.check_defined(parameter, &visible)?;
+            if parameter.operands[0] != *parameter_type {
+                return Err(self.invalid_operand(parameter, parameter.operands[0], "differs from the parameter type"));
+            }
+            visible.insert(parameter.operands[1]);
+        }
+
+        for (block_index, block) in function.blocks.iter().enumerate() {
+            let count = block.instructions.len();
+            let invalid_block = |reason| Err(ValidationError::InvalidBlock { label: block.label, reason });
+
+            if !block.instructions.last().is_some_and(|i| is_terminator(i.opcode)) {
+                return invalid_block("does not end with a branch, return or OpUnreachable");
+            }
+
+            for (index, instruction) in block.instructions.iter().enumerate() {
+                // OpLine and OpNoLine may be placed anywhere.
+                let previous = block.instructions[..index].iter()
+                    .rev()
+                    .map(|i| i.opcode)
+                    .find(|o| !matches!(o, 8 | 317));
+                match instruction.opcode {
+                    opcode if is_terminator(opcode) && index + 1 != count => {
+                        return invalid_block("has a terminator before its last instruction");
+                    },
+                    // OpPhi
+                    245 if previous.is_some_and(|p| p != 245) => {
+                        return invalid_block("has OpPhi after other instructions");
+                    },
+                    // OpVariable
+                    59 if block_index != 0 || previous.is_some_and(|p| p != 59) => {
+                        return invalid_block("has OpVariable which is not at the beginning of the first block");
+                    },
+                    // OpVariable
+                    59 if instruction.operands[2] != 7 => {
+                        return invalid_block("has OpVariable with a storage class other than Function");
+                    },
+                    // OpLoopMerge
+                    246 if index + 2 != count || !matches!(block.instructions[count - 1].opcode, 249 | 250) => {
+                        return invalid_block("has OpLoopMerge which is not followed by the branch of the block");
+                    },
+                    // OpSelectionMerge
+                    247 if index + 2 != count || !matches!(block.instructions[count - 1].opcode, 250 | 251) => {
+                        return invalid_block("has OpSelectionMerge which is not followed by the branch of the block");
+                    },
+                    _ => {},
+                }
+
+                self.check_defined(instruction, &visible)?;
+
+                for label in label_operands(instruction) {
+                    if !labels.contains(&label) {
+                        return Err(self.invalid_operand(instruction, label, "is not a label of this function"));
+                    }
+                }
+
+                match instruction.opcode {
+                    // OpPhi, values may come from blocks which are later in the function.
+                    245 => for pair in instruction.operands[2..].chunks(2) {
+                        if !self.visible.contains(&pair[0]) && !function_ids.contains(&pair[0]) {
+                            return Err(ValidationError::UndefinedId {
+                                opcode: 245,
+                                result: instruction.result_id(),
+                                id: pair[0],
+                            });
+                        }
+                    },
+                    // OpReturn
+                    253 if self.types.get(&return_type).map(|t| t.opcode) != Some(19) => {
+                        return Err(ValidationError::InvalidFunction { function: id, reason: "returns no value" });
+                    },
+                    _ => {},
+                }
+
+                self.check_types(instruction, Some(return_type))?;
+
+                if let Some(id) = instruction.result_id() {
+                    visible.insert(id);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the result type and the used ids are visible. Labels, phi values and called functions may be
+    /// defined later and are checked by the caller.
+    fn check_defined(&self, instruction: &Instruction, visible: &HashSet<u32>) -> Result<(), ValidationError> {
+        let result = instruction.result_id();
+
+        if let Some(result_type) = instruction.result_type() {
+            if !self.types.contains_key(&result_type) {
+                return match visible.contains(&result_type) {
+                    true => Err(self.invalid_operand(instruction, result_type, "is not a type")),
+                    false => Err(ValidationError::UndefinedId { opcode: instruction.opcode, result, id: result_type }),
+                };
+            }
+        }
+
+        for id in used_ids(instruction).unwrap_or_default() {
+            if !visible.contains(&id) {
+                return Err(ValidationError::UndefinedId { opcode: instruction.opcode, result, id });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_exists(&self, instruction: &Instruction, id: u32) -> Result<(), ValidationError> {
+        match self.all.contains(&id) {
+            true => Ok(()),
+            false => Err(ValidationError::UndefinedId { opcode: instruction.opcode, result: None, id }),
+        }
+    }
+
+    fn invalid_operand(&self, instruction: &Instruction, id: u32, reason: &'static str) -> ValidationError {
+        ValidationError::InvalidOperand { opcode: instruction.opcode, result: instruction.result_id(), id, reason }
+    }
+
+    fn type_of(&self, id: u32) -> Option<u32> {
+        self.value_types.get(&id).copied()
+    }
+
+    /// Returns the component kind (opcode of OpTypeBool, OpTypeInt or OpTypeFloat), width and component count of a
+    /// scalar or vector type. Booleans have no width.
+    fn shape(&self, type_id: u32) -> Option<(u16, u32, u32)> {
+        let t = self.types.get(&type_id)?;
+        match t.opcode {
+            // OpTypeBool
+            20 => Some((20, 0, 1)),
+            // OpTypeInt, OpTypeFloat
+            21 | 22 => Some((t.opcode, t.operands[1], 1)),
+            // OpTypeVector
+            23 => self.shape(t.operands[1]).map(|(kind, width, _)| (kind, width, t.operands[2])),
+            _ => None,
+        }
+    }
+
+    fn value_shape(&self, id: u32) -> Option<(u16, u32, u32)> {
+        self.type_of(id).and_then(|t| self.shape(t))
+    }
+
+    /// Returns the type pointed to by a pointer type.
+    fn pointee(&self, pointer_type: u32) -> Option<u32> {
+        // OpTypePointer
+        self.types.get(&pointer_type).filter(|t| t.opcode == 32).map(|t| t.operands[2])
+    }
+
+    /// Returns the type of a member of a composite type.
+    fn member_type(&self, composite_type: u32, index: u32) -> Option<u32> {
+        let t = self.types.get(&composite_type)?;
+        match t.opcode {
+            // OpTypeVector, OpTypeMatrix
+            23 | 24 if index < t.operands[2] => Some(t.operands[1]),
+            // OpTypeArray, OpTypeRuntimeArray
+            28 | 29 => Some(t.operands[1]),
+            // OpTypeStruct
+            30 => t.operands.get(1 + index as usize).copied(),
+            _ => None,
+        }
+    }
+
+    fn check_types(&self, instruction: &Instruction, return_type: Option<u32>) -> Result<(), ValidationError> {
+        let o = &instruction.operands;
+        let invalid = |id, reason| Err(self.invalid_operand(instruction, id, reason));
+        let result_shape = || o.first().and_then(|t| self.shape(*t));
+
+        match instruction.opcode {
+            // OpFNegate, OpFAdd, OpFSub, OpFMul, OpFDiv, OpFRem, OpFMod
+            127 | 129 | 131 | 133 | 136 | 140 | 141 => {
+                if !matches!(result_shape(), Some((22, _, _))) {
+                    return invalid(o[0], "is not a float scalar or vector type");
+                }
+                for id in &o[2..] {
+                    if self.type_of(*id) != Some(o[0]) {
+                        return invalid(*id, "does not have the result type");
+                    }
+                }
+            },
+            // OpSNegate, OpIAdd, OpISub, OpIMul, OpUDiv, OpSDiv, OpUMod, OpSRem, OpSMod, OpBitwiseOr, OpBitwiseXor,
+            // OpBitwiseAnd, OpNot
+            126 | 128 | 130 | 132 | 134 | 135 | 137 | 138 | 139 | 197 | 198 | 199 | 200 => {
+                let Some(shape @ (21, _, _)) = result_shape() else {
+                    return invalid(o[0], "is not an integer scalar or vector type");
+                };
+                for id in &o[2..] {
+                    if self.value_shape(*id) != Some(shape) {
+                        return invalid(*id, "does not have the width and component count of the result type");
+                    }
+                }
+            },
+            // OpShiftRightLogical, OpShiftRightArithmetic, OpShiftLeftLogical
+            194..=196 => {
+                let Some(shape @ (21, _, count)) = result_shape() else {
+                    return invalid(o[0], "is not an integer scalar or vector type");
+                };
+                if self.value_shape(o[2]) != Some(shape) {
+                    return invalid(o[2], "does not have the width and component count of the result type");
+                }
+                if !matches!(self.value_shape(o[3]), Some((21, _, c)) if c == count) {
+                    return invalid(o[3], "is not an integer with the component count of the result type");
+                }
+            },
+            // OpVectorTimesScalar
+            142 => {
+                if !matches!(result_shape(), Some((22, _, _))) {
+                    return invalid(o[0], "is not a float vector type");
+                }
+                if self.type_of(o[2]) != Some(o[0]) {
+                    return invalid(o[2], "does not have the result type");
+                }
+                if self.type_of(o[3]) != self.member_type(o[0], 0) {
+                    return invalid(o[3], "does not have the component type of the result type");
+                }
+            },
+            // OpIEqual .. OpFUnordGreaterThanEqual
+            170..=191 => {
+                let Some((20, _, count)) = result_shape() else {
+                    return invalid(o[0], "is not a boolean scalar or vector type");
+                };
+                let kind = if instruction.opcode < 180 { 21 } else { 22 };
+                for id in &o[2..] {
+                    if !matches!(self.value_shape(*id), Some((k, _, c)) if k == kind && c == count) {
+                        return invalid(*id, "does not have the kind and component count required by the comparison");
+                    }
+                }
+                if self.value_shape(o[2]).map(|s| s.1) != self.value_shape(o[3]).map(|s| s.1) {
+                    return invalid(o[3], "has a different width than the other operand");
+                }
+            },
+            // OpLogicalEqual, OpLogicalNotEqual, OpLogicalOr, OpLogicalAnd, OpLogicalNot
+            164..=168 => {
+                if !matches!(result_shape(), Some((20, _, _))) {
+                    return invalid(o[0], "is not a boolean scalar or vector type");
+                }
+                for id in &o[2..] {
+                    if self.type_of(*id) != Some(o[0]) {
+                        return invalid(*id, "does not have the result type");
+                    }
+                }
+            },
+            // OpSelect
+            169 => {
+                let count = result_shape().map(|s| s.2).unwrap_or(1);
+                if !matches!(self.value_shape(o[2]), Some((20, _, c)) if c == 1 || c == count) {
+                    return invalid(o[2], "is not a boolean condition");
+                }
+                for id in &o[3..] {
+                    if self.type_of(*id) != Some(o[0]) {
+                        return invalid(*id, "does not have the result type");
+                    }
+                }
+            },
+            // OpConvertFToU, OpConvertFToS, OpConvertSToF, OpConvertUToF, OpUConvert, OpSConvert, OpFConvert
+            109..=115 => {
+                let (from, to) = match instruction.opcode {
+                    109 | 110 => (22, 21),
+                    111 | 112 => (21, 22),
+                    113 | 114 => (21, 21),
+                    _ => (22, 22),
+                };
+                let Some((_, _, count)) = result_shape().filter(|s| s.0 == to) else {
+                    return invalid(o[0], "does not have the kind of the conversion result");
+                };
+                if !matches!(self.value_shape(o[2]), Some((k, _, c)) if k == from && c == count) {
+                    return invalid(o[2], "does not have the kind or the component count required by the conversion");
+                }
+            },
+            // OpBitcast
+            124 => {
+                let bits = |shape: Option<(u16, u32, u32)>| shape.map(|(_, width, count)| width * count);
+                let from = bits(self.value_shape(o[2]));
+                if from.is_some() && bits(result_shape()).is_some() && from != bits(result_shape()) {
+                    return invalid(o[2], "does not have the bit width of the result type");
+                }
+            },
+            // OpCompositeConstruct
+            80 => {
+                if let Some((kind, width, count)) = result_shape() {
+                    let mut components = 0;
+                    for id in &o[2..] {
+                        match self.value_shape(*id) {
+                            Some((k, w, c)) if k == kind && w == width => components += c,
+                            _ => return invalid(*id, "does not have the component type of the result type"),
+                        }
+                    }
+                    if components != count {
+                        return invalid(o[1], "is constructed from a different number of components than it has");
+                    }
+                } else {
+                    for (index, id) in o[2..].iter().enumerate() {
+                        if self.type_of(*id) != self.member_type(o[0], index as u32) {
+                            return invalid(*id, "does not have the type of the member it constructs");
+                        }
+                    }
+                }
+            },
+            // OpCompositeExtract
+            81 => {
+                let mut type_id = self.type_of(o[2]);
+                for index in &o[3..] {
+                    type_id = type_id.and_then(|t| self.member_type(t, *index));
+                }
+                if type_id != Some(o[0]) {
+                    return invalid(o[2], "does not have a member of the result type at the given indices");
+                }
+            },
+            // OpLoad
+            61 if self.type_of(o[2]).and_then(|t| self.pointee(t)) != Some(o[0]) => {
+                return invalid(o[2], "is not a pointer to the result type");
+            },
+            // OpStore
+            62 => {
+                let Some(pointee) = self.type_of(o[0]).and_then(|t| self.pointee(t)) else {
+                    return invalid(o[0], "is not a pointer");
+                };
+                if self.type_of(o[1]) != Some(pointee) {
+                    return invalid(o[1], "does not have the type pointed to by the pointer");
+                }
+            },
+            // OpAccessChain, OpInBoundsAccessChain
+            65 | 66 => {
+                if self.pointee(o[0]).is_none() {
+                    return invalid(o[0], "is not a pointer type");
+                }
+                if self.type_of(o[2]).and_then(|t| self.pointee(t)).is_none() {
+                    return invalid(o[2], "is not a pointer");
+                }
+            },
+            // OpVariable
+            59 => {
+                let Some(pointee) = self.pointee(o[0]) else {
+                    return invalid(o[0], "is not a pointer type");
+                };
+                if self.types[&o[0]].operands[1] != o[2] {
+                    return invalid(o[0], "has a different storage class than the variable");
+                }
+                if let Some(initializer) = o.get(3) {
+                    if self.type_of(*initializer) != Some(pointee) {
+                        return invalid(*initializer, "does not have the type of the variable");
+                    }
+                }
+            },
+            // OpFunctionCall
+            57 => {
+                let Some(callee) = self.functions.get(&o[2]) else {
+                    return invalid(o[2], "is not a function");
+                };
+                if callee.definition.operands[0] != o[0] {
+                    return invalid(o[0], "differs from the return type of the called function");
+                }
+                if callee.function_parameters().count() != o.len() - 3 {
+                    return invalid(o[2], "is called with a different number of arguments than its parameters");
+                }
+                for (argument, parameter) in o[3..].iter().zip(callee.function_parameters()) {
+                    if self.type_of(*argument) != Some(parameter.operands[0]) {
+                        return invalid(*argument, "does not have the type of the parameter");
+                    }
+                }
+            },
+            // OpExtInst
+            12 => {
+                let Some(import) = self.module.ext_inst_imports.iter().find(|i| i.operands[0] == o[2]) else {
+                    return invalid(o[2], "is not an OpExtInstImport");
+                };
+
+                // Float GLSL.std.450 instructions whose operands have the result type, Sin .. Atanh, Atan2, Pow,
+                // Exp .. Log2, Sqrt, InverseSqrt and Fma.
+                let operand_count = match o[3] {
+                    13..=24 | 27..=32 => 1,
+                    25 | 26 => 2,
+                    50 => 3,
+                    _ => return Ok(()),
+                };
+                if import.string(1) != "GLSL.std.450" {
+                    return Ok(());
+                }
+
+                if !matches!(result_shape(), Some((22, _, _))) {
+                    return invalid(o[0], "is not a float scalar or vector type");
+                }
+                if o.len() != 4 + operand_count {
+                    return invalid(o[2], "is used with a wrong number of operands for the instruction");
+                }
+                for id in &o[4..] {
+                    if self.type_of(*id) != Some(o[0]) {
+                        return invalid(*id, "does not have the result type");
+                    }
+                }
+            },
+            // OpPhi
+            245 => {
+                for pair in o[2..].chunks(2) {
+                    if self.type_of(pair[0]).is_some_and(|t| t != o[0]) {
+                        return invalid(pair[0], "does not have the result type");
+                    }
+                }
+            },
+            // OpBranchConditional
+            250 if self.value_shape(o[0]) != Some((20, 0, 1)) => return invalid(o[0], "is not a boolean scalar"),
+            // OpReturnValue
+            254 if self.type_of(o[0]) != return_type => {
+                return invalid(o[0], "does not have the return type of the function");
+            },
+            _ => {},
+        }
+
+        Ok(())
+    }
+}
+
+fn function_instructions(function: &Function) -> impl Iterator<Item = &Instruction> {
+    function.parameters.iter().chain(function.blocks.iter().flat_map(|b| &b.instructions))
+}
+
+fn is_terminator(opcode: u16) -> bool {
+    // OpBranch, OpBranchConditional, OpSwitch, OpKill, OpReturn, OpReturnValue, OpUnreachable, OpTerminateInvocation
+    matches!(opcode, 249..=255 | 4416)
+}
+
+/// Returns the labels an instruction branches or refers to.
+fn label_operands(instruction: &Instruction) -> Vec<u32> {
+    let o = &instruction.operands;
+    match instruction.opcode {
+        // OpLoopMerge
+        246 => o[..2].to_vec(),
+        // OpSelectionMerge, OpBranch
+        247 | 249 => vec![o[0]],
+        // OpBranchConditional
+        250 => o[1..3].to_vec(),
+        // OpSwitch, the default label and pairs of a literal and a label. Literals are one word for 32 bit selectors,
+        // which are the only ones used by the rewriter.
+        251 => std::iter::once(o[1]).chain(o[2..].chunks(2).filter_map(|c| c.get(1).copied())).collect(),
+        // OpPhi, pairs of a value and a parent block
+        245 => o[2..].chunks(2).map(|c| c[1]).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Returns the ids which an instruction uses and which must be defined before it, without its result type. Returns
+/// `None` for opcodes whose operand layout is not known.
+fn used_ids(instruction: &Instruction) -> Option<Vec<u32>> {
+    let o = &instruction.operands;
+    let ids = match instruction.opcode {
+        // OpNop, OpUndef, OpNoLine, OpReturn, OpKill, OpUnreachable, OpTerminateInvocation, OpTypeVoid, OpTypeBool,
+        // OpTypeInt, OpTypeFloat, OpTypeSampler, OpConstantTrue, OpConstantFalse, OpConstant, OpConstantNull,
+        // OpSpecConstantTrue, OpSpecConstantFalse, OpSpecConstant
+        0 | 1 | 317 | 252 | 253 | 255 | 4416 | 19..=22 | 26 | 41..=43 | 46 | 48..=50 => &[][..],
+        // OpLoopMerge, OpSelectionMerge, OpBranch and OpPhi refer to labels and values which may be defined later
+        245..=247 | 249 => &[][..],
+        // OpLine
+        8 => &o[..1],
+        // OpExtInst, set and operands
+        12 => return Some(std::iter::once(o[2]).chain(o[4..].iter().copied()).collect()),
+        // OpTypeVector, OpTypeMatrix, OpTypeImage, OpTypeSampledImage, OpTypeRuntimeArray
+        23..=25 | 27 | 29 => &o[1..2],
+        // OpTypeArray
+        28 => &o[1..3],
+        // OpTypeStruct, OpTypeFunction
+        30 | 33 => &o[1..],
+        // OpTypePointer
+        32 => &o[2..3],
+        // OpConstantComposite, OpSpecConstantComposite
+        44 | 51 => &o[2..],
+        // OpFunctionCall, arguments
+        57 => &o[3..],
+        // OpVariable, initializer
+        59 => &o[3..],
+        // OpLoad, OpArrayLength, OpCompositeExtract, OpCopyObject, conversion instructions
+        61 | 68 | 81 | 83 | 109..=124 => &o[2..3],
+        // OpStore, OpCopyMemory
+        62 | 63 => &o[..2],
+        // OpAccessChain, OpInBoundsAccessChain, OpCompositeConstruct, arithmetic, relational, logical and bit
+        // instructions
+        65 | 66 | 77 | 78 | 80 | 126..=152 | 154..=191 | 194..=205 => &o[2..],
+        // OpVectorShuffle, OpCompositeInsert
+        79 | 82 => &o[2..4],
+        // OpControlBarrier, OpMemoryBarrier
+        224 | 225 => &o[..],
+        // OpBranchConditional, OpSwitch, OpReturnValue
+        250 | 251 | 254 => &o[..1],
+        _ => return None,
+    };
+    Some(ids.to_vec())
+}
+
+/// Returns the number of operands which instructions with the given opcode must have, without optional operands.
+fn minimum_operand_count(opcode: u16) -> usize {
+    let (has_type, has_result) = result_layout(opcode);
+    let count = match opcode {
+        // OpName, OpMemberName, OpLine, OpExecutionMode, OpCapability, OpTypeFloat, OpTypeImage, OpTypeSampledImage,
+        // OpTypeRuntimeArray, OpTypeFunction, OpConstant, OpFunctionCall, OpVariable, OpLoad, OpAccessChain,
+        // OpInBoundsAccessChain, OpDecorate, OpMemberDecorate, OpCompositeExtract, OpCopyObject, conversion
+        // instructions, OpSNegate, OpFNegate, relational instructions with one operand, OpLogicalNot, OpNot, bit
+        // instructions with one operand, OpBranch, OpReturnValue
+        5 | 6 | 8 | 16 | 17 | 22 | 25 | 27 | 29 | 33 | 43 | 57 | 59 | 61 | 65 | 66 | 71 | 72 | 81 | 83 | 109..=124 | 126
+        | 127 | 154..=163 | 168 | 200..=205 | 249 | 254 => 1,
+        // OpExtInst, OpMemoryModel, OpEntryPoint, OpTypeInt, OpTypeVector, OpTypeMatrix, OpTypeArray, OpTypePointer,
+        // OpFunction, OpStore, OpCopyMemory, OpArrayLength, OpVectorExtractDynamic, OpVectorShuffle,
+        // OpCompositeInsert, arithmetic instructions with two operands, binary logical, relational and bit
+        // instructions, OpMemoryBarrier, OpSelectionMerge, OpSwitch
+        12 | 14 | 15 | 21 | 23 | 24 | 28 | 32 | 54 | 62 | 63 | 68 | 77 | 79 | 82 | 128..=152 | 164..=167
+        | 170..=199 | 225 | 247 | 251 => 2,
+        // OpVectorInsertDynamic, OpSelect, OpControlBarrier, OpLoopMerge, OpBranchConditional
+        78 | 169 | 224 | 246 | 250 => 3,
+        _ => 0,
+    };
+
+    has_type as usize + has_result as usize + count
+}
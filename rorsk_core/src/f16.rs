@@ -0,0 +1,48 @@
+//! Conversions of IEEE-754 binary16 values, which are stored as their bits because Rust has no stable f16 type.
+
+pub fn f16_to_f32(h: u16) -> f32 {
+    let sign = ((h & 0x8000) as u32) << 16;
+    let exp = ((h >> 10) & 0x1F) as u32;
+    let fraction = (h & 0x3FF) as u32;
+
+    let bits = match exp {
+        0x1F => sign | 0x7F800000 | (fraction << 13),
+        0 if fraction == 0 => sign,
+        0 => {
+            // Subnormal halves are fraction * 2^-24, which is normal in f32.
+            let msb = 31 - fraction.leading_zeros();
+            sign | ((msb + 103) << 23) | ((fraction << (23 - msb)) & 0x7FFFFF)
+        }
+        _ => sign | ((exp + 112) << 23) | (fraction << 13),
+    };
+    f32::from_bits(bits)
+}
+
+pub fn f32_to_f16(f: f32) -> u16 {
+    let bits = f.to_bits();
+    let sign = (bits >> 16) & 0x8000;
+    let abs = bits & 0x7FFFFFFF;
+
+    if abs > 0x7F800000 {
+        return (sign | 0x7E00 | ((abs >> 13) & 0x3FF)) as u16;
+    }
+    if abs >= 0x47800000 {
+        return (sign | 0x7C00) as u16;
+    }
+
+    // Normal halves keep 10 fraction bits, subnormal halves are counted in units of 2^-24.
+    let exp = abs >> 23;
+    let sig = (abs & 0x7FFFFF) | 0x800000;
+    let normal = exp >= 113;
+    let shift = if normal { 13 } else { (126 - exp).min(31) };
+
+    // The hidden bit is carried into the exponent field, so normal halves get exp - 113 added on top.
+    let mut h = (sig >> shift) + if normal { (exp - 113) << 10 } else { 0 };
+    let rest = sig & ((1 << shift) - 1);
+    let half = 1 << (shift - 1);
+    if rest > half || (rest == half && (h & 1) != 0) {
+        h += 1;
+    }
+
+    (sign | h) as u16
+}
@@ -0,0 +1,3 @@
+pub mod f16;
+pub mod result_file;
+pub mod sf32;
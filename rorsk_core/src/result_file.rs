@@ -0,0 +1,292 @@
+use std::{io::{self, Read, Write}, fs::File, path::Path};
+
+pub const MAGIC: [u8; 8] = *b"RORSKRES";
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ElementType {
+    I32,
+    F32,
+    F64,
+    F16,
+}
+
+impl ElementType {
+    pub fn name(self) -> &'static str {
+        match self {
+            ElementType::I32 => "i32",
+            ElementType::F32 => "f32",
+            ElementType::F64 => "f64",
+            ElementType::F16 => "f16",
+        }
+    }
+
+    pub fn size(self) -> usize {
+        match self {
+            ElementType::I32 | ElementType::F32 => 4,
+            ElementType::F64 => 8,
+            ElementType::F16 => 2,
+        }
+    }
+
+    fn to_id(self) -> u32 {
+        match self {
+            ElementType::I32 => 0,
+            ElementType::F32 => 1,
+            ElementType::F64 => 2,
+            ElementType::F16 => 3,
+        }
+    }
+
+    fn from_id(id: u32) -> Option<Self> {
+        match id {
+            0 => Some(ElementType::I32),
+            1 => Some(ElementType::F32),
+            2 => Some(ElementType::F64),
+            3 => Some(ElementType::F16),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeviceInfo {
+    pub name: String,
+    pub vendor_id: u32,
+    pub device_id: u32,
+    pub driver_version: u32,
+    /// `VkDriverId`, 0 when unknown.
+    pub driver_id: u32,
+    pub driver_name: String,
+    pub driver_info: String,
+    /// Packed like `VK_MAKE_API_VERSION`, 0 when unknown.
+    pub api_version: u32,
+    /// Major, minor, subminor and patch of the passed conformance test suite.
+    pub conformance_version: [u8; 4],
+    /// 0 when unknown.
+    pub subgroup_size: u32,
+    /// Float controls of 16, 32 and 64-bit floats, `None` when the device does not report them.
+    pub float_controls: Option<[FloatControls; 3]>,
+    /// Index of the device in the enumeration order of the machine which computed the result.
+    pub index: u32,
+    /// Zeroes when unknown.
+    pub uuid: [u8; 16],
+}
+
+/// Float controls of one float width, as reported by `VkPhysicalDeviceFloatControlsProperties`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FloatControls {
+    pub signed_zero_inf_nan_preserve: bool,
+    pub denorm_preserve: bool,
+    pub denorm_flush_to_zero: bool,
+    pub rounding_mode_rte: bool,
+    pub rounding_mode_rtz: bool,
+}
+
+impl FloatControls {
+    fn to_bits(self) -> u32 {
+        self.signed_zero_inf_nan_preserve as u32
+            | (self.denorm_preserve as u32) << 1
+            | (self.denorm_flush_to_zero as u32) << 2
+            | (self.rounding_mode_rte as u32) << 3
+            | (self.rounding_mode_rtz as u32) << 4
+    }
+
+    fn from_bits(bits: u32) -> Self {
+        FloatControls {
+            signed_zero_inf_nan_preserve: bits & 1 != 0,
+            denorm_preserve: bits & 1 << 1 != 0,
+            denorm_flush_to_zero: bits & 1 << 2 != 0,
+            rounding_mode_rte: bits & 1 << 3 != 0,
+            rounding_mode_rtz: bits & 1 << 4 != 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResultHeader {
+    pub element_type: ElementType,
+    pub problem_name: String,
+    pub expression: String,
+    pub input_sha256: String,
+    pub device: DeviceInfo,
+    pub is_conformant: bool,
+    /// Mode of the conformant rewrite, like `sf32` or `ieee`, empty for unconformant data.
+    pub conformant_mode: String,
+    /// Computed on the CPU instead of a device, `device` describes the pseudo-device.
+    pub is_reference: bool,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+/// Layout, all integers little-endian and strings prefixed with their u32 byte length:
+/// magic, format version, element type, is conformant (u32), conformant mode, is reference (u32), timestamp (u64),
+/// problem name, expression, input SHA256, device name, vendor id, device id, driver version, driver id, driver name,
+/// driver info, API version, conformance version (4 bytes), subgroup size, whether float controls are known (u32),
+/// float controls of 16, 32 and 64-bit floats (u32 bit sets), device index, device UUID (16 bytes), data length in
+/// bytes (u64), data.
+#[derive(Debug, Clone)]
+pub struct ResultFile {
+    pub header: ResultHeader,
+    pub data: Vec<u8>,
+}
+
+impl ResultFile {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read(&mut File::open(path)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut file = File::create(path)?;
+        self.write(&mut file)?;
+        file.flush()
+    }
+
+    pub fn read(reader: &mut impl Read) -> io::Result<Self> {
+        let header = ResultHeader::read(reader)?;
+
+        let length = read_u64(reader)?;
+        if !length.is_multiple_of(header.element_type.size() as u64) {
+            return Err(invalid_data("data length is not a multiple of the element size"));
+        }
+        let data = read_bytes(reader, length)?;
+
+        Ok(ResultFile { header, data })
+    }
+
+    pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        self.header.write(writer)?;
+        writer.write_all(&(self.data.len() as u64).to_le_bytes())?;
+        writer.write_all(&self.data)
+    }
+}
+
+impl ResultHeader {
+    /// Reads only the header, leaving the reader positioned at the data length.
+    pub fn read(reader: &mut impl Read) -> io::Result<Self> {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(invalid_data("not a RORSK result file"));
+        }
+
+        let version = read_u32(reader)?;
+        if version != FORMAT_VERSION {
+            return Err(invalid_data(&format!("unsupported format version {version}")));
+        }
+
+        let element_type = ElementType::from_id(read_u32(reader)?)
+            .ok_or_else(|| invalid_data("unknown element type"))?;
+        let is_conformant = read_u32(reader)? != 0;
+        let conformant_mode = read_string(reader)?;
+        let is_reference = read_u32(reader)? != 0;
+        let timestamp = read_u64(reader)?;
+        let problem_name = read_string(reader)?;
+        let expression = read_string(reader)?;
+        let input_sha256 = read_string(reader)?;
+
+        let mut device = DeviceInfo {
+            name: read_string(reader)?,
+            vendor_id: read_u32(reader)?,
+            device_id: read_u32(reader)?,
+            driver_version: read_u32(reader)?,
+            driver_id: read_u32(reader)?,
+            driver_name: read_string(reader)?,
+            driver_info: read_string(reader)?,
+            api_version: read_u32(reader)?,
+            ..Default::default()
+        };
+        reader.read_exact(&mut device.conformance_version)?;
+        device.subgroup_size = read_u32(reader)?;
+        let has_float_controls = read_u32(reader)? != 0;
+        let mut float_controls = [FloatControls::default(); 3];
+        for controls in &mut float_controls {
+            *controls = FloatControls::from_bits(read_u32(reader)?);
+        }
+        device.float_controls = has_float_controls.then_some(float_controls);
+        device.index = read_u32(reader)?;
+        reader.read_exact(&mut device.uuid)?;
+
+        Ok(ResultHeader {
+            element_type,
+            problem_name,
+            expression,
+            input_sha256,
+            device,
+            is_conformant,
+            conformant_mode,
+            is_reference,
+            timestamp,
+        })
+    }
+
+    pub fn write(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        writer.write_all(&self.element_type.to_id().to_le_bytes())?;
+        writer.write_all(&(self.is_conformant as u32).to_le_bytes())?;
+        write_string(writer, &self.conformant_mode)?;
+        writer.write_all(&(self.is_reference as u32).to_le_bytes())?;
+        writer.write_all(&self.timestamp.to_le_bytes())?;
+        write_string(writer, &self.problem_name)?;
+        write_string(writer, &self.expression)?;
+        write_string(writer, &self.input_sha256)?;
+        write_string(writer, &self.device.name)?;
+        writer.write_all(&self.device.vendor_id.to_le_bytes())?;
+        writer.write_all(&self.device.device_id.to_le_bytes())?;
+        writer.write_all(&self.device.driver_version.to_le_bytes())?;
+        writer.write_all(&self.device.driver_id.to_le_bytes())?;
+        write_string(writer, &self.device.driver_name)?;
+        write_string(writer, &self.device.driver_info)?;
+        writer.write_all(&self.device.api_version.to_le_bytes())?;
+        writer.write_all(&self.device.conformance_version)?;
+        writer.write_all(&self.device.subgroup_size.to_le_bytes())?;
+        writer.write_all(&(self.device.float_controls.is_some() as u32).to_le_bytes())?;
+        for controls in self.device.float_controls.unwrap_or_default() {
+            writer.write_all(&controls.to_bits().to_le_bytes())?;
+        }
+        writer.write_all(&self.device.index.to_le_bytes())?;
+        writer.write_all(&self.device.uuid)
+    }
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_string(reader: &mut impl Read) -> io::Result<String> {
+    let length = read_u32(reader)?;
+    let bytes = read_bytes(reader, length as u64)?;
+    String::from_utf8(bytes).map_err(|_| invalid_data("string is not valid UTF-8"))
+}
+
+/// Reads `length` bytes, which come from the file itself, so the buffer only grows with the bytes actually read
+/// instead of allocating a corrupt length up front.
+fn read_bytes(reader: &mut impl Read, length: u64) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    reader.take(length).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != length {
+        return Err(invalid_data("file ends before the length stored in it"));
+    }
+    Ok(bytes)
+}
+
+fn write_string(writer: &mut impl Write, value: &str) -> io::Result<()> {
+    writer.write_all(&(value.len() as u32).to_le_bytes())?;
+    writer.write_all(value.as_bytes())
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_owned())
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,100 @@
+use std::io::ErrorKind;
+
+use super::{DeviceInfo, ElementType, FloatControls, ResultFile, ResultHeader, FORMAT_VERSION, MAGIC};
+
+fn result_file() -> ResultFile {
+    let controls = |bits| FloatControls::from_bits(bits);
+    ResultFile {
+        header: ResultHeader {
+            element_type: ElementType::F16,
+            problem_name: "f16-add".to_owned(),
+            expression: "r = a + b;".to_owned(),
+            input_sha256: "ab".repeat(32),
+            device: DeviceInfo {
+                name: "Device ż".to_owned(),
+                vendor_id: 0x10DE,
+                device_id: 0x2204,
+                driver_version: 7,
+                driver_id: 4,
+                driver_name: "driver".to_owned(),
+                driver_info: "1.2.3".to_owned(),
+                api_version: 0x0040_3000,
+                conformance_version: [1, 3, 5, 0],
+                subgroup_size: 32,
+                float_controls: Some([controls(0b00001), controls(0b10110), controls(0b01000)]),
+                index: 3,
+                uuid: [9; 16],
+            },
+            is_conformant: true,
+            conformant_mode: "ieee".to_owned(),
+            is_reference: true,
+            timestamp: 1_700_000_000,
+        },
+        data: vec![1, 2, 3, 4, 5, 6],
+    }
+}
+
+fn bytes(file: &ResultFile) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    file.write(&mut bytes).unwrap();
+    bytes
+}
+
+#[test]
+fn write_read_round_trip() {
+    let file = result_file();
+    let read = ResultFile::read(&mut bytes(&file).as_slice()).unwrap();
+    assert_eq!(read.header, file.header);
+    assert_eq!(read.data, file.data);
+
+    // Unknown float controls stay unknown.
+    let mut file = result_file();
+    file.header.device.float_controls = None;
+    assert_eq!(ResultFile::read(&mut bytes(&file).as_slice()).unwrap().header, file.header);
+}
+
+#[test]
+fn header_leaves_reader_at_data_length() {
+    let bytes = bytes(&result_file());
+    let mut reader = bytes.as_slice();
+    ResultHeader::read(&mut reader).unwrap();
+    assert_eq!(reader, [6, 0, 0, 0, 0, 0, 0, 0, 1, 2, 3, 4, 5, 6]);
+}
+
+#[test]
+fn truncated_files_are_rejected() {
+    let bytes = bytes(&result_file());
+    for length in [0, 8, 20, bytes.len() - 7, bytes.len() - 1] {
+        assert!(ResultFile::read(&mut &bytes[..length]).is_err(), "{length}");
+    }
+}
+
+#[test]
+fn corrupt_lengths_do_not_allocate() {
+    let file = result_file();
+    let mut corrupt = bytes(&file);
+    let data_length = corrupt.len() - file.data.len() - 8;
+    corrupt[data_length..data_length + 8].copy_from_slice(&(u64::MAX - 1).to_le_bytes());
+    assert_eq!(ResultFile::read(&mut corrupt.as_slice()).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // The conformant mode is the first string, after magic, version, element type and is conformant.
+    let mut corrupt = bytes(&file);
+    corrupt[20..24].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert_eq!(ResultFile::read(&mut corrupt.as_slice()).unwrap_err().kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn other_files_are_rejected() {
+    let mut other = bytes(&result_file());
+    other[..8].copy_from_slice(b"NOTRORSK");
+    assert_eq!(ResultFile::read(&mut other.as_slice()).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    let mut newer = bytes(&result_file());
+    assert_eq!(newer[..8], MAGIC);
+    newer[8..12].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+    assert_eq!(ResultFile::read(&mut newer.as_slice()).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    let mut odd = result_file();
+    odd.data.push(0);
+    assert_eq!(ResultFile::read(&mut bytes(&odd).as_slice()).unwrap_err().kind(), ErrorKind::InvalidData);
+}
@@ -0,0 +1,174 @@
+//! Bit-exact CPU emulation of the sf32 software float emitted by the conformant rewriter of rorsk_conformant.
+//!
+//! An sf32 is an `i32` holding a signed 24 bit fraction in its upper bits and a biased 8 bit exponent in its lowest
+//! byte. Every function mirrors the SPIR-V function of the same name, including its integer overflow behaviour.
+
+pub fn from_fraction_and_exp(traw32: i32, exp: i32) -> i32 {
+    if exp < 0 {
+        return 0;
+    }
+
+    traw32.wrapping_shl(8) | exp.min(255)
+}
+
+pub fn from_float(value: f32) -> i32 {
+    if value == 0.0 {
+        return 0;
+    }
+
+    let t754raw = value.to_bits() as i32;
+    let mut t_raction = (t754raw & 0x007FFFFF) + 0x00800000;
+    let exponent = (t754raw & 0x7FFFFFFF) >> 23;
+    if t754raw < 0 {
+        t_raction = -t_raction;
+    }
+
+    from_fraction_and_exp(t_raction >> 1, exponent - 22)
+}
+
+pub fn get_fraction(sf32: i32) -> i32 {
+    sf32 >> 8
+}
+
+pub fn get_exponent(sf32: i32) -> i32 {
+    sf32 & 0xFF
+}
+
+/// fraction * 2^exponent, rounded to nearest even when it falls into the subnormal range.
+pub fn to_float(sf32: i32) -> f32 {
+    let fraction = get_fraction(sf32);
+    if fraction == 0 {
+        return 0.0;
+    }
+
+    let exponent = get_exponent(sf32) - 127;
+    let magnitude = fraction.unsigned_abs();
+    let msb = 31 - magnitude.leading_zeros() as i32;
+    f32::from_bits(ieee_f32_round_pack((fraction < 0) as u32, exponent + msb + 126, magnitude << (30 - msb)))
+}
+
+pub fn normalize_64(traw_value: i64, t_exponent: i32) -> i32 {
+    if traw_value == 0 {
+        return 0;
+    }
+
+    // abs(i64::MIN) stays negative, its highest set bit is still 63.
+    let index = 63 - traw_value.wrapping_abs().leading_zeros() as i32;
+    if index <= 22 {
+        let u_delta = 22 - index;
+        from_fraction_and_exp((traw_value << u_delta) as i32, t_exponent - u_delta)
+    } else {
+        let u_delta = index - 22;
+        from_fraction_and_exp((traw_value >> u_delta) as i32, t_exponent + u_delta)
+    }
+}
+
+pub fn add(lhs: i32, rhs: i32) -> i32 {
+    let lhs_exponent = get_exponent(lhs);
+    let rhs_exponent = get_exponent(rhs);
+    let mut lhs_raw = (get_fraction(lhs) as i64) << 32;
+    let mut rhs_raw = (get_fraction(rhs) as i64) << 32;
+
+    if lhs_exponent < rhs_exponent {
+        lhs_raw >>= (rhs_exponent - lhs_exponent).min(63);
+        normalize_64(lhs_raw.wrapping_add(rhs_raw), rhs_exponent - 32)
+    } else {
+        rhs_raw >>= (lhs_exponent - rhs_exponent).min(63);
+        normalize_64(lhs_raw.wrapping_add(rhs_raw), lhs_exponent - 32)
+    }
+}
+
+pub fn sub(lhs: i32, rhs: i32) -> i32 {
+    add(lhs, from_fraction_and_exp(get_fraction(rhs).wrapping_neg(), get_exponent(rhs)))
+}
+
+pub fn mul(lhs: i32, rhs: i32) -> i32 {
+    let traw_value = get_fraction(lhs) as i64 * get_fraction(rhs) as i64;
+    let t_exponent = get_exponent(lhs) + get_exponent(rhs) - 127;
+    normalize_64(traw_value, t_exponent)
+}
+
+pub fn div(lhs: i32, rhs: i32) -> i32 {
+    let n_divid = get_fraction(rhs);
+    if n_divid == 0 {
+        return 0;
+    }
+
+    let traw_value = ((get_fraction(lhs) as i64) << 32) / n_divid as i64;
+    let t_exponent = get_exponent(lhs) - get_exponent(rhs) + 95;
+    normalize_64(traw_value, t_exponent)
+}
+
+/// Conformant f32 addition, as computed by the rewritten shader in sf32 mode.
+pub fn conformant_add(lhs: f32, rhs: f32) -> f32 {
+    conformant_binary(lhs, rhs, add, |a, b| a + b)
+}
+
+/// Conformant f32 subtraction, as computed by the rewritten shader in sf32 mode.
+pub fn conformant_sub(lhs: f32, rhs: f32) -> f32 {
+    conformant_binary(lhs, rhs, sub, |a, b| a - b)
+}
+
+/// Conformant f32 multiplication, as computed by the rewritten shader in sf32 mode.
+pub fn conformant_mul(lhs: f32, rhs: f32) -> f32 {
+    conformant_binary(lhs, rhs, mul, |a, b| a * b)
+}
+
+/// Conformant f32 division, as computed by the rewritten shader in sf32 mode.
+pub fn conformant_div(lhs: f32, rhs: f32) -> f32 {
+    conformant_binary(lhs, rhs, div, |a, b| a / b)
+}
+
+fn conformant_binary(
+    lhs: f32, rhs: f32, sf32_operation: fn(i32, i32) -> i32, ieee_operation: fn(f32, f32) -> f32
+) -> f32 {
+    // Special values can not be represented by sf32, so they are handled by the IEEE-754 implementation.
+    if is_special(lhs) || is_special(rhs) {
+        return ieee_f32(lhs, rhs, ieee_operation);
+    }
+
+    to_float(sf32_operation(from_float(lhs), from_float(rhs)))
+}
+
+/// Zero, subnormal, infinity and NaN have an exponent of either 0 or 0xFF.
+fn is_special(value: f32) -> bool {
+    ((value.to_bits() >> 23).wrapping_add(1) & 0xFF) < 2
+}
+
+/// Correctly rounded IEEE-754 operation with the NaN results of the rewriter, a NaN operand is quieted and
+/// propagated, an invalid operation returns the positive default NaN.
+pub fn ieee_f32(lhs: f32, rhs: f32, operation: fn(f32, f32) -> f32) -> f32 {
+    let result = operation(lhs, rhs);
+    if !result.is_nan() {
+        result
+    } else if lhs.is_nan() {
+        f32::from_bits(lhs.to_bits() | 0x400000)
+    } else if rhs.is_nan() {
+        f32::from_bits(rhs.to_bits() | 0x400000)
+    } else {
+        f32::from_bits(0x7FC00000)
+    }
+}
+
+fn ieee_f32_round_pack(sign: u32, exp: i32, sig: u32) -> u32 {
+    if exp > 0xFD {
+        return (sign << 31) | 0x7F800000;
+    }
+
+    let (exp, sig) = if exp < 0 { (0, shift_right_jam(sig, exp.unsigned_abs())) } else { (exp, sig) };
+
+    let round_bits = sig & 0x7F;
+    let mut sig = (sig + 0x40) >> 7;
+    if round_bits == 0x40 {
+        sig &= !1;
+    }
+
+    // A carry out of the significand increments the exponent, which also rounds up to infinity or to the smallest
+    // normal number.
+    (sign << 31).wrapping_add((exp as u32) << 23).wrapping_add(sig)
+}
+
+fn shift_right_jam(a: u32, dist: u32) -> u32 {
+    let d = dist.min(31);
+    (a >> d) | ((a & ((1 << d) - 1)) != 0) as u32
+}
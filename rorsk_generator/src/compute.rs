@@ -1,50 +1,111 @@
 use core::slice;
-use std::{time::Instant, fs::{self, File}, mem, io::{Write, Read}, path::Path};
+use std::{time::{Instant, SystemTime, UNIX_EPOCH}, fs, mem, io::Read, path::PathBuf};
 
+use rorsk_core::result_file::{DeviceInfo, ElementType, ResultFile, ResultHeader};
 use vulkano::buffer::BufferContents;
 
-use crate::{runner, conformant};
+use rorsk_conformant::{disassembler, spirv, OpSet, Options, Processed};
 
-pub(crate) struct Compute<T> where T: BufferContents + Clone {
+use crate::{error::Error, runner::Runner, interpreter, reference::{self, Reference}, Problem};
+
+#[derive(Clone)]
+pub(crate) struct Settings {
+    pub conformant_mode: rorsk_conformant::Mode,
+    pub output_dir: PathBuf,
+    pub disassemble: bool,
+    pub unconformant: bool,
+    pub conformant: bool,
+    pub reference: bool,
+}
+
+pub(crate) struct Compute<T> where T: BufferContents + Clone + Reference {
     initial_data: Vec<T>,
+    initial_data_sha256: String,
+    element_type: ElementType,
+    settings: Settings,
 }
 
-impl<T> Compute<T> where T: BufferContents + Clone {
-    pub(crate) fn new(initial_data: Vec<T>) -> Self {
-        let vec = unsafe {
-            slice::from_raw_parts::<u8>(initial_data.as_ptr() as *const u8, initial_data.len() * mem::size_of::<T>())
-        };
-        let sha256 = sha256::digest(vec);
+/// SPIR-V code of a problem, compiled once and run on every device.
+pub(crate) struct Shaders {
+    unconformant: Vec<u8>,
+    conformant: Option<Vec<u8>>,
+}
+
+impl<T> Compute<T> where T: BufferContents + Clone + Reference {
+    pub(crate) fn new(initial_data: Vec<T>, element_type: ElementType, settings: Settings) -> Self {
+        assert_eq!(mem::size_of::<T>(), element_type.size());
+
+        let sha256 = sha256::digest(as_bytes(&initial_data));
         println!("Loaded initial data. SHA256: `{sha256}`.");
 
         Compute {
             initial_data,
+            initial_data_sha256: sha256,
+            element_type,
+            settings,
         }
     }
 
-    pub(crate) fn compute(&self, problem_name: &str, glsl_type: &str, expression: &str) {
-        let offset = self.initial_data.len() / 2;
+    /// Operands `a`, `b` and `c`, stored one after another in the initial data.
+    fn operands(&self) -> [&[T]; 3] {
+        let count = self.initial_data.len() / 3;
+        let (a, rest) = self.initial_data.split_at(count);
+        let (b, c) = rest.split_at(count);
+        [a, b, c]
+    }
+
+    pub(crate) fn compile(&self, problem: &Problem) -> Result<Shaders, Error> {
+        let result_type = glsl_type(problem.operation.result_type(self.element_type));
+        let glsl_type = glsl_type(self.element_type);
+        let extensions = match self.element_type {
+            ElementType::F16 => r#"
+            #extension GL_EXT_shader_explicit_arithmetic_types_float16 : require
+            #extension GL_EXT_shader_16bit_storage : require
+            "#,
+            _ => "",
+        };
+
+        // Every operand is bound to its own buffer, followed by the result.
+        let arity = problem.operation.arity();
+        let mut buffers = String::new();
+        let mut operands = String::new();
+        for (binding, name) in ["a", "b", "c"][..arity].iter().enumerate() {
+            buffers.push_str(&format!(r#"
+            layout(set = 0, binding = {binding}) readonly buffer Operand{binding} {{
+                {glsl_type} {name}_data[];
+            }};
+            "#));
+            operands.push_str(&format!(r#"
+                {glsl_type} {name} = {name}_data[i];"#));
+        }
 
         let mut spirv_code = Vec::new();
-        glsl_to_spirv::compile(&format!("{}{glsl_type}{}{glsl_type}{}{glsl_type}{}{offset}{}{glsl_type}{}{expression}{}", r#"
+        glsl_to_spirv::compile(&format!(r#"
             #version 450
-
+            {extensions}
             layout(local_size_x = 64, local_size_y = 1, local_size_z = 1) in;
-
-            layout(set = 0, binding = 0) buffer Data {
-                "#, r#" data[];
-            };
-
-            void main() {
-                "#, r#" a = data[gl_GlobalInvocationID.x];
-                "#, r#" b = data[gl_GlobalInvocationID.x + "#, r#"];
-                "#, r#" r;
-
-                "#, r#"
-
-                data[gl_GlobalInvocationID.x] = r;
-            }
-        "#), glsl_to_spirv::ShaderType::Compute).unwrap().read_to_end(&mut spirv_code).unwrap();
+            {buffers}
+            layout(set = 0, binding = {arity}) writeonly buffer Result {{
+                {result_type} r_data[];
+            }};
+
+            void main() {{
+                // The last workgroup can reach past the end of the data.
+                uint i = gl_GlobalInvocationID.x;
+                if (i >= uint(r_data.length())) {{
+                    return;
+                }}
+                {operands}
+                {result_type} r;
+
+                {}
+
+                r_data[i] = r;
+            }}
+        "#, problem.expression), glsl_to_spirv::ShaderType::Compute)
+            .map_err(Error::ShaderCompile)?
+            .read_to_end(&mut spirv_code)
+            .map_err(|e| Error::ShaderCompile(e.to_string()))?;
 
         /*glsl_to_spirv::compile(r#"
             #version 450
@@ -139,31 +200,180 @@ impl<T> Compute<T> where T: BufferContents + Clone {
             }
         "#, glsl_to_spirv::ShaderType::Compute).unwrap().read_to_end(&mut spirv_code).unwrap();*/
 
-        self.compute_impl(problem_name, &spirv_code, false);
+        let conformant = if self.settings.conformant {
+            let options = Options {
+                mode: self.settings.conformant_mode,
+                op_sets: OpSet::ALL.to_vec(),
+            };
+            let processed = rorsk_conformant::rewrite(&spirv::words_from_bytes(&spirv_code), options)?;
+            if self.settings.disassemble {
+                self.save_disassembly(problem.name, &processed)?;
+            }
+            Some(spirv::words_to_bytes(&processed.module.emit()))
+        } else {
+            None
+        };
+
+        Ok(Shaders { unconformant: spirv_code, conformant })
+    }
+
+    /// Runs the shaders on the device, or with the SPIR-V interpreter when there is no runner.
+    pub(crate) fn compute(
+        &self, problem: &Problem, shaders: &Shaders, runner: Option<&Runner>
+    ) -> Result<(), Error> {
+        if self.settings.unconformant {
+            self.compute_impl(problem, &shaders.unconformant, false, runner)?;
+        }
+
+        if let Some(conformant) = &shaders.conformant {
+            self.compute_impl(problem, conformant, true, runner)?;
+        }
+        Ok(())
+    }
+
+    /// Computes the reference and the conformant emulation, they do not depend on the device.
+    pub(crate) fn compute_on_cpu(&self, problem: &Problem) -> Result<(), Error> {
+        if self.settings.reference {
+            self.compute_reference(problem)?;
+        }
 
-        let conformant = conformant::process(spirv_code);
-        self.compute_impl(problem_name, &conformant, true);
+        if self.settings.reference && self.settings.conformant {
+            self.compute_conformant_emulation(problem)?;
+        }
+        Ok(())
     }
 
-    fn compute_impl(&self, problem_name: &str, spirv_code: &[u8], is_conformant: bool) {
+    fn compute_reference(&self, problem: &Problem) -> Result<(), Error> {
         let now = Instant::now();
+        println!("Computing reference data from problem named `{}` on the CPU...", problem.name);
 
-        let conformant_str = if is_conformant { "conformant" } else { "unconformant" };
-        println!("Computing {conformant_str} data from problem named `{problem_name}`...");
+        let Some(data) = reference::compute(problem.operation, self.operands()) else {
+            println!("Skipped, problem `{}` has no reference implementation.", problem.name);
+            return Ok(());
+        };
+        println!("Done in {} ms.", now.elapsed().as_millis());
 
-        let output = runner::run::<T>(spirv_code, &self.initial_data, self.initial_data.len() / 64 / 2);
+        let device = DeviceInfo {
+            name: "CPU reference".to_owned(),
+            vendor_id: 0,
+            device_id: 0,
+            driver_version: 0,
+            ..Default::default()
+        };
+        self.save(problem, &data, device, false, true)
+    }
+
+    fn compute_conformant_emulation(&self, problem: &Problem) -> Result<(), Error> {
+        let now = Instant::now();
+        println!("Emulating conformant data from problem named `{}` on the CPU...", problem.name);
+
+        let mode = self.settings.conformant_mode;
+        let Some(data) = reference::compute_conformant(problem.operation, mode, self.operands()) else {
+            println!("Skipped, problem `{}` can not be emulated in {mode:?} mode.", problem.name);
+            return Ok(());
+        };
         println!("Done in {} ms.", now.elapsed().as_millis());
 
+        let device = DeviceInfo {
+            name: format!("CPU {mode:?} emulation"),
+            vendor_id: 0,
+            device_id: 0,
+            driver_version: 0,
+            ..Default::default()
+        };
+        self.save(problem, &data, device, true, true)
+    }
+
+    fn compute_impl(
+        &self, problem: &Problem, spirv_code: &[u8], is_conformant: bool, runner: Option<&Runner>
+    ) -> Result<(), Error> {
+        let now = Instant::now();
+
+        let conformant_str = if is_conformant { "conformant" } else { "unconformant" };
+        let device_name = runner.map_or("SPIR-V interpreter", Runner::device_name);
+        println!("Computing {conformant_str} data from problem named `{}` on `{device_name}`...", problem.name);
+
+        let operands = &self.operands()[..problem.operation.arity()];
+        let result_size = problem.operation.result_type(self.element_type).size();
+        let output = match runner {
+            Some(runner) => runner.run::<T>(spirv_code, operands, result_size)?,
+            None => interpreter::run::<T>(spirv_code, operands, result_size)?,
+        };
+        println!("Done in {} ms.", now.elapsed().as_millis());
+
+        self.save(problem, &output.data, output.device, is_conformant, false)
+    }
+
+    fn save_disassembly(&self, problem_name: &str, processed: &Processed) -> Result<(), Error> {
+        let (original, module) = (&processed.original, &processed.module);
+        let names = disassembler::names(original, &processed.created);
+        let files = [
+            (format!("{problem_name}.spvasm"), disassembler::disassemble(original, &names)),
+            (format!("{problem_name}_conformant.spvasm"), disassembler::disassemble(module, &names)),
+            (format!("{problem_name}_conformant.diff"), disassembler::diff(original, module, &names)),
+        ];
+
+        fs::create_dir_all(&self.settings.output_dir).map_err(Error::io(&self.settings.output_dir))?;
+        for (file_name, text) in files {
+            let path = self.settings.output_dir.join(file_name);
+            fs::write(&path, text).map_err(Error::io(&path))?;
+            println!("Saved disassembly to `{}`.", path.display());
+        }
+        Ok(())
+    }
+
+    fn save(
+        &self, problem: &Problem, data: &[u8], device: DeviceInfo, is_conformant: bool, is_reference: bool
+    ) -> Result<(), Error> {
+        let device_str = if is_reference {
+            "reference".to_owned()
+        } else {
+            // Identical devices share vendor and device ids, so the UUID, or the index when it is unknown, keeps
+            // their results apart.
+            let instance = if device.uuid == [0; 16] {
+                device.index.to_string()
+            } else {
+                device.uuid.iter().map(|byte| format!("{byte:02x}")).collect()
+            };
+            format!("{}_{}_{instance}", device.vendor_id, device.device_id)
+        };
         let conformant_str = if is_conformant { "binc" } else { "bin" };
-        let path = format!("../output/{problem_name}_{0}_{1}.{conformant_str}", output.device_vendor_id, output.device_id);
+        let path = self.settings.output_dir.join(format!("{}_{device_str}.{conformant_str}", problem.name));
+
+        let result = ResultFile {
+            header: ResultHeader {
+                element_type: problem.operation.result_type(self.element_type),
+                problem_name: problem.name.to_owned(),
+                expression: problem.expression.to_owned(),
+                input_sha256: self.initial_data_sha256.clone(),
+                device,
+                is_conformant,
+                conformant_mode: if is_conformant { self.settings.conformant_mode.to_string() } else { String::new() },
+                is_reference,
+                // A clock before 1970 is recorded as 0 instead of failing the run.
+                timestamp: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+            },
+            data: data.to_vec(),
+        };
+
+        fs::create_dir_all(&self.settings.output_dir).map_err(Error::io(&self.settings.output_dir))?;
+        result.save(&path).map_err(Error::io(&path))?;
 
-        fs::create_dir_all("../output").unwrap();
-        let mut file = File::create(path.clone()).unwrap();
-        file.write_all(unsafe {
-            slice::from_raw_parts::<u8>(output.data.as_ptr() as *const u8, output.data.len() * mem::size_of::<T>())
-        }).unwrap();
+        let sha256 = sha256::try_digest(path.as_path()).map_err(Error::io(&path))?;
+        println!("Saved result data to `{}`. SHA256: `{sha256}`.", path.display());
+        Ok(())
+    }
+}
+
+pub(crate) fn as_bytes<T>(data: &[T]) -> &[u8] {
+    unsafe { slice::from_raw_parts::<u8>(data.as_ptr() as *const u8, mem::size_of_val(data)) }
+}
 
-        let sha256 = sha256::try_digest(Path::new(&path)).unwrap();
-        println!("Saved result data to `{path}`. SHA256: `{sha256}`.");
+fn glsl_type(element_type: ElementType) -> &'static str {
+    match element_type {
+        ElementType::I32 => "int",
+        ElementType::F32 => "float",
+        ElementType::F64 => "double",
+        ElementType::F16 => "float16_t",
     }
 }
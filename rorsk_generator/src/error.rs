@@ -0,0 +1,77 @@
+use std::{fmt, io, path::PathBuf};
+
+use rorsk_conformant::spirv::ParseError;
+
+use crate::interpreter::ExecutionError;
+
+#[derive(Debug)]
+pub enum Error {
+    /// A Vulkan call failed, `context` describes what was being done.
+    Vulkan { context: &'static str, message: String },
+    NoSuitableDevice,
+    NoDevice(String),
+    UnsuitableDevice(usize),
+    ShaderCompile(String),
+    Conformant(rorsk_conformant::Error),
+    Parse(ParseError),
+    Interpreter(ExecutionError),
+    Io { path: PathBuf, source: io::Error },
+    UnsuitableData,
+}
+
+impl Error {
+    /// Returns a closure for `map_err` which wraps any Vulkan error together with the context.
+    pub fn vulkan<E: fmt::Display>(context: &'static str) -> impl FnOnce(E) -> Error {
+        move |e| Error::Vulkan { context, message: e.to_string() }
+    }
+
+    pub fn io(path: impl Into<PathBuf>) -> impl FnOnce(io::Error) -> Error {
+        let path = path.into();
+        move |source| Error::Io { path, source }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Vulkan { context, message } => write!(f, "failed to {context}: {message}"),
+            Error::NoSuitableDevice => write!(f, "no device is suitable for computing"),
+            Error::NoDevice(selector) => write!(f, "there is no device matching `{selector}`"),
+            Error::UnsuitableDevice(index) => write!(f, "device with index {index} is not suitable for computing"),
+            Error::ShaderCompile(message) => write!(f, "failed to compile the shader: {message}"),
+            Error::Conformant(e) => write!(f, "failed to make the shader conformant: {e}"),
+            Error::Parse(e) => write!(f, "failed to parse the shader: {e}"),
+            Error::Interpreter(e) => write!(f, "failed to interpret the shader: {e}"),
+            Error::Io { path, source } => write!(f, "failed to access `{}`: {source}", path.display()),
+            Error::UnsuitableData => write!(f, "the generated data has no positive or no negative numbers"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Conformant(e) => Some(e),
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<rorsk_conformant::Error> for Error {
+    fn from(e: rorsk_conformant::Error) -> Self {
+        Error::Conformant(e)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Error::Parse(e)
+    }
+}
+
+impl From<ExecutionError> for Error {
+    fn from(e: ExecutionError) -> Self {
+        Error::Interpreter(e)
+    }
+}
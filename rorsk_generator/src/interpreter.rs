@@ -0,0 +1,1293 @@
+use std::{collections::HashMap, fmt};
+
+use rorsk_conformant::spirv::{self, Function, Instruction, Module};
+use rorsk_core::{f16::{f16_to_f32, f32_to_f16}, result_file::DeviceInfo};
+use vulkano::buffer::BufferContents;
+
+use crate::{compute::as_bytes, error::Error, runner::Output};
+
+#[derive(Debug)]
+pub enum ExecutionError {
+    MissingEntryPoint(String),
+    MissingBuffer(u32),
+    UnsupportedOpcode(u16),
+    UnsupportedExtInst(String, u32),
+    UnsupportedType(u32),
+    UndefinedId(u32),
+    InvalidPointer(u32),
+    OutOfBounds(u32, usize),
+    Unreachable,
+}
+
+impl fmt::Display for ExecutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecutionError::MissingEntryPoint(name) => write!(f, "entry point `{name}` not found"),
+            ExecutionError::MissingBuffer(binding) => write!(f, "no buffer bound to binding {binding}"),
+            ExecutionError::UnsupportedOpcode(opcode) => write!(f, "unsupported opcode {opcode}"),
+            ExecutionError::UnsupportedExtInst(set, instruction) => {
+                write!(f, "unsupported extended instruction {instruction} from `{set}`")
+            },
+            ExecutionError::UnsupportedType(id) => write!(f, "unsupported type %{id}"),
+            ExecutionError::UndefinedId(id) => write!(f, "%{id} is used before it is defined"),
+            ExecutionError::InvalidPointer(id) => write!(f, "%{id} is not a valid pointer"),
+            ExecutionError::OutOfBounds(binding, offset) => {
+                write!(f, "access to byte {offset} is out of bounds of the buffer bound to binding {binding}")
+            },
+            ExecutionError::Unreachable => write!(f, "reached OpUnreachable or OpKill"),
+        }
+    }
+}
+
+/// Runs a compute shader on the CPU, the counterpart of `Runner::run` for machines without a Vulkan driver.
+pub fn run<T>(
+    spirv_code: &[u8], operands: &[&[T]], result_size: usize
+) -> Result<Output, Error> where T: BufferContents + Clone {
+    let module = Module::parse(&spirv::words_from_bytes(spirv_code))?;
+    let mut interpreter = Interpreter::new(&module)?;
+
+    let count = operands[0].len();
+    let mut buffers = operands.iter().map(|operand| as_bytes(operand).to_vec()).collect::<Vec<_>>();
+    buffers.push(vec![0; count * result_size]);
+    interpreter.dispatch("main", &mut buffers, [count.div_ceil(64) as u32, 1, 1])?;
+
+    Ok(Output {
+        data: buffers.pop().unwrap_or_default(),
+        device: DeviceInfo {
+            name: "CPU SPIR-V interpreter".to_owned(),
+            ..Default::default()
+        },
+    })
+}
+
+#[derive(Clone, Debug)]
+enum Type {
+    Void,
+    Bool,
+    /// Signedness is given by instructions, not by the type.
+    Int { width: u32 },
+    Float { width: u32 },
+    Vector { component: u32, count: u32 },
+    Array { element: u32, length: u32 },
+    RuntimeArray { element: u32 },
+    Struct { members: Vec<u32> },
+    Pointer { pointee: u32 },
+    Other,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Scalar {
+    Bool,
+    Int { width: u32 },
+    Float { width: u32 },
+}
+
+#[derive(Clone, Debug)]
+enum Base {
+    /// Index into the memory of the invocation, used by Private, Function and Input variables.
+    Memory(usize),
+    /// Binding of a storage buffer in descriptor set 0.
+    Buffer(u32),
+}
+
+#[derive(Clone, Debug)]
+struct Pointer {
+    base: Base,
+    base_type: u32,
+    /// Indices of OpAccessChain, applied to `base_type`.
+    path: Vec<u32>,
+    pointee: u32,
+}
+
+/// Scalars are stored as their bits, booleans as 0 or 1.
+#[derive(Clone, Debug)]
+enum Value {
+    Scalar(u64),
+    Composite(Vec<Value>),
+    Pointer(Pointer),
+}
+
+enum Flow {
+    Next,
+    Branch(u32),
+    Return(Option<Value>),
+}
+
+struct Frame {
+    results: HashMap<u32, Value>,
+    memory_base: usize,
+}
+
+/// Executes compute shaders on the CPU, one invocation after another. Results of float instructions are correctly
+/// rounded, GLSL.std.450 functions are computed in f64 and rounded to the result type.
+pub struct Interpreter<'a> {
+    module: &'a Module,
+    types: HashMap<u32, Type>,
+    value_types: HashMap<u32, u32>,
+    globals: HashMap<u32, Value>,
+    functions: HashMap<u32, &'a Function>,
+    ext_inst_imports: HashMap<u32, String>,
+    builtins: HashMap<u32, u32>,
+    bindings: HashMap<u32, u32>,
+    member_offsets: HashMap<(u32, u32), u32>,
+    array_strides: HashMap<u32, u32>,
+    memory: Vec<Value>,
+}
+
+impl<'a> Interpreter<'a> {
+    pub fn new(module: &'a Module) -> Result<Self, ExecutionError> {
+        let mut interpreter = Interpreter {
+            module,
+            types: HashMap::new(),
+            value_types: HashMap::new(),
+            globals: HashMap::new(),
+            functions: HashMap::new(),
+            ext_inst_imports: HashMap::new(),
+            builtins: HashMap::new(),
+            bindings: HashMap::new(),
+            member_offsets: HashMap::new(),
+            array_strides: HashMap::new(),
+            memory: Vec::new(),
+        };
+
+        for instruction in &module.ext_inst_imports {
+            interpreter.ext_inst_imports.insert(instruction.operands[0], instruction.string(1));
+        }
+
+        for instruction in &module.annotations {
+            let operands = &instruction.operands;
+            match (instruction.opcode, operands.get(1)) {
+                // OpDecorate BuiltIn
+                (71, Some(11)) => {
+                    interpreter.builtins.insert(operands[0], operands[2]);
+                },
+                // OpDecorate Binding
+                (71, Some(33)) => {
+                    interpreter.bindings.insert(operands[0], operands[2]);
+                },
+                // OpDecorate ArrayStride
+                (71, Some(6)) => {
+                    interpreter.array_strides.insert(operands[0], operands[2]);
+                },
+                // OpMemberDecorate Offset
+                (72, Some(_)) if operands[2] == 35 => {
+                    interpreter.member_offsets.insert((operands[0], operands[1]), operands[3]);
+                },
+                _ => {},
+            }
+        }
+
+        for instruction in &module.types_global_values {
+            interpreter.global(instruction)?;
+        }
+
+        for function in &module.functions {
+            interpreter.functions.insert(function.definition.operands[1], function);
+
+            let instructions = function.parameters.iter().chain(function.blocks.iter().flat_map(|b| &b.instructions));
+            for instruction in instructions {
+                if let (Some(result_type), Some(id)) = (instruction.result_type(), instruction.result_id()) {
+                    interpreter.value_types.insert(id, result_type);
+                }
+            }
+        }
+
+        Ok(interpreter)
+    }
+
+    /// Runs the entry point for every invocation of `group_count` workgroups. Storage buffers are indexed by their
+    /// binding in descriptor set 0.
+    pub fn dispatch(
+        &mut self, entry_point: &str, buffers: &mut [Vec<u8>], group_count: [u32; 3]
+    ) -> Result<(), ExecutionError> {
+        let entry = self.module.entry_points.iter()
+            .find(|i| i.string(2) == entry_point)
+            .ok_or_else(|| ExecutionError::MissingEntryPoint(entry_point.to_owned()))?;
+        let function_id = entry.operands[1];
+
+        let local_size = self.module.execution_modes.iter()
+            // OpExecutionMode LocalSize
+            .find(|i| i.operands[0] == function_id && i.operands[1] == 17)
+            .map(|i| [i.operands[2], i.operands[3], i.operands[4]])
+            .unwrap_or([1, 1, 1]);
+
+        let function = *self.functions.get(&function_id).ok_or(ExecutionError::UndefinedId(function_id))?;
+
+        for gz in 0..group_count[2] {
+            for gy in 0..group_count[1] {
+                for gx in 0..group_count[0] {
+                    for lz in 0..local_size[2] {
+                        for ly in 0..local_size[1] {
+                            for lx in 0..local_size[0] {
+                                self.reset_invocation([gx, gy, gz], [lx, ly, lz], local_size, group_count)?;
+                                self.call(function, &[], buffers)?;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Creates global variables of a new invocation and fills built-in inputs.
+    fn reset_invocation(
+        &mut self, group: [u32; 3], local: [u32; 3], local_size: [u32; 3], group_count: [u32; 3]
+    ) -> Result<(), ExecutionError> {
+        self.memory.clear();
+
+        let vector = |v: [u32; 3]| Value::Composite(v.iter().map(|x| Value::Scalar(*x as u64)).collect());
+        let global = [0, 1, 2].map(|i| group[i] * local_size[i] + local[i]);
+        let index = local[2] * local_size[0] * local_size[1] + local[1] * local_size[0] + local[0];
+
+        for instruction in &self.module.types_global_values {
+            // OpVariable
+            if instruction.opcode != 59 {
+                continue;
+            }
+
+            let (id, storage_class) = (instruction.operands[1], instruction.operands[2]);
+            let pointee = self.pointee(instruction.operands[0], id)?;
+
+            let base = match storage_class {
+                // Uniform, StorageBuffer
+                2 | 12 => Base::Buffer(*self.bindings.get(&id).ok_or(ExecutionError::InvalidPointer(id))?),
+                _ => {
+                    let value = match self.builtins.get(&id) {
+                        // NumWorkgroups
+                        Some(24) => vector(group_count),
+                        // WorkgroupSize
+                        Some(25) => vector(local_size),
+                        // WorkgroupId
+                        Some(26) => vector(group),
+                        // LocalInvocationId
+                        Some(27) => vector(local),
+                        // GlobalInvocationId
+                        Some(28) => vector(global),
+                        // LocalInvocationIndex
+                        Some(29) => Value::Scalar(index as u64),
+                        _ => match instruction.operands.get(3) {
+                            Some(initializer) => {
+                                self.globals.get(initializer).cloned().ok_or(ExecutionError::UndefinedId(*initializer))?
+                            },
+                            None => self.zero(pointee)?,
+                        },
+                    };
+
+                    self.memory.push(value);
+                    Base::Memory(self.memory.len() - 1)
+                },
+            };
+
+            self.globals.insert(id, Value::Pointer(Pointer { base, base_type: pointee, path: Vec::new(), pointee }));
+        }
+
+        Ok(())
+    }
+
+    fn global(&mut self, instruction: &Instruction) -> Result<(), ExecutionError> {
+        let operands = &instruction.operands;
+        if let (Some(result_type), Some(id)) = (instruction.result_type(), instruction.result_id()) {
+            self.value_types.insert(id, result_type);
+        }
+
+        match instruction.opcode {
+            // OpTypeVoid
+            19 => {
+                self.types.insert(operands[0], Type::Void);
+            },
+            // OpTypeBool
+            20 => {
+                self.types.insert(operands[0], Type::Bool);
+            },
+            // OpTypeInt
+            21 => {
+                self.types.insert(operands[0], Type::Int { width: operands[1] });
+            },
+            // OpTypeFloat
+            22 => {
+                self.types.insert(operands[0], Type::Float { width: operands[1] });
+            },
+            // OpTypeVector
+            23 => {
+                self.types.insert(operands[0], Type::Vector { component: operands[1], count: operands[2] });
+            },
+            // OpTypeArray
+            28 => {
+                let length = self.constant(operands[2])? as u32;
+                self.types.insert(operands[0], Type::Array { element: operands[1], length });
+            },
+            // OpTypeRuntimeArray
+            29 => {
+                self.types.insert(operands[0], Type::RuntimeArray { element: operands[1] });
+            },
+            // OpTypeStruct
+            30 => {
+                self.types.insert(operands[0], Type::Struct { members: operands[1..].to_vec() });
+            },
+            // OpTypePointer
+            32 => {
+                self.types.insert(operands[0], Type::Pointer { pointee: operands[2] });
+            },
+            // Matrix, image, sampler, function and other opaque types
+            24..=27 | 31 | 33..=38 => {
+                self.types.insert(operands[0], Type::Other);
+            },
+            // OpConstantTrue, OpSpecConstantTrue
+            41 | 48 => {
+                self.globals.insert(operands[1], Value::Scalar(1));
+            },
+            // OpConstantFalse, OpSpecConstantFalse
+            42 | 49 => {
+                self.globals.insert(operands[1], Value::Scalar(0));
+            },
+            // OpConstant, OpSpecConstant
+            43 | 50 => {
+                let low = operands[2] as u64;
+                let high = operands.get(3).copied().unwrap_or(0) as u64;
+                self.globals.insert(operands[1], Value::Scalar(low | (high << 32)));
+            },
+            // OpConstantComposite, OpSpecConstantComposite
+            44 | 51 => {
+                let values = operands[2..].iter()
+                    .map(|id| self.globals.get(id).cloned().ok_or(ExecutionError::UndefinedId(*id)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.globals.insert(operands[1], Value::Composite(values));
+            },
+            // OpUndef, OpConstantNull
+            1 | 46 => {
+                let zero = self.zero(operands[0])?;
+                self.globals.insert(operands[1], zero);
+            },
+            // OpLine, OpVariable, OpNoLine. Variables are created for every invocation.
+            8 | 59 | 317 => {},
+            opcode => return Err(ExecutionError::UnsupportedOpcode(opcode)),
+        }
+
+        Ok(())
+    }
+
+    fn zero(&self, type_id: u32) -> Result<Value, ExecutionError> {
+        Ok(match self.types.get(&type_id) {
+            Some(Type::Bool | Type::Int { .. } | Type::Float { .. }) => Value::Scalar(0),
+            Some(Type::Vector { component, count }) => Value::Composite(vec![self.zero(*component)?; *count as usize]),
+            Some(Type::Array { element, length }) => Value::Composite(vec![self.zero(*element)?; *length as usize]),
+            Some(Type::Struct { members }) => {
+                Value::Composite(members.iter().map(|m| self.zero(*m)).collect::<Result<_, _>>()?)
+            },
+            _ => return Err(ExecutionError::UnsupportedType(type_id)),
+        })
+    }
+
+    fn constant(&self, id: u32) -> Result<u64, ExecutionError> {
+        match self.globals.get(&id) {
+            Some(Value::Scalar(value)) => Ok(*value),
+            _ => Err(ExecutionError::UndefinedId(id)),
+        }
+    }
+
+    /// Returns the scalar type of a scalar or vector type.
+    fn scalar_type(&self, type_id: u32) -> Result<Scalar, ExecutionError> {
+        match self.types.get(&type_id) {
+            Some(Type::Bool) => Ok(Scalar::Bool),
+            Some(Type::Int { width }) => Ok(Scalar::Int { width: *width }),
+            Some(Type::Float { width }) => Ok(Scalar::Float { width: *width }),
+            Some(Type::Vector { component, .. }) => self.scalar_type(*component),
+            _ => Err(ExecutionError::UnsupportedType(type_id)),
+        }
+    }
+
+    /// Returns the scalar type of the value with the given id.
+    fn scalar_type_of(&self, id: u32) -> Result<Scalar, ExecutionError> {
+        let type_id = self.value_types.get(&id).copied().ok_or(ExecutionError::UndefinedId(id))?;
+        self.scalar_type(type_id)
+    }
+
+    fn call(
+        &mut self, function: &'a Function, arguments: &[Value], buffers: &mut [Vec<u8>]
+    ) -> Result<Option<Value>, ExecutionError> {
+        let mut frame = Frame {
+            results: HashMap::new(),
+            memory_base: self.memory.len(),
+        };
+        for (parameter, argument) in function.function_parameters().zip(arguments) {
+            frame.results.insert(parameter.operands[1], argument.clone());
+        }
+
+        let mut block_index = 0;
+        let mut previous_label = 0;
+        let result = 'blocks: loop {
+            let block = &function.blocks[block_index];
+
+            for instruction in &block.instructions {
+                let target = match self.execute(&mut frame, instruction, previous_label, buffers)? {
+                    Flow::Next => continue,
+                    Flow::Branch(target) => target,
+                    Flow::Return(value) => break 'blocks value,
+                };
+
+                previous_label = block.label;
+                block_index = function.blocks.iter()
+                    .position(|b| b.label == target)
+                    .ok_or(ExecutionError::UndefinedId(target))?;
+                continue 'blocks;
+            }
+
+            // Every block ends with a branch or a return, the parser does not allow anything else.
+            return Err(ExecutionError::Unreachable);
+        };
+
+        // Function variables are freed when the function returns.
+        self.memory.truncate(frame.memory_base);
+        Ok(result)
+    }
+
+    fn value(&self, frame: &Frame, id: u32) -> Result<Value, ExecutionError> {
+        frame.results.get(&id).or_else(|| self.globals.get(&id)).cloned().ok_or(ExecutionError::UndefinedId(id))
+    }
+
+    fn scalar(&self, frame: &Frame, id: u32) -> Result<u64, ExecutionError> {
+        match self.value(frame, id)? {
+            Value::Scalar(value) => Ok(value),
+            _ => Err(ExecutionError::UndefinedId(id)),
+        }
+    }
+
+    fn pointer(&self, frame: &Frame, id: u32) -> Result<Pointer, ExecutionError> {
+        match self.value(frame, id)? {
+            Value::Pointer(pointer) => Ok(pointer),
+            _ => Err(ExecutionError::InvalidPointer(id)),
+        }
+    }
+
+    fn pointee(&self, pointer_type: u32, id: u32) -> Result<u32, ExecutionError> {
+        match self.types.get(&pointer_type) {
+            Some(Type::Pointer { pointee }) => Ok(*pointee),
+            _ => Err(ExecutionError::InvalidPointer(id)),
+        }
+    }
+
+    fn execute(
+        &mut self, frame: &mut Frame, instruction: &Instruction, previous_label: u32, buffers: &mut [Vec<u8>]
+    ) -> Result<Flow, ExecutionError> {
+        let operands = &instruction.operands;
+        let result = match instruction.opcode {
+            // OpNop, OpLine, OpNoLine, OpControlBarrier, OpMemoryBarrier, OpLoopMerge, OpSelectionMerge. Invocations
+            // run one after another, so barriers have nothing to wait for.
+            0 | 8 | 317 | 224 | 225 | 246 | 247 => return Ok(Flow::Next),
+            // OpUndef
+            1 => self.zero(operands[0])?,
+            // OpExtInst
+            12 => {
+                let arguments = operands[4..].iter()
+                    .map(|id| self.value(frame, *id))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.ext_inst(operands[0], operands[2], operands[3], &arguments)?
+            },
+            // OpFunctionCall
+            57 => {
+                let function = *self.functions.get(&operands[2]).ok_or(ExecutionError::UndefinedId(operands[2]))?;
+                let arguments = operands[3..].iter()
+                    .map(|id| self.value(frame, *id))
+                    .collect::<Result<Vec<_>, _>>()?;
+                match self.call(function, &arguments, buffers)? {
+                    Some(value) => value,
+                    None => return Ok(Flow::Next),
+                }
+            },
+            // OpVariable
+            59 => {
+                let pointee = self.pointee(operands[0], operands[1])?;
+                let value = match operands.get(3) {
+                    Some(initializer) => self.value(frame, *initializer)?,
+                    None => self.zero(pointee)?,
+                };
+                self.memory.push(value);
+
+                let base = Base::Memory(self.memory.len() - 1);
+                Value::Pointer(Pointer { base, base_type: pointee, path: Vec::new(), pointee })
+            },
+            // OpLoad
+            61 => {
+                let pointer = self.pointer(frame, operands[2])?;
+                self.load(&pointer, buffers)?
+            },
+            // OpStore
+            62 => {
+                let pointer = self.pointer(frame, operands[0])?;
+                let value = self.value(frame, operands[1])?;
+                self.store(&pointer, value, buffers)?;
+                return Ok(Flow::Next);
+            },
+            // OpCopyMemory
+            63 => {
+                let target = self.pointer(frame, operands[0])?;
+                let source = self.pointer(frame, operands[1])?;
+                let value = self.load(&source, buffers)?;
+                self.store(&target, value, buffers)?;
+                return Ok(Flow::Next);
+            },
+            // OpAccessChain, OpInBoundsAccessChain
+            65 | 66 => {
+                let mut pointer = self.pointer(frame, operands[2])?;
+                for index in &operands[3..] {
+                    pointer.path.push(self.scalar(frame, *index)? as u32);
+                }
+                pointer.pointee = self.pointee(operands[0], operands[1])?;
+                Value::Pointer(pointer)
+            },
+            // OpArrayLength
+            68 => {
+                let pointer = self.pointer(frame, operands[2])?;
+                let Base::Buffer(binding) = pointer.base else {
+                    return Err(ExecutionError::InvalidPointer(operands[2]));
+                };
+                let buffer = buffers.get(binding as usize).ok_or(ExecutionError::MissingBuffer(binding))?;
+                let (offset, stride) = self.runtime_array_layout(pointer.pointee, operands[3])?;
+                Value::Scalar(((buffer.len() as u32 - offset) / stride) as u64)
+            },
+            // OpVectorExtractDynamic
+            77 => {
+                let index = self.scalar(frame, operands[3])? as usize;
+                component(&self.value(frame, operands[2])?, index)
+            },
+            // OpVectorInsertDynamic
+            78 => {
+                let index = self.scalar(frame, operands[4])? as usize;
+                let mut vector = self.value(frame, operands[2])?;
+                if let Value::Composite(values) = &mut vector {
+                    values[index] = self.value(frame, operands[3])?;
+                }
+                vector
+            },
+            // OpVectorShuffle
+            79 => {
+                let a = components(&self.value(frame, operands[2])?);
+                let b = components(&self.value(frame, operands[3])?);
+                let all = a.iter().chain(&b).collect::<Vec<_>>();
+                // Component 0xFFFFFFFF is undefined.
+                Value::Composite(operands[4..].iter()
+                    .map(|i| all.get(*i as usize).map(|v| (*v).clone()).unwrap_or(Value::Scalar(0)))
+                    .collect())
+            },
+            // OpCompositeConstruct
+            80 => {
+                let mut values = Vec::new();
+                let is_vector = matches!(self.types.get(&operands[0]), Some(Type::Vector { .. }));
+                for id in &operands[2..] {
+                    // Vectors may be constructed from smaller vectors.
+                    match self.value(frame, *id)? {
+                        Value::Composite(parts) if is_vector => values.extend(parts),
+                        value => values.push(value),
+                    }
+                }
+                Value::Composite(values)
+            },
+            // OpCompositeExtract
+            81 => {
+                let mut value = self.value(frame, operands[2])?;
+                for index in &operands[3..] {
+                    value = component(&value, *index as usize);
+                }
+                value
+            },
+            // OpCompositeInsert
+            82 => {
+                let mut composite = self.value(frame, operands[3])?;
+                let object = self.value(frame, operands[2])?;
+                insert(&mut composite, &operands[4..], object);
+                composite
+            },
+            // OpCopyObject
+            83 => self.value(frame, operands[2])?,
+            // OpConvertFToU .. OpFConvert, OpBitcast
+            109..=115 | 124 => self.convert(frame, instruction)?,
+            // OpSNegate, OpFNegate, OpNot, OpBitReverse, OpBitCount
+            126 | 127 | 200 | 204 | 205 => {
+                let scalar = self.scalar_type(operands[0])?;
+                map1(&self.value(frame, operands[2])?, |a| unary(instruction.opcode, scalar, a))
+            },
+            // OpIAdd .. OpFMod, OpShiftRightLogical .. OpBitwiseAnd
+            128..=141 | 194..=199 => {
+                let scalar = self.scalar_type(operands[0])?;
+                // Shift is allowed to have a different width than base.
+                let shift_scalar = self.scalar_type_of(operands[3])?;
+                let a = self.value(frame, operands[2])?;
+                let b = self.value(frame, operands[3])?;
+                map2(&a, &b, |a, b| binary(instruction.opcode, scalar, shift_scalar, a, b))
+            },
+            // OpVectorTimesScalar
+            142 => {
+                let scalar = self.scalar_type(operands[0])?;
+                let b = self.scalar(frame, operands[3])?;
+                map1(&self.value(frame, operands[2])?, |a| binary(133, scalar, scalar, a, b))
+            },
+            // OpDot
+            148 => {
+                let scalar = self.scalar_type(operands[0])?;
+                let a = components(&self.value(frame, operands[2])?);
+                let b = components(&self.value(frame, operands[3])?);
+                let mut sum = 0;
+                for (a, b) in a.iter().zip(&b) {
+                    let product = binary(133, scalar, scalar, as_scalar(a), as_scalar(b));
+                    sum = binary(129, scalar, scalar, sum, product);
+                }
+                Value::Scalar(sum)
+            },
+            // OpAny, OpAll
+            154 | 155 => {
+                let values = components(&self.value(frame, operands[2])?);
+                let result = match instruction.opcode {
+                    154 => values.iter().any(|v| as_scalar(v) != 0),
+                    _ => values.iter().all(|v| as_scalar(v) != 0),
+                };
+                Value::Scalar(result as u64)
+            },
+            // OpIsNan, OpIsInf, OpIsFinite, OpIsNormal, OpSignBitSet
+            156..=160 => {
+                let scalar = self.scalar_type_of(operands[2])?;
+                map1(&self.value(frame, operands[2])?, |a| {
+                    let f = to_f64(scalar, a);
+                    (match instruction.opcode {
+                        156 => f.is_nan(),
+                        157 => f.is_infinite(),
+                        158 => f.is_finite(),
+                        159 => is_normal(scalar, f),
+                        _ => f.is_sign_negative(),
+                    }) as u64
+                })
+            },
+            // OpLogicalEqual, OpLogicalNotEqual, OpLogicalOr, OpLogicalAnd
+            164..=167 => {
+                let a = self.value(frame, operands[2])?;
+                let b = self.value(frame, operands[3])?;
+                map2(&a, &b, |a, b| (match instruction.opcode {
+                    164 => (a != 0) == (b != 0),
+                    165 => (a != 0) != (b != 0),
+                    166 => a != 0 || b != 0,
+                    _ => a != 0 && b != 0,
+                }) as u64)
+            },
+            // OpLogicalNot
+            168 => map1(&self.value(frame, operands[2])?, |a| (a == 0) as u64),
+            // OpSelect
+            169 => {
+                let a = self.value(frame, operands[3])?;
+                let b = self.value(frame, operands[4])?;
+                match self.value(frame, operands[2])? {
+                    Value::Scalar(condition) => if condition != 0 { a } else { b },
+                    // Vector conditions select component-wise.
+                    Value::Composite(condition) => Value::Composite(condition.iter().enumerate()
+                        .map(|(i, c)| if as_scalar(c) != 0 { component(&a, i) } else { component(&b, i) })
+                        .collect()),
+                    Value::Pointer(_) => return Err(ExecutionError::InvalidPointer(operands[2])),
+                }
+            },
+            // OpIEqual .. OpFUnordGreaterThanEqual
+            170..=191 => {
+                let scalar = self.scalar_type_of(operands[2])?;
+                let a = self.value(frame, operands[2])?;
+                let b = self.value(frame, operands[3])?;
+                map2(&a, &b, |a, b| compare(instruction.opcode, scalar, a, b) as u64)
+            },
+            // OpBitFieldInsert
+            201 => {
+                let scalar = self.scalar_type(operands[0])?;
+                let offset = self.scalar(frame, operands[4])?;
+                let count = self.scalar(frame, operands[5])?;
+                let mask = mask_bits(count as u32) << offset;
+                let base = self.value(frame, operands[2])?;
+                let insert = self.value(frame, operands[3])?;
+                map2(&base, &insert, |base, insert| truncate(scalar, (base & !mask) | ((insert << offset) & mask)))
+            },
+            // OpBitFieldSExtract, OpBitFieldUExtract
+            202 | 203 => {
+                let scalar = self.scalar_type(operands[0])?;
+                let offset = self.scalar(frame, operands[3])?;
+                let count = self.scalar(frame, operands[4])? as u32;
+                map1(&self.value(frame, operands[2])?, |base| {
+                    let bits = (base >> offset) & mask_bits(count);
+                    if instruction.opcode == 202 && count > 0 && (bits >> (count - 1)) & 1 == 1 {
+                        truncate(scalar, bits | !mask_bits(count))
+                    } else {
+                        bits
+                    }
+                })
+            },
+            // OpPhi
+            245 => {
+                let pair = operands[2..].chunks(2)
+                    .find(|pair| pair[1] == previous_label)
+                    .ok_or(ExecutionError::UndefinedId(previous_label))?;
+                self.value(frame, pair[0])?
+            },
+            // OpBranch
+            249 => return Ok(Flow::Branch(operands[0])),
+            // OpBranchConditional
+            250 => {
+                let condition = self.scalar(frame, operands[0])?;
+                return Ok(Flow::Branch(if condition != 0 { operands[1] } else { operands[2] }));
+            },
+            // OpSwitch
+            251 => {
+                let selector = self.scalar(frame, operands[0])?;
+                // Literals are as wide as the selector.
+                let literal_words = match self.scalar_type_of(operands[0])? {
+                    Scalar::Int { width: 64 } => 2,
+                    _ => 1,
+                };
+
+                let mut target = operands[1];
+                for case in operands[2..].chunks(literal_words + 1) {
+                    let literal = case[..literal_words].iter().rev().fold(0, |a, w| (a << 32) | *w as u64);
+                    if literal == selector {
+                        target = case[literal_words];
+                        break;
+                    }
+                }
+                return Ok(Flow::Branch(target));
+            },
+            // OpKill, OpUnreachable, OpTerminateInvocation
+            252 | 255 | 4416 => return Err(ExecutionError::Unreachable),
+            // OpReturn
+            253 => return Ok(Flow::Return(None)),
+            // OpReturnValue
+            254 => return Ok(Flow::Return(Some(self.value(frame, operands[0])?))),
+            opcode => return Err(ExecutionError::UnsupportedOpcode(opcode)),
+        };
+
+        frame.results.insert(operands[1], result);
+        Ok(Flow::Next)
+    }
+
+    fn convert(&self, frame: &Frame, instruction: &Instruction) -> Result<Value, ExecutionError> {
+        let operands = &instruction.operands;
+        let to = self.scalar_type(operands[0])?;
+        let from = self.scalar_type_of(operands[2])?;
+        let value = self.value(frame, operands[2])?;
+
+        // OpBitcast may change the component count, like uint to f16vec2.
+        if instruction.opcode == 124 {
+            let mut bytes = Vec::new();
+            for part in components(&value) {
+                bytes.extend_from_slice(&as_scalar(&part).to_le_bytes()[..(width(from) / 8) as usize]);
+            }
+            let parts = bytes.chunks((width(to) / 8) as usize)
+                .map(|c| Value::Scalar(c.iter().rev().fold(0, |a, b| (a << 8) | *b as u64)))
+                .collect::<Vec<_>>();
+            return Ok(match self.types.get(&operands[0]) {
+                Some(Type::Vector { .. }) => Value::Composite(parts),
+                _ => parts.into_iter().next().unwrap_or(Value::Scalar(0)),
+            });
+        }
+
+        Ok(map1(&value, |a| match instruction.opcode {
+            // OpConvertFToU, out of range values are undefined and saturated here.
+            109 => truncate(to, to_f64(from, a) as u64),
+            // OpConvertFToS
+            110 => truncate(to, to_f64(from, a) as i64 as u64),
+            // OpConvertSToF
+            111 => match width(to) {
+                32 => (sign_extend(from, a) as f32).to_bits() as u64,
+                _ => to_bits(to, sign_extend(from, a) as f64),
+            },
+            // OpConvertUToF
+            112 => match width(to) {
+                32 => (truncate(from, a) as f32).to_bits() as u64,
+                _ => to_bits(to, truncate(from, a) as f64),
+            },
+            // OpUConvert
+            113 => truncate(to, truncate(from, a)),
+            // OpSConvert
+            114 => truncate(to, sign_extend(from, a) as u64),
+            // OpFConvert
+            _ => to_bits(to, to_f64(from, a)),
+        }))
+    }
+
+    fn ext_inst(
+        &self, result_type: u32, set: u32, number: u32, arguments: &[Value]
+    ) -> Result<Value, ExecutionError> {
+        let name = self.ext_inst_imports.get(&set).cloned().unwrap_or_default();
+        if name != "GLSL.std.450" {
+            return Err(ExecutionError::UnsupportedExtInst(name, number));
+        }
+
+        let scalar = self.scalar_type(result_type)?;
+        let f = |a: u64| to_f64(scalar, a);
+        let s = |a: u64| sign_extend(scalar, a);
+        let float = |v: f64| to_bits(scalar, v);
+        let int = |v: i64| truncate(scalar, v as u64);
+
+        let unary = |op: &dyn Fn(u64) -> u64| map1(&arguments[0], op);
+        let binary = |op: &dyn Fn(u64, u64) -> u64| map2(&arguments[0], &arguments[1], op);
+
+        Ok(match number {
+            // Round
+            1 => unary(&|a| float(f(a).round())),
+            // RoundEven
+            2 => unary(&|a| float(f(a).round_ties_even())),
+            // Trunc
+            3 => unary(&|a| float(f(a).trunc())),
+            // FAbs
+            4 => unary(&|a| float(f(a).abs())),
+            // SAbs
+            5 => unary(&|a| int(s(a).wrapping_abs())),
+            // FSign
+            6 => unary(&|a| float(if f(a) > 0.0 { 1.0 } else if f(a) < 0.0 { -1.0 } else { f(a) })),
+            // SSign
+            7 => unary(&|a| int(s(a).signum())),
+            // Floor
+            8 => unary(&|a| float(f(a).floor())),
+            // Ceil
+            9 => unary(&|a| float(f(a).ceil())),
+            // Fract
+            10 => unary(&|a| float(f(a) - f(a).floor())),
+            // Sin
+            13 => unary(&|a| float(f(a).sin())),
+            // Cos
+            14 => unary(&|a| float(f(a).cos())),
+            // Tan
+            15 => unary(&|a| float(f(a).tan())),
+            // Pow
+            26 => binary(&|a, b| float(f(a).powf(f(b)))),
+            // Exp
+            27 => unary(&|a| float(f(a).exp())),
+            // Log
+            28 => unary(&|a| float(f(a).ln())),
+            // Exp2
+            29 => unary(&|a| float(f(a).exp2())),
+            // Log2
+            30 => unary(&|a| float(f(a).log2())),
+            // Sqrt
+            31 => unary(&|a| float(f(a).sqrt())),
+            // InverseSqrt
+            32 => unary(&|a| float(1.0 / f(a).sqrt())),
+            // FMin
+            37 => binary(&|a, b| float(f(a).min(f(b)))),
+            // UMin
+            38 => binary(&|a, b| a.min(b)),
+            // SMin
+            39 => binary(&|a, b| int(s(a).min(s(b)))),
+            // FMax
+            40 => binary(&|a, b| float(f(a).max(f(b)))),
+            // UMax
+            41 => binary(&|a, b| a.max(b)),
+            // SMax
+            42 => binary(&|a, b| int(s(a).max(s(b)))),
+            // FClamp, UClamp, SClamp, min(max(x, min_value), max_value)
+            43..=45 => {
+                let low = map2(&arguments[0], &arguments[1], |a, b| match number {
+                    43 => float(f(a).max(f(b))),
+                    44 => a.max(b),
+                    _ => int(s(a).max(s(b))),
+                });
+                map2(&low, &arguments[2], |a, b| match number {
+                    43 => float(f(a).min(f(b))),
+                    44 => a.min(b),
+                    _ => int(s(a).min(s(b))),
+                })
+            },
+            // FMix, x + (y - x) * a
+            46 => {
+                let t = map2(&arguments[1], &arguments[0], |y, x| float(f(y) - f(x)));
+                let t = map2(&t, &arguments[2], |d, a| float(f(d) * f(a)));
+                map2(&arguments[0], &t, |x, t| float(f(x) + f(t)))
+            },
+            // Fma
+            50 => map3(&arguments[0], &arguments[1], &arguments[2], |a, b, c| match width(scalar) {
+                32 => {
+                    let [a, b, c] = [a, b, c].map(|v| f32::from_bits(v as u32));
+                    a.mul_add(b, c).to_bits() as u64
+                },
+                _ => float(f(a).mul_add(f(b), f(c))),
+            }),
+            // Ldexp, the exponent is always a 32 bit integer.
+            53 => binary(&|a, b| float(f(a) * 2f64.powi(b as u32 as i32))),
+            // FindILsb
+            73 => unary(&|a| if a == 0 { 0xFFFFFFFF } else { a.trailing_zeros() as u64 }),
+            // FindSMsb
+            74 => unary(&|a| {
+                let v = a as u32 as i32;
+                let v = if v < 0 { !v } else { v };
+                if v == 0 { 0xFFFFFFFF } else { 31 - v.leading_zeros() as u64 }
+            }),
+            // FindUMsb
+            75 => unary(&|a| if a as u32 == 0 { 0xFFFFFFFF } else { 31 - (a as u32).leading_zeros() as u64 }),
+            _ => return Err(ExecutionError::UnsupportedExtInst(name, number)),
+        })
+    }
+
+    /// Returns the offset of a runtime array member of a struct and the stride of its elements.
+    fn runtime_array_layout(&self, struct_type: u32, member: u32) -> Result<(u32, u32), ExecutionError> {
+        let offset = self.member_offsets.get(&(struct_type, member)).copied().unwrap_or(0);
+        let element_type = match self.types.get(&struct_type) {
+            Some(Type::Struct { members }) => members[member as usize],
+            _ => return Err(ExecutionError::UnsupportedType(struct_type)),
+        };
+        let stride = self.array_stride(element_type)?;
+        Ok((offset, stride))
+    }
+
+    fn array_stride(&self, type_id: u32) -> Result<u32, ExecutionError> {
+        self.array_strides.get(&type_id).copied().ok_or(ExecutionError::UnsupportedType(type_id))
+    }
+
+    fn member_offset(&self, type_id: u32, member: u32) -> usize {
+        self.member_offsets.get(&(type_id, member)).copied().unwrap_or(0) as usize
+    }
+
+    fn buffer_offset(&self, pointer: &Pointer) -> Result<usize, ExecutionError> {
+        let mut offset = 0;
+        let mut type_id = pointer.base_type;
+        for index in &pointer.path {
+            let (element_offset, next) = match self.types.get(&type_id) {
+                Some(Type::Struct { members }) => (self.member_offset(type_id, *index), members[*index as usize]),
+                Some(Type::Array { element, .. } | Type::RuntimeArray { element }) => {
+                    ((self.array_stride(type_id)? * index) as usize, *element)
+                },
+                Some(Type::Vector { component, .. }) => ((self.size(*component)? * index) as usize, *component),
+                _ => return Err(ExecutionError::UnsupportedType(type_id)),
+            };
+            offset += element_offset;
+            type_id = next;
+        }
+        Ok(offset)
+    }
+
+    fn size(&self, type_id: u32) -> Result<u32, ExecutionError> {
+        match self.types.get(&type_id) {
+            Some(Type::Bool) => Ok(4),
+            Some(Type::Int { width } | Type::Float { width }) => Ok(width / 8),
+            Some(Type::Vector { component, count }) => Ok(self.size(*component)? * count),
+            _ => Err(ExecutionError::UnsupportedType(type_id)),
+        }
+    }
+
+    fn load(&self, pointer: &Pointer, buffers: &[Vec<u8>]) -> Result<Value, ExecutionError> {
+        match pointer.base {
+            Base::Memory(index) => {
+                let mut value = &self.memory[index];
+                for i in &pointer.path {
+                    value = match value {
+                        Value::Composite(values) => &values[*i as usize],
+                        _ => return Err(ExecutionError::UnsupportedType(pointer.base_type)),
+                    };
+                }
+                Ok(value.clone())
+            },
+            Base::Buffer(binding) => {
+                let offset = self.buffer_offset(pointer)?;
+                let buffer = buffers.get(binding as usize).ok_or(ExecutionError::MissingBuffer(binding))?;
+                self.read(buffer, binding, offset, pointer.pointee)
+            },
+        }
+    }
+
+    fn store(&mut self, pointer: &Pointer, value: Value, buffers: &mut [Vec<u8>]) -> Result<(), ExecutionError> {
+        match pointer.base {
+            Base::Memory(index) => {
+                let mut target = &mut self.memory[index];
+                for i in &pointer.path {
+                    target = match target {
+                        Value::Composite(values) => &mut values[*i as usize],
+                        _ => return Err(ExecutionError::UnsupportedType(pointer.base_type)),
+                    };
+                }
+                *target = value;
+                Ok(())
+            },
+            Base::Buffer(binding) => {
+                let offset = self.buffer_offset(pointer)?;
+                let buffer = buffers.get_mut(binding as usize).ok_or(ExecutionError::MissingBuffer(binding))?;
+                self.write(buffer, binding, offset, pointer.pointee, &value)
+            },
+        }
+    }
+
+    /// Reads a value laid out by Offset and ArrayStride decorations, scalars are little endian.
+    fn read(&self, buffer: &[u8], binding: u32, offset: usize, type_id: u32) -> Result<Value, ExecutionError> {
+        let values = match self.types.get(&type_id) {
+            Some(Type::Vector { component, count }) => {
+                let size = self.size(*component)? as usize;
+                (0..*count as usize)
+                    .map(|i| self.read(buffer, binding, offset + i * size, *component))
+                    .collect::<Result<_, _>>()?
+            },
+            Some(Type::Array { element, length }) => {
+                let stride = self.array_stride(type_id)? as usize;
+                (0..*length as usize)
+                    .map(|i| self.read(buffer, binding, offset + i * stride, *element))
+                    .collect::<Result<_, _>>()?
+            },
+            Some(Type::Struct { members }) => {
+                members.iter().enumerate()
+                    .map(|(i, m)| self.read(buffer, binding, offset + self.member_offset(type_id, i as u32), *m))
+                    .collect::<Result<_, _>>()?
+            },
+            _ => {
+                let size = self.size(type_id)? as usize;
+                let bytes = buffer.get(offset..offset + size).ok_or(ExecutionError::OutOfBounds(binding, offset))?;
+                return Ok(Value::Scalar(bytes.iter().rev().fold(0, |a, b| (a << 8) | *b as u64)));
+            },
+        };
+        Ok(Value::Composite(values))
+    }
+
+    fn write(
+        &self, buffer: &mut [u8], binding: u32, offset: usize, type_id: u32, value: &Value
+    ) -> Result<(), ExecutionError> {
+        match (self.types.get(&type_id), value) {
+            (Some(Type::Vector { component, .. }), Value::Composite(values)) => {
+                let size = self.size(*component)? as usize;
+                for (i, v) in values.iter().enumerate() {
+                    self.write(buffer, binding, offset + i * size, *component, v)?;
+                }
+            },
+            (Some(Type::Array { element, .. }), Value::Composite(values)) => {
+                let stride = self.array_stride(type_id)? as usize;
+                for (i, v) in values.iter().enumerate() {
+                    self.write(buffer, binding, offset + i * stride, *element, v)?;
+                }
+            },
+            (Some(Type::Struct { members }), Value::Composite(values)) => {
+                for (i, (m, v)) in members.iter().zip(values).enumerate() {
+                    self.write(buffer, binding, offset + self.member_offset(type_id, i as u32), *m, v)?;
+                }
+            },
+            (_, Value::Scalar(bits)) => {
+                let size = self.size(type_id)? as usize;
+                let bytes = buffer.get_mut(offset..offset + size).ok_or(ExecutionError::OutOfBounds(binding, offset))?;
+                bytes.copy_from_slice(&bits.to_le_bytes()[..size]);
+            },
+            _ => return Err(ExecutionError::UnsupportedType(type_id)),
+        }
+        Ok(())
+    }
+}
+
+fn as_scalar(value: &Value) -> u64 {
+    match value {
+        Value::Scalar(value) => *value,
+        _ => 0,
+    }
+}
+
+fn component(value: &Value, index: usize) -> Value {
+    match value {
+        Value::Composite(values) => values[index].clone(),
+        value => value.clone(),
+    }
+}
+
+fn components(value: &Value) -> Vec<Value> {
+    match value {
+        Value::Composite(values) => values.clone(),
+        value => vec![value.clone()],
+    }
+}
+
+fn insert(composite: &mut Value, path: &[u32], object: Value) {
+    match (composite, path.split_first()) {
+        (Value::Composite(values), Some((index, rest))) => insert(&mut values[*index as usize], rest, object),
+        (target, _) => *target = object,
+    }
+}
+
+/// Applies the operation to a scalar or to every component of a composite.
+fn map1(value: &Value, op: impl Fn(u64) -> u64 + Copy) -> Value {
+    match value {
+        Value::Scalar(a) => Value::Scalar(op(*a)),
+        Value::Composite(values) => Value::Composite(values.iter().map(|v| map1(v, op)).collect()),
+        Value::Pointer(_) => value.clone(),
+    }
+}
+
+/// Applies the operation component-wise, a scalar operand is paired with every component of the other one.
+fn map2(a: &Value, b: &Value, op: impl Fn(u64, u64) -> u64 + Copy) -> Value {
+    match (a, b) {
+        (Value::Scalar(a), Value::Scalar(b)) => Value::Scalar(op(*a, *b)),
+        (Value::Composite(a), Value::Composite(b)) => {
+            Value::Composite(a.iter().zip(b).map(|(a, b)| map2(a, b, op)).collect())
+        },
+        (Value::Composite(a), b) => Value::Composite(a.iter().map(|a| map2(a, b, op)).collect()),
+        (a, Value::Composite(b)) => Value::Composite(b.iter().map(|b| map2(a, b, op)).collect()),
+        _ => a.clone(),
+    }
+}
+
+fn map3(a: &Value, b: &Value, c: &Value, op: impl Fn(u64, u64, u64) -> u64 + Copy) -> Value {
+    match (a, b, c) {
+        (Value::Scalar(a), Value::Scalar(b), Value::Scalar(c)) => Value::Scalar(op(*a, *b, *c)),
+        (Value::Composite(a), Value::Composite(b), Value::Composite(c)) => {
+            Value::Composite(a.iter().zip(b).zip(c).map(|((a, b), c)| map3(a, b, c, op)).collect())
+        },
+        _ => a.clone(),
+    }
+}
+
+fn width(scalar: Scalar) -> u32 {
+    match scalar {
+        Scalar::Bool => 32,
+        Scalar::Int { width } | Scalar::Float { width } => width,
+    }
+}
+
+fn mask_bits(bits: u32) -> u64 {
+    if bits >= 64 { u64::MAX } else { (1 << bits) - 1 }
+}
+
+fn truncate(scalar: Scalar, bits: u64) -> u64 {
+    bits & mask_bits(width(scalar))
+}
+
+fn sign_extend(scalar: Scalar, bits: u64) -> i64 {
+    let shift = 64 - width(scalar);
+    ((bits << shift) as i64) >> shift
+}
+
+fn to_f64(scalar: Scalar, bits: u64) -> f64 {
+    match width(scalar) {
+        16 => f16_to_f32(bits as u16) as f64,
+        32 => f32::from_bits(bits as u32) as f64,
+        _ => f64::from_bits(bits),
+    }
+}
+
+/// Rounds to the width of the scalar. Halves are rounded through f32, which is not always correct for values which
+/// are not representable in f32.
+fn to_bits(scalar: Scalar, value: f64) -> u64 {
+    match width(scalar) {
+        16 => f32_to_f16(value as f32) as u64,
+        32 => (value as f32).to_bits() as u64,
+        _ => value.to_bits(),
+    }
+}
+
+fn is_normal(scalar: Scalar, value: f64) -> bool {
+    match width(scalar) {
+        16 => value.is_finite() && value.abs() >= 6.103515625e-5,
+        32 => (value as f32).is_normal(),
+        _ => value.is_normal(),
+    }
+}
+
+fn unary(opcode: u16, scalar: Scalar, a: u64) -> u64 {
+    match opcode {
+        // OpSNegate
+        126 => truncate(scalar, sign_extend(scalar, a).wrapping_neg() as u64),
+        // OpFNegate
+        127 => a ^ (1 << (width(scalar) - 1)),
+        // OpNot
+        200 => truncate(scalar, !a),
+        // OpBitReverse
+        204 => a.reverse_bits() >> (64 - width(scalar)),
+        // OpBitCount
+        _ => a.count_ones() as u64,
+    }
+}
+
+/// Integer and bit operations wrap to the width of the result, float operations are correctly rounded. Halves are
+/// computed in f32, which has more than twice their precision, so a single rounding back is still correct.
+fn binary(opcode: u16, scalar: Scalar, shift_scalar: Scalar, a: u64, b: u64) -> u64 {
+    let w = width(scalar);
+    let (ua, ub) = (truncate(scalar, a), truncate(scalar, b));
+    let (sa, sb) = (sign_extend(scalar, a), sign_extend(scalar, b));
+    let shift = truncate(shift_scalar, b) as u32;
+
+    let float = |op32: fn(f32, f32) -> f32, op64: fn(f64, f64) -> f64| match w {
+        16 => f32_to_f16(op32(f16_to_f32(a as u16), f16_to_f32(b as u16))) as u64,
+        32 => op32(f32::from_bits(a as u32), f32::from_bits(b as u32)).to_bits() as u64,
+        _ => op64(f64::from_bits(a), f64::from_bits(b)).to_bits(),
+    };
+
+    let result = match opcode {
+        // OpIAdd
+        128 => a.wrapping_add(b),
+        // OpFAdd
+        129 => float(|a, b| a + b, |a, b| a + b),
+        // OpISub
+        130 => a.wrapping_sub(b),
+        // OpFSub
+        131 => float(|a, b| a - b, |a, b| a - b),
+        // OpIMul
+        132 => a.wrapping_mul(b),
+        // OpFMul
+        133 => float(|a, b| a * b, |a, b| a * b),
+        // OpUDiv, division by zero is undefined and returns 0 here.
+        134 => ua.checked_div(ub).unwrap_or(0),
+        // OpSDiv
+        135 => if sb == 0 { 0 } else { sa.wrapping_div(sb) as u64 },
+        // OpFDiv
+        136 => float(|a, b| a / b, |a, b| a / b),
+        // OpUMod
+        137 => ua.checked_rem(ub).unwrap_or(0),
+        // OpSRem
+        138 => if sb == 0 { 0 } else { sa.wrapping_rem(sb) as u64 },
+        // OpSMod, the sign of the result matches the divisor.
+        139 => if sb == 0 { 0 } else { sa.wrapping_rem(sb).wrapping_add(sb).wrapping_rem(sb) as u64 },
+        // OpFRem
+        140 => float(|a, b| a % b, |a, b| a % b),
+        // OpFMod
+        141 => float(|a, b| a - b * (a / b).floor(), |a, b| a - b * (a / b).floor()),
+        // OpShiftRightLogical
+        194 => if shift >= w { 0 } else { ua >> shift },
+        // OpShiftRightArithmetic
+        195 => if shift >= w { (sa >> 63) as u64 } else { (sa >> shift) as u64 },
+        // OpShiftLeftLogical
+        196 => if shift >= w { 0 } else { a << shift },
+        // OpBitwiseOr
+        197 => a | b,
+        // OpBitwiseXor
+        198 => a ^ b,
+        // OpBitwiseAnd
+        _ => a & b,
+    };
+
+    match scalar {
+        Scalar::Float { .. } => result,
+        _ => truncate(scalar, result),
+    }
+}
+
+fn compare(opcode: u16, scalar: Scalar, a: u64, b: u64) -> bool {
+    let (ua, ub) = (truncate(scalar, a), truncate(scalar, b));
+    let (sa, sb) = (sign_extend(scalar, a), sign_extend(scalar, b));
+    let (fa, fb) = (to_f64(scalar, a), to_f64(scalar, b));
+    let unordered = fa.is_nan() || fb.is_nan();
+
+    match opcode {
+        // OpIEqual, OpINotEqual
+        170 => ua == ub,
+        171 => ua != ub,
+        // OpUGreaterThan, OpSGreaterThan, OpUGreaterThanEqual, OpSGreaterThanEqual
+        172 => ua > ub,
+        173 => sa > sb,
+        174 => ua >= ub,
+        175 => sa >= sb,
+        // OpULessThan, OpSLessThan, OpULessThanEqual, OpSLessThanEqual
+        176 => ua < ub,
+        177 => sa < sb,
+        178 => ua <= ub,
+        179 => sa <= sb,
+        // OpFOrdEqual, OpFUnordEqual, OpFOrdNotEqual, OpFUnordNotEqual
+        180 => fa == fb,
+        181 => unordered || fa == fb,
+        182 => !unordered && fa != fb,
+        183 => fa != fb,
+        // OpFOrdLessThan, OpFUnordLessThan, OpFOrdGreaterThan, OpFUnordGreaterThan
+        184 => fa < fb,
+        185 => unordered || fa < fb,
+        186 => fa > fb,
+        187 => unordered || fa > fb,
+        // OpFOrdLessThanEqual, OpFUnordLessThanEqual, OpFOrdGreaterThanEqual, OpFUnordGreaterThanEqual
+        188 => fa <= fb,
+        189 => unordered || fa <= fb,
+        190 => fa >= fb,
+        _ => unordered || fa >= fb,
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,530 @@
+//! Rewrites minimal modules with rorsk_conformant, runs them in the interpreter and compares the results bit for bit
+//! with Rust floats and `rorsk_core::sf32`.
+
+use std::{mem, ops::Range};
+
+use rorsk_conformant::{spirv, Mode, Options};
+use rorsk_core::{f16::{f16_to_f32, f32_to_f16}, sf32};
+use vulkano::buffer::BufferContents;
+
+const F_ADD: u32 = 129;
+const F_SUB: u32 = 131;
+const F_MUL: u32 = 133;
+const F_DIV: u32 = 136;
+const VECTOR_TIMES_SCALAR: u32 = 142;
+const GLSL_SIN: u32 = 13;
+const GLSL_COS: u32 = 14;
+const GLSL_POW: u32 = 26;
+const GLSL_EXP: u32 = 27;
+const GLSL_LOG: u32 = 28;
+const GLSL_EXP2: u32 = 29;
+const GLSL_LOG2: u32 = 30;
+const GLSL_SQRT: u32 = 31;
+const GLSL_INVERSE_SQRT: u32 = 32;
+const GLSL_FMA: u32 = 50;
+
+const MODES: [Mode; 2] = [Mode::Sf32, Mode::Ieee];
+
+trait Float: BufferContents + Copy {
+    const WIDTH: u32;
+    /// Zeroes, infinities, NaN, subnormals, extremes and a few ordinary numbers.
+    const SPECIALS: &'static [Self];
+
+    fn from_random(bits: u64) -> Self;
+    fn from_le_bytes(bytes: &[u8]) -> Self;
+    fn bits(self) -> u64;
+    fn is_nan(self) -> bool;
+}
+
+impl Float for f32 {
+    const WIDTH: u32 = 32;
+    const SPECIALS: &'static [Self] = &[
+        0.0, -0.0, f32::INFINITY, f32::NEG_INFINITY, f32::NAN, f32::from_bits(1), f32::from_bits(0x807f_ffff),
+        f32::MIN_POSITIVE, f32::MAX, 1.0, -1.5, 3.0,
+    ];
+
+    fn from_random(bits: u64) -> Self {
+        // Every fourth value is a subnormal.
+        let mask = if bits & 0x3 == 0 { 0x807f_ffff } else { u32::MAX };
+        f32::from_bits((bits >> 32) as u32 & mask)
+    }
+
+    fn from_le_bytes(bytes: &[u8]) -> Self {
+        f32::from_le_bytes(bytes.try_into().unwrap())
+    }
+
+    fn bits(self) -> u64 {
+        self.to_bits() as u64
+    }
+
+    fn is_nan(self) -> bool {
+        self.is_nan()
+    }
+}
+
+impl Float for f64 {
+    const WIDTH: u32 = 64;
+    const SPECIALS: &'static [Self] = &[
+        0.0, -0.0, f64::INFINITY, f64::NEG_INFINITY, f64::NAN, f64::from_bits(1), f64::from_bits(0x800f_ffff_ffff_ffff),
+        f64::MIN_POSITIVE, f64::MAX, 1.0, -1.5, 3.0,
+    ];
+
+    fn from_random(bits: u64) -> Self {
+        let mask = if bits & 0x3 == 0 { 0x800f_ffff_ffff_ffff } else { u64::MAX };
+        f64::from_bits(bits & mask)
+    }
+
+    fn from_le_bytes(bytes: &[u8]) -> Self {
+        f64::from_le_bytes(bytes.try_into().unwrap())
+    }
+
+    fn bits(self) -> u64 {
+        self.to_bits()
+    }
+
+    fn is_nan(self) -> bool {
+        self.is_nan()
+    }
+}
+
+/// Halves are stored as their bits.
+impl Float for u16 {
+    const WIDTH: u32 = 16;
+    const SPECIALS: &'static [Self] = &[
+        0x0000, 0x8000, 0x7c00, 0xfc00, 0x7e00, 0x0001, 0x83ff, 0x0400, 0x7bff, 0x3c00, 0xbe00, 0x4200,
+    ];
+
+    fn from_random(bits: u64) -> Self {
+        let mask = if bits & 0x3 == 0 { 0x83ff } else { u16::MAX };
+        (bits >> 48) as u16 & mask
+    }
+
+    fn from_le_bytes(bytes: &[u8]) -> Self {
+        u16::from_le_bytes(bytes.try_into().unwrap())
+    }
+
+    fn bits(self) -> u64 {
+        self as u64
+    }
+
+    fn is_nan(self) -> bool {
+        self & 0x7fff > 0x7c00
+    }
+}
+
+/// Every pair of specials followed by random numbers, so any count of invocations is exercised.
+fn operands<T: Float>(count: usize) -> [Vec<T>; 3] {
+    let specials = T::SPECIALS;
+    let mut state = 0x1234_5678_9abc_def1u64;
+    let mut random = || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        T::from_random(state)
+    };
+
+    let mut operands: [Vec<T>; 3] = Default::default();
+    for i in 0..specials.len() * specials.len() + count {
+        let (a, b) = match specials.get(i / specials.len()) {
+            Some(a) => (*a, specials[i % specials.len()]),
+            None => (random(), random()),
+        };
+        operands[0].push(a);
+        operands[1].push(b);
+        operands[2].push(random());
+    }
+    operands
+}
+
+struct Ids {
+    glsl: u32,
+    float: u32,
+    vector: u32,
+}
+
+#[derive(Default)]
+struct Shader {
+    words: Vec<u32>,
+    bound: u32,
+}
+
+impl Shader {
+    fn id(&mut self) -> u32 {
+        self.bound += 1;
+        self.bound
+    }
+
+    fn inst(&mut self, opcode: u32, operands: &[u32]) {
+        self.words.push(opcode | ((operands.len() as u32 + 1) << 16));
+        self.words.extend_from_slice(operands);
+    }
+
+    fn string(value: &str) -> Vec<u32> {
+        let mut bytes = value.as_bytes().to_vec();
+        bytes.resize(bytes.len() / 4 * 4 + 4, 0);
+        bytes.chunks(4).map(|c| u32::from_le_bytes(c.try_into().unwrap())).collect()
+    }
+}
+
+/// Builds a compute shader like the generator's template: operand `k` is loaded from binding `k`, `body` computes a
+/// value with `components` components and component `c` is stored at `i * components + c` of the next binding.
+fn shader(
+    width: u32, operand_count: u32, components: u32, body: impl FnOnce(&mut Shader, &Ids, &[u32]) -> u32
+) -> Vec<u32> {
+    let mut s = Shader::default();
+    let [glsl, main, gid] = [s.id(), s.id(), s.id()];
+    let [void, function, float, uint, int, bool] = [s.id(), s.id(), s.id(), s.id(), s.id(), s.id()];
+    let [uvec3, gid_pointer, uint_pointer, uint_0, int_0, components_id] =
+        [s.id(), s.id(), s.id(), s.id(), s.id(), s.id()];
+    let [array, float_pointer] = [s.id(), s.id()];
+    let vector = if components > 1 { s.id() } else { float };
+    let component_ids = (0..components).map(|_| s.id()).collect::<Vec<_>>();
+    // Struct, its pointer and the variable of every binding.
+    let bindings = (0..=operand_count).map(|_| [s.id(), s.id(), s.id()]).collect::<Vec<_>>();
+
+    s.inst(17, &[1]);
+    match width {
+        16 => s.inst(17, &[9]),
+        64 => s.inst(17, &[10]),
+        _ => {},
+    }
+    s.inst(11, &[&[glsl][..], &Shader::string("GLSL.std.450")].concat());
+    s.inst(14, &[0, 1]);
+    s.inst(15, &[&[5, main][..], &Shader::string("main"), &[gid]].concat());
+    s.inst(16, &[main, 17, 64, 1, 1]);
+    s.inst(71, &[gid, 11, 28]);
+    s.inst(71, &[array, 6, width / 8]);
+    for (binding, [structure, _, variable]) in bindings.iter().enumerate() {
+        s.inst(72, &[*structure, 0, 35, 0]);
+        s.inst(71, &[*structure, 3]);
+        s.inst(71, &[*variable, 34, 0]);
+        s.inst(71, &[*variable, 33, binding as u32]);
+    }
+
+    s.inst(19, &[void]);
+    s.inst(33, &[function, void]);
+    s.inst(22, &[float, width]);
+    s.inst(21, &[uint, 32, 0]);
+    s.inst(21, &[int, 32, 1]);
+    s.inst(20, &[bool]);
+    s.inst(23, &[uvec3, uint, 3]);
+    s.inst(32, &[gid_pointer, 1, uvec3]);
+    s.inst(59, &[gid_pointer, gid, 1]);
+    s.inst(32, &[uint_pointer, 1, uint]);
+    s.inst(43, &[uint, uint_0, 0]);
+    s.inst(43, &[int, int_0, 0]);
+    s.inst(43, &[uint, components_id, components]);
+    for (component, id) in component_ids.iter().enumerate() {
+        s.inst(43, &[uint, *id, component as u32]);
+    }
+    s.inst(29, &[array, float]);
+    s.inst(32, &[float_pointer, 2, float]);
+    if components > 1 {
+        s.inst(23, &[vector, float, components]);
+    }
+    for [structure, pointer, variable] in &bindings {
+        s.inst(30, &[*structure, array]);
+        s.inst(32, &[*pointer, 2, *structure]);
+        s.inst(59, &[*pointer, *variable, 2]);
+    }
+
+    s.inst(54, &[void, main, 0, function]);
+    let [entry, out_of_bounds, merge] = [s.id(), s.id(), s.id()];
+    s.inst(248, &[entry]);
+    let [pointer, i, length, is_out_of_bounds] = [s.id(), s.id(), s.id(), s.id()];
+    s.inst(65, &[uint_pointer, pointer, gid, uint_0]);
+    s.inst(61, &[uint, i, pointer]);
+    s.inst(68, &[uint, length, bindings[0][2], 0]);
+    s.inst(174, &[bool, is_out_of_bounds, i, length]);
+    s.inst(247, &[merge, 0]);
+    s.inst(250, &[is_out_of_bounds, out_of_bounds, merge]);
+    s.inst(248, &[out_of_bounds]);
+    s.inst(253, &[]);
+    s.inst(248, &[merge]);
+
+    let mut values = Vec::new();
+    for [_, _, variable] in &bindings[..operand_count as usize] {
+        let [pointer, value] = [s.id(), s.id()];
+        s.inst(65, &[float_pointer, pointer, *variable, int_0, i]);
+        s.inst(61, &[float, value, pointer]);
+        values.push(value);
+    }
+
+    let result = body(&mut s, &Ids { glsl, float, vector }, &values);
+
+    let first = s.id();
+    s.inst(132, &[uint, first, i, components_id]);
+    for (component, component_id) in component_ids.iter().enumerate() {
+        let value = match components {
+            1 => result,
+            _ => {
+                let value = s.id();
+                s.inst(81, &[float, value, result, component as u32]);
+                value
+            },
+        };
+        let [index, pointer] = [s.id(), s.id()];
+        s.inst(128, &[uint, index, first, *component_id]);
+        s.inst(65, &[float_pointer, pointer, bindings[operand_count as usize][2], int_0, index]);
+        s.inst(62, &[pointer, value]);
+    }
+    s.inst(253, &[]);
+    s.inst(56, &[]);
+
+    [&[0x0723_0203, 0x0001_0000, 0, s.bound + 1, 0][..], &s.words].concat()
+}
+
+/// Rewrites the shader in the mode and runs it on the operands, returns the stored components.
+fn run<T: Float>(shader: &[u32], mode: Mode, operands: &[&[T]], components: usize) -> Vec<T> {
+    let words = rorsk_conformant::process(shader, Options { mode, ..Default::default() })
+        .unwrap_or_else(|e| panic!("failed to rewrite the shader in {mode:?} mode: {e}"));
+    let output = super::run(&spirv::words_to_bytes(&words), operands, components * mem::size_of::<T>())
+        .unwrap_or_else(|e| panic!("failed to run the shader rewritten in {mode:?} mode: {e}"));
+    output.data.chunks(mem::size_of::<T>()).map(T::from_le_bytes).collect()
+}
+
+/// Compares bits, any NaN is equal to any other NaN.
+fn check<T: Float>(name: &str, mode: Mode, got: &[T], expected: &[T]) {
+    assert_eq!(got.len(), expected.len(), "{name} in {mode:?} mode returned a wrong number of results");
+    let mismatches = got.iter().zip(expected)
+        .enumerate()
+        .filter(|(_, (got, expected))| got.bits() != expected.bits() && !(got.is_nan() && expected.is_nan()))
+        .map(|(i, (got, expected))| format!("[{i}] {:#x} instead of {:#x}", got.bits(), expected.bits()))
+        .collect::<Vec<_>>();
+    assert!(mismatches.is_empty(), "{name} in {mode:?} mode has {} mismatches: {:?}", mismatches.len(),
+        &mismatches[..mismatches.len().min(8)]);
+}
+
+fn ieee_f32(opcode: u32, a: f32, b: f32) -> f32 {
+    match opcode {
+        F_ADD => a + b,
+        F_SUB => a - b,
+        F_MUL => a * b,
+        _ => a / b,
+    }
+}
+
+fn ieee_f64(opcode: u32, a: f64, b: f64) -> f64 {
+    match opcode {
+        F_ADD => a + b,
+        F_SUB => a - b,
+        F_MUL => a * b,
+        _ => a / b,
+    }
+}
+
+/// Correctly rounded, f32 has more than twice the precision of f16.
+fn ieee_f16(opcode: u32, a: u16, b: u16) -> u16 {
+    f32_to_f16(ieee_f32(opcode, f16_to_f32(a), f16_to_f32(b)))
+}
+
+/// Only f32 has a software float counterpart, other widths are lowered to IEEE-754 in both modes.
+fn conformant_f32(mode: Mode, opcode: u32, a: f32, b: f32) -> f32 {
+    match (mode, opcode) {
+        (Mode::Sf32, F_ADD) => sf32::conformant_add(a, b),
+        (Mode::Sf32, F_SUB) => sf32::conformant_sub(a, b),
+        (Mode::Sf32, F_MUL) => sf32::conformant_mul(a, b),
+        (Mode::Sf32, _) => sf32::conformant_div(a, b),
+        (Mode::Ieee, _) => ieee_f32(opcode, a, b),
+    }
+}
+
+fn check_binary<T: Float>(expected: impl Fn(Mode, u32, T, T) -> T) {
+    let [a, b, _] = operands::<T>(100);
+    for opcode in [F_ADD, F_SUB, F_MUL, F_DIV] {
+        let shader = shader(T::WIDTH, 2, 1, |s, ids, values| {
+            let result = s.id();
+            s.inst(opcode, &[ids.float, result, values[0], values[1]]);
+            result
+        });
+        for mode in MODES {
+            let got = run(&shader, mode, &[&a, &b], 1);
+            let expected = a.iter().zip(&b).map(|(a, b)| expected(mode, opcode, *a, *b)).collect::<Vec<_>>();
+            check(&format!("f{} opcode {opcode}", T::WIDTH), mode, &got, &expected);
+        }
+    }
+}
+
+#[test]
+fn binary_f16() {
+    check_binary::<u16>(|_, opcode, a, b| ieee_f16(opcode, a, b));
+}
+
+#[test]
+fn binary_f32() {
+    check_binary::<f32>(conformant_f32);
+}
+
+#[test]
+fn binary_f64() {
+    check_binary::<f64>(|_, opcode, a, b| ieee_f64(opcode, a, b));
+}
+
+/// Vectors of `a, b, a, b` and `b, a, b, a`, so every component has other operands.
+fn construct_vectors(s: &mut Shader, ids: &Ids, components: u32, a: u32, b: u32) -> [u32; 2] {
+    let [x, y] = [s.id(), s.id()];
+    let (even, odd) = ([a, b, a, b], [b, a, b, a]);
+    s.inst(80, &[&[ids.vector, x][..], &even[..components as usize]].concat());
+    s.inst(80, &[&[ids.vector, y][..], &odd[..components as usize]].concat());
+    [x, y]
+}
+
+#[test]
+fn binary_vectors() {
+    let [a, b, _] = operands::<f32>(100);
+    for components in 2..=4 {
+        for opcode in [F_ADD, F_SUB, F_MUL, F_DIV] {
+            let shader = shader(32, 2, components, |s, ids, values| {
+                let [x, y] = construct_vectors(s, ids, components, values[0], values[1]);
+                let result = s.id();
+                s.inst(opcode, &[ids.vector, result, x, y]);
+                result
+            });
+            for mode in MODES {
+                let got = run(&shader, mode, &[&a, &b], components as usize);
+                let expected = a.iter().zip(&b)
+                    .flat_map(|(a, b)| (0..components).map(move |c| if c % 2 == 0 { (*a, *b) } else { (*b, *a) }))
+                    .map(|(x, y)| conformant_f32(mode, opcode, x, y))
+                    .collect::<Vec<_>>();
+                check(&format!("vec{components} opcode {opcode}"), mode, &got, &expected);
+            }
+        }
+    }
+}
+
+#[test]
+fn vector_times_scalar() {
+    let [a, b, _] = operands::<f32>(100);
+    for components in 2..=4 {
+        let shader = shader(32, 2, components, |s, ids, values| {
+            let [x, _] = construct_vectors(s, ids, components, values[0], values[1]);
+            let result = s.id();
+            s.inst(VECTOR_TIMES_SCALAR, &[ids.vector, result, x, values[1]]);
+            result
+        });
+        for mode in MODES {
+            let got = run(&shader, mode, &[&a, &b], components as usize);
+            let expected = a.iter().zip(&b)
+                .flat_map(|(a, b)| (0..components).map(move |c| (if c % 2 == 0 { *a } else { *b }, *b)))
+                .map(|(x, y)| conformant_f32(mode, F_MUL, x, y))
+                .collect::<Vec<_>>();
+            check(&format!("vec{components} OpVectorTimesScalar"), mode, &got, &expected);
+        }
+    }
+}
+
+fn ext_inst_shader(number: u32, operand_count: u32) -> Vec<u32> {
+    shader(32, operand_count, 1, |s, ids, values| {
+        let result = s.id();
+        s.inst(12, &[&[ids.float, result, ids.glsl, number][..], values].concat());
+        result
+    })
+}
+
+/// Square root and fma are correctly rounded in both modes.
+#[test]
+fn sqrt() {
+    let [a, _, _] = operands::<f32>(100);
+    let shader = ext_inst_shader(GLSL_SQRT, 1);
+    for mode in MODES {
+        let got = run(&shader, mode, &[&a], 1);
+        check("sqrt", mode, &got, &a.iter().map(|a| a.sqrt()).collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn fma() {
+    let [a, b, c] = operands::<f32>(100);
+    let shader = ext_inst_shader(GLSL_FMA, 3);
+    for mode in MODES {
+        let got = run(&shader, mode, &[&a, &b, &c], 1);
+        let expected = (0..a.len()).map(|i| a[i].mul_add(b[i], c[i])).collect::<Vec<_>>();
+        check("fma", mode, &got, &expected);
+    }
+}
+
+/// `operands` followed by `count` operands spread over `a` and `b`.
+fn operands_in(count: usize, a: Range<f32>, b: Range<f32>) -> [Vec<f32>; 2] {
+    let [mut x, mut y, _] = operands::<f32>(count);
+    for i in 0..count {
+        // Steps of the golden ratio through `b`, so the pairs do not repeat a pattern.
+        x.push(a.start + (a.end - a.start) * (i as f32 / count as f32));
+        y.push(b.start + (b.end - b.start) * (i as f32 * 0.618034).fract());
+    }
+    [x, y]
+}
+
+/// Distance to the exact result in units in the last place of the f32 nearest to it. NaN against NaN and results
+/// which are equal, including zeros of a different sign, are 0.
+fn ulp_error(got: f32, expected: f64) -> f64 {
+    if got.is_nan() || expected.is_nan() {
+        return if got.is_nan() && expected.is_nan() { 0.0 } else { f64::INFINITY };
+    }
+    if got as f64 == expected || got == expected as f32 && got.is_infinite() {
+        return 0.0;
+    }
+    if got.is_infinite() || expected.is_infinite() {
+        return f64::INFINITY;
+    }
+    let exponent = ((expected.abs().to_bits() >> 52) as i32 - 1023).max(-126);
+    (got as f64 - expected).abs() / 2f64.powi(exponent - 23)
+}
+
+/// The other GLSL.std.450 instructions are approximations, which are compared with f64 up to their maximum error, as
+/// measured on tens of millions of inputs.
+fn check_ulp(name: &str, number: u32, operands: &[&[f32]], expected: impl Fn(usize) -> f64, max_ulp: f64) {
+    let shader = ext_inst_shader(number, operands.len() as u32);
+    for mode in MODES {
+        let got = run(&shader, mode, operands, 1);
+        let exceeding = got.iter()
+            .enumerate()
+            .filter(|(i, got)| ulp_error(**got, expected(*i)) > max_ulp)
+            .map(|(i, got)| {
+                let operands = operands.iter().map(|o| o[i]).collect::<Vec<_>>();
+                format!("{operands:?} gave {got:?} instead of {:?}", expected(i))
+            })
+            .collect::<Vec<_>>();
+        assert!(exceeding.is_empty(), "{name} in {mode:?} mode exceeds {max_ulp} ULP {} times: {:?}", exceeding.len(),
+            &exceeding[..exceeding.len().min(8)]);
+    }
+}
+
+#[test]
+fn inverse_sqrt() {
+    let [a, _] = operands_in(100, 0.0..100.0, 0.0..1.0);
+    check_ulp("inversesqrt", GLSL_INVERSE_SQRT, &[&a], |i| 1.0 / (a[i] as f64).sqrt(), 1.5);
+}
+
+#[test]
+fn exp() {
+    let [a, _] = operands_in(100, -110.0..100.0, 0.0..1.0);
+    check_ulp("exp", GLSL_EXP, &[&a], |i| (a[i] as f64).exp(), 1.5);
+    check_ulp("exp2", GLSL_EXP2, &[&a], |i| (a[i] as f64).exp2(), 1.5);
+}
+
+#[test]
+fn log() {
+    let [a, _] = operands_in(100, -10.0..1000.0, 0.0..1.0);
+    check_ulp("log", GLSL_LOG, &[&a], |i| (a[i] as f64).ln(), 3.0);
+    check_ulp("log2", GLSL_LOG2, &[&a], |i| (a[i] as f64).log2(), 3.0);
+}
+
+/// GLSL leaves pow undefined for negative bases, the lowering returns NaN for them.
+#[test]
+fn pow() {
+    let [a, b] = operands_in(100, 0.0..20.0, -40.0..40.0);
+    let expected = |i: usize| match (a[i], b[i]) {
+        (a, b) if a < 0.0 && b != 0.0 => f64::NAN,
+        (a, b) => (a.abs() as f64).powf(b as f64),
+    };
+    check_ulp("pow", GLSL_POW, &[&a, &b], expected, 2.0);
+}
+
+/// Large arguments are reduced modulo pi/2 with every bit of 2/pi they need.
+#[test]
+fn sin_cos() {
+    let [mut a, _] = operands_in(100, -100.0..100.0, 0.0..1.0);
+    a.extend([65536.0, -70000.0, 1e10, 1e30, f32::MAX, -f32::MAX, 1.8235002e37, 2.8229613e37]);
+    check_ulp("sin", GLSL_SIN, &[&a], |i| (a[i] as f64).sin(), 2.0);
+    check_ulp("cos", GLSL_COS, &[&a], |i| (a[i] as f64).cos(), 2.0);
+}
@@ -1,42 +1,286 @@
-use std::mem;
+use std::{path::PathBuf, process::ExitCode};
 
-use compute::Compute;
+use clap::{Args, Parser, Subcommand, CommandFactory, error::ErrorKind};
+use compute::{Compute, Settings};
+use error::Error;
+use reference::{Operation, Reference};
+use runner::{DeviceSelector, Runner};
+use rorsk_core::result_file::ElementType;
 
 mod compute;
-mod conformant;
+mod error;
+mod interpreter;
 mod noise;
+mod reference;
 mod runner;
 
 const DATA_SIZE: usize = 16 * 1000 * 1000; // 16 MB.
 
-fn main() {
-    f32_problems();
-    i32_problems();
+pub(crate) struct Problem {
+    pub name: &'static str,
+    pub element_type: ElementType,
+    pub expression: &'static str,
+    pub operation: Operation,
 }
 
-fn f32_problems() {
-    let mut vec = Vec::with_capacity(DATA_SIZE / mem::size_of::<f32>() * 2);
-    for i in 0..vec.capacity() {
-        vec.push(noise::white_noise_1d_f32(i));
+const fn problem(
+    name: &'static str, element_type: ElementType, expression: &'static str, operation: Operation
+) -> Problem {
+    Problem { name, element_type, expression, operation }
+}
+
+const PROBLEMS: &[Problem] = &[
+    problem("f32-add", ElementType::F32, "r = a + b;", Operation::Add),
+    problem("f32-sub", ElementType::F32, "r = a - b;", Operation::Sub),
+    problem("f32-mul", ElementType::F32, "r = a * b;", Operation::Mul),
+    problem("f32-div", ElementType::F32, "r = a / b;", Operation::Div),
+    problem("f32-sqrt", ElementType::F32, "r = sqrt(a);", Operation::Sqrt),
+    problem("f32-inversesqrt", ElementType::F32, "r = inversesqrt(a);", Operation::InverseSqrt),
+    problem("f32-fma", ElementType::F32, "r = fma(a, b, c);", Operation::Fma),
+    problem("f32-exp", ElementType::F32, "r = exp(a);", Operation::Exp),
+    problem("f32-exp2", ElementType::F32, "r = exp2(a);", Operation::Exp2),
+    problem("f32-log", ElementType::F32, "r = log(a);", Operation::Log),
+    problem("f32-log2", ElementType::F32, "r = log2(a);", Operation::Log2),
+    problem("f32-pow", ElementType::F32, "r = pow(a, b);", Operation::Pow),
+    problem("f32-sin", ElementType::F32, "r = sin(a);", Operation::Sin),
+    problem("f32-cos", ElementType::F32, "r = cos(a);", Operation::Cos),
+    problem("f32-less", ElementType::F32, "r = int(a < b);", Operation::Less),
+    problem("i32-add", ElementType::I32, "r = a + b;", Operation::Add),
+    problem("i32-sub", ElementType::I32, "r = a - b;", Operation::Sub),
+    problem("i32-mul", ElementType::I32, "r = a * b;", Operation::Mul),
+    problem("i32-div", ElementType::I32, "r = a / b;", Operation::Div),
+    problem("i32-less", ElementType::I32, "r = int(a < b);", Operation::Less),
+    problem("f64-add", ElementType::F64, "r = a + b;", Operation::Add),
+    problem("f64-sub", ElementType::F64, "r = a - b;", Operation::Sub),
+    problem("f64-mul", ElementType::F64, "r = a * b;", Operation::Mul),
+    problem("f64-div", ElementType::F64, "r = a / b;", Operation::Div),
+    problem("f64-less", ElementType::F64, "r = int(a < b);", Operation::Less),
+    problem("f16-add", ElementType::F16, "r = a + b;", Operation::Add),
+    problem("f16-sub", ElementType::F16, "r = a - b;", Operation::Sub),
+    problem("f16-mul", ElementType::F16, "r = a * b;", Operation::Mul),
+    problem("f16-div", ElementType::F16, "r = a / b;", Operation::Div),
+    problem("f16-less", ElementType::F16, "r = int(a < b);", Operation::Less),
+];
+
+/// Generates conformant and unconformant data for the Vulkan devices of this machine.
+#[derive(Parser)]
+#[command(version, args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    run: RunArgs,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Computes the selected problems. This is the default when no subcommand is given.
+    Run(RunArgs),
+    /// Lists Vulkan devices together with the indices, names and ids accepted by `--device`.
+    ListDevices,
+    /// Lists problems which can be selected with `--problem`.
+    ListProblems,
+}
+
+#[derive(Args)]
+struct RunArgs {
+    /// Problem name or glob pattern like `f32-*`, can be repeated. Every problem is computed when omitted.
+    #[arg(short, long = "problem", value_name = "PATTERN")]
+    problems: Vec<glob::Pattern>,
+
+    /// Number of elements in each operand. Defaults to 16 MB of data per operand.
+    #[arg(short = 'n', long, value_parser = parse_count)]
+    count: Option<usize>,
+
+    /// Seed of the input data noise.
+    #[arg(long, default_value_t = 0)]
+    seed: u32,
+
+    /// Directory where result files are saved.
+    #[arg(short, long, value_name = "DIR", default_value = "../output")]
+    output: PathBuf,
+
+    /// Device to compute on, can be repeated: an index from `list-devices`, a name substring, `id:VENDOR[:DEVICE]`
+    /// or `type:discrete|integrated|virtual|cpu|other`. The best suited device is chosen when omitted.
+    #[arg(short, long = "device", value_name = "SELECTOR")]
+    devices: Vec<DeviceSelector>,
+
+    /// Computes every problem on every suitable device, each device saves its own result files.
+    #[arg(long, conflicts_with = "devices")]
+    all_devices: bool,
+
+    /// Runs shaders with the SPIR-V interpreter on the CPU instead of a Vulkan device. Much slower, so a small
+    /// `--count` is recommended.
+    #[arg(long, conflicts_with_all = ["devices", "all_devices"])]
+    interpreter: bool,
+
+    /// Saves the disassembly of the original and the conformant SPIR-V module of every problem next to the result
+    /// files, together with a diff between them.
+    #[arg(long, conflicts_with = "unconformant_only")]
+    disassemble: bool,
+
+    /// Software float used for f32 arithmetic of conformant data, `sf32` or `ieee`.
+    #[arg(long, env = "RORSK_CONFORMANT_MODE", default_value = "sf32")]
+    mode: rorsk_conformant::Mode,
+
+    /// Computes only conformant data.
+    #[arg(long, conflicts_with = "unconformant_only")]
+    conformant_only: bool,
+
+    /// Computes only unconformant data.
+    #[arg(long)]
+    unconformant_only: bool,
+
+    /// Skips reference data computed on the CPU.
+    #[arg(long)]
+    no_reference: bool,
+}
+
+fn parse_count(value: &str) -> Result<usize, String> {
+    let count: usize = value.parse().map_err(|e| format!("{e}"))?;
+    if count == 0 {
+        return Err("must be positive".to_owned());
     }
-    let c = Compute::new(vec);
+    Ok(count)
+}
 
-    c.compute("f32-add", "float", "r = a + b;");
-    c.compute("f32-sub", "float", "r = a - b;");
-    c.compute("f32-mul", "float", "r = a * b;");
-    c.compute("f32-div", "float", "r = a / b;");
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match cli.command {
+        None => run(cli.run),
+        Some(Command::Run(args)) => run(args),
+        Some(Command::ListDevices) => match runner::list_devices() {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("Failed to list devices: {e}.");
+                ExitCode::from(2)
+            }
+        },
+        Some(Command::ListProblems) => {
+            for problem in PROBLEMS {
+                println!("{:<16} {}", problem.name, problem.expression);
+            }
+            ExitCode::SUCCESS
+        }
+    }
 }
 
-fn i32_problems() {
-    let mut vec = Vec::with_capacity(DATA_SIZE / mem::size_of::<i32>() * 2);
-    for i in 0..vec.capacity() {
-        let mut e = ((noise::white_noise_1d_f32(i) - 0.5) * 1000000000.0) as i32;
-        if e == 0 {
-            e = 1;
+fn run(args: RunArgs) -> ExitCode {
+    for pattern in &args.problems {
+        if !PROBLEMS.iter().any(|p| pattern.matches(p.name)) {
+            Cli::command().error(
+                ErrorKind::InvalidValue, format!("no problem matches `{pattern}`, see `list-problems`")
+            ).exit();
         }
-        vec.push(e);
     }
 
+    let settings = Settings {
+        conformant_mode: args.mode,
+        output_dir: args.output.clone(),
+        disassemble: args.disassemble,
+        unconformant: !args.conformant_only,
+        conformant: !args.unconformant_only,
+        reference: !args.no_reference,
+    };
+    println!("Using conformant mode: {}.", settings.conformant_mode);
+
+    let runners = if args.interpreter {
+        Vec::new()
+    } else {
+        match runner::create_runners(&args.devices, args.all_devices) {
+            Ok(runners) => runners,
+            Err(e) => {
+                eprintln!("Failed to initialize Vulkan: {e}.");
+                return ExitCode::from(2);
+            }
+        }
+    };
+    // `None` runs shaders with the interpreter.
+    let runners = if args.interpreter { vec![None] } else { runners.iter().map(Some).collect::<Vec<_>>() };
+
+    // A failed problem is reported and skipped, so one missing extension does not stop the whole run.
+    let mut failed = 0;
+    for element_type in [ElementType::F32, ElementType::I32, ElementType::F64, ElementType::F16] {
+        let selected = PROBLEMS.iter()
+            .filter(|p| p.element_type == element_type)
+            .filter(|p| args.problems.is_empty() || args.problems.iter().any(|pattern| pattern.matches(p.name)))
+            .collect::<Vec<_>>();
+        if selected.is_empty() {
+            continue;
+        }
+
+        let count = args.count.unwrap_or(DATA_SIZE / element_type.size());
+        failed += match element_type {
+            ElementType::F32 => compute(f32_data(count, args.seed), element_type, &settings, &runners, &selected),
+            ElementType::I32 => match i32_data(count, args.seed) {
+                Ok(data) => compute(data, element_type, &settings, &runners, &selected),
+                Err(e) => {
+                    eprintln!("Failed to generate {} data: {e}.", element_type.name());
+                    selected.len()
+                }
+            },
+            ElementType::F64 => compute(f64_data(count, args.seed), element_type, &settings, &runners, &selected),
+            ElementType::F16 => compute(f16_data(count, args.seed), element_type, &settings, &runners, &selected),
+        };
+    }
+
+    if failed != 0 {
+        eprintln!("{failed} computation(s) failed.");
+        return ExitCode::from(1);
+    }
+    ExitCode::SUCCESS
+}
+
+/// Computes the problems on every runner and returns how many computations failed.
+fn compute<T>(
+    initial_data: Vec<T>, element_type: ElementType, settings: &Settings, runners: &[Option<&Runner>],
+    problems: &[&Problem]
+) -> usize where T: vulkano::buffer::BufferContents + Clone + Reference {
+    let c = Compute::new(initial_data, element_type, settings.clone());
+    let mut failed = 0;
+    for problem in problems {
+        match c.compile(problem) {
+            Ok(shaders) => for runner in runners {
+                if let Err(e) = c.compute(problem, &shaders, *runner) {
+                    let device_name = runner.map_or("SPIR-V interpreter", Runner::device_name);
+                    eprintln!("Failed to compute problem `{}` on `{device_name}`: {e}.", problem.name);
+                    failed += 1;
+                }
+            },
+            Err(e) => {
+                eprintln!("Failed to compile problem `{}`: {e}.", problem.name);
+                failed += 1;
+            }
+        }
+
+        if let Err(e) = c.compute_on_cpu(problem) {
+            eprintln!("Failed to compute problem `{}` on the CPU: {e}.", problem.name);
+            failed += 1;
+        }
+    }
+    failed
+}
+
+fn f32_data(count: usize, seed: u32) -> Vec<f32> {
+    (0..count * 3).map(|i| noise::white_noise_1d_f32(seed, i)).collect()
+}
+
+fn f64_data(count: usize, seed: u32) -> Vec<f64> {
+    (0..count * 3).map(|i| noise::white_noise_1d_f64(seed, i)).collect()
+}
+
+fn f16_data(count: usize, seed: u32) -> Vec<u16> {
+    // Halves are stored as their bits, Rust has no stable f16 type.
+    (0..count * 3).map(|i| noise::white_noise_1d_f16(seed, i)).collect()
+}
+
+fn i32_data(count: usize, seed: u32) -> Result<Vec<i32>, Error> {
+    let vec = (0..count * 3).map(|i| {
+        let e = ((noise::white_noise_1d_f32(seed, i) - 0.5) * 1000000000.0) as i32;
+        if e == 0 { 1 } else { e }
+    }).collect::<Vec<_>>();
+
     let mut has_positive = false;
     let mut has_negative = false;
     for i in &vec {
@@ -52,13 +296,8 @@ fn i32_problems() {
     }
 
     if !has_positive || !has_negative {
-        panic!("The generated data is not suitable for this test.");
+        return Err(Error::UnsuitableData);
     }
 
-    let c = Compute::new(vec);
-
-    c.compute("i32-add", "int", "r = a + b;");
-    c.compute("i32-sub", "int", "r = a - b;");
-    c.compute("i32-mul", "int", "r = a * b;");
-    c.compute("i32-div", "int", "r = a / b;");
+    Ok(vec)
 }
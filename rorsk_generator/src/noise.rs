@@ -1,10 +1,24 @@
 use std::num::Wrapping;
 
-pub(crate) fn white_noise_1d_f32(x: usize) -> f32 {
-    let mut n = Wrapping(31337);
+pub(crate) fn white_noise_1d_f64(seed: u32, x: usize) -> f64 {
+    let mut n = Wrapping(31337 ^ seed);
     n ^= Wrapping(1619) * Wrapping(x as u32);
 
     n = n * n * n * Wrapping(60493);
-    let d = n.0 as f64 / 2147483648.0f64;
-    d as f32
+    n.0 as f64 / 2147483648.0f64
+}
+
+pub(crate) fn white_noise_1d_f32(seed: u32, x: usize) -> f32 {
+    white_noise_1d_f64(seed, x) as f32
+}
+
+/// Returns bits of a half float, the fraction is truncated and values below the normal range become zero.
+pub(crate) fn white_noise_1d_f16(seed: u32, x: usize) -> u16 {
+    let bits = white_noise_1d_f32(seed, x).to_bits();
+    let exp = (bits >> 23) as i32 - 127 + 15;
+    if exp <= 0 {
+        return 0;
+    }
+
+    ((exp as u32) << 10 | (bits >> 13) & 0x3FF) as u16
 }
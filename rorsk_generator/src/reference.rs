@@ -0,0 +1,176 @@
+use rorsk_core::{f16::{f16_to_f32, f32_to_f16}, result_file::ElementType, sf32};
+use rorsk_conformant::Mode;
+
+use crate::compute::as_bytes;
+
+/// Operations of problems, computed on the CPU to get reference results.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Operation {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Sqrt,
+    InverseSqrt,
+    Fma,
+    Exp,
+    Exp2,
+    Log,
+    Log2,
+    Pow,
+    Sin,
+    Cos,
+    /// a < b
+    Less,
+}
+
+impl Operation {
+    /// Number of operands used by the operation, `a`, `b` and `c` in this order.
+    pub(crate) fn arity(self) -> usize {
+        match self {
+            Operation::Sqrt | Operation::InverseSqrt | Operation::Exp | Operation::Exp2 | Operation::Log
+            | Operation::Log2 | Operation::Sin | Operation::Cos => 1,
+            Operation::Fma => 3,
+            _ => 2,
+        }
+    }
+
+    /// Comparisons return `int` 0 or 1, other operations return the type of their operands.
+    pub(crate) fn result_type(self, element_type: ElementType) -> ElementType {
+        match self {
+            Operation::Less => ElementType::I32,
+            _ => element_type,
+        }
+    }
+}
+
+pub(crate) trait Reference: Copy {
+    /// Result of the operation, `None` when it has no reference implementation for the type.
+    fn reference(operation: Operation, a: Self, b: Self, c: Self) -> Option<Self>;
+
+    fn less(a: Self, b: Self) -> bool;
+
+    /// Result of the conformant module, `None` when it can not be emulated on the CPU. Types other than f32 are
+    /// lowered to correctly rounded IEEE-754, which is the reference.
+    fn conformant(operation: Operation, _mode: Mode, a: Self, b: Self, c: Self) -> Option<Self> {
+        Self::reference(operation, a, b, c)
+    }
+}
+
+/// Returns bytes of the results, which have the result type of the operation, `None` when the operation has no
+/// reference implementation for the type.
+pub(crate) fn compute<T: Reference>(operation: Operation, operands: [&[T]; 3]) -> Option<Vec<u8>> {
+    evaluate(operation, operands, |a, b, c| T::reference(operation, a, b, c))
+}
+
+pub(crate) fn compute_conformant<T: Reference>(
+    operation: Operation, mode: Mode, operands: [&[T]; 3]
+) -> Option<Vec<u8>> {
+    evaluate(operation, operands, |a, b, c| T::conformant(operation, mode, a, b, c))
+}
+
+fn evaluate<T: Reference>(
+    operation: Operation, [a, b, c]: [&[T]; 3], f: impl Fn(T, T, T) -> Option<T>
+) -> Option<Vec<u8>> {
+    // Comparisons are exact, so the conformant module returns the reference too.
+    if operation == Operation::Less {
+        let results = a.iter().zip(b).map(|(a, b)| T::less(*a, *b) as i32).collect::<Vec<_>>();
+        return Some(as_bytes(&results).to_vec());
+    }
+
+    let results = (0..a.len()).map(|i| f(a[i], b[i], c[i])).collect::<Option<Vec<_>>>()?;
+    Some(as_bytes(&results).to_vec())
+}
+
+impl Reference for f32 {
+    fn reference(operation: Operation, a: Self, b: Self, c: Self) -> Option<Self> {
+        Some(match operation {
+            Operation::Add => a + b,
+            Operation::Sub => a - b,
+            Operation::Mul => a * b,
+            Operation::Div => a / b,
+            Operation::Sqrt => a.sqrt(),
+            // Computed in f64, so the result is rounded only once in practice.
+            Operation::InverseSqrt => (1.0 / (a as f64).sqrt()) as f32,
+            Operation::Fma => a.mul_add(b, c),
+            Operation::Exp => a.exp(),
+            Operation::Exp2 => a.exp2(),
+            Operation::Log => a.ln(),
+            Operation::Log2 => a.log2(),
+            Operation::Pow => a.powf(b),
+            Operation::Sin => a.sin(),
+            Operation::Cos => a.cos(),
+            // Comparisons are computed by `less`.
+            Operation::Less => return None,
+        })
+    }
+
+    fn less(a: Self, b: Self) -> bool {
+        a < b
+    }
+
+    fn conformant(operation: Operation, mode: Mode, a: Self, b: Self, c: Self) -> Option<Self> {
+        match (mode, operation) {
+            (Mode::Sf32, Operation::Add) => Some(sf32::conformant_add(a, b)),
+            (Mode::Sf32, Operation::Sub) => Some(sf32::conformant_sub(a, b)),
+            (Mode::Sf32, Operation::Mul) => Some(sf32::conformant_mul(a, b)),
+            (Mode::Sf32, Operation::Div) => Some(sf32::conformant_div(a, b)),
+            // Square root and fma are correctly rounded in both modes.
+            (_, Operation::Add | Operation::Sub | Operation::Mul | Operation::Div)
+            | (_, Operation::Sqrt | Operation::Fma) => Self::reference(operation, a, b, c),
+            // The rest are polynomial approximations, which are not emulated. Their maximum errors against f64 are
+            // checked by the interpreter tests.
+            _ => None,
+        }
+    }
+}
+
+impl Reference for f64 {
+    fn reference(operation: Operation, a: Self, b: Self, _c: Self) -> Option<Self> {
+        match operation {
+            Operation::Add => Some(a + b),
+            Operation::Sub => Some(a - b),
+            Operation::Mul => Some(a * b),
+            Operation::Div => Some(a / b),
+            _ => None,
+        }
+    }
+
+    fn less(a: Self, b: Self) -> bool {
+        a < b
+    }
+}
+
+impl Reference for i32 {
+    fn reference(operation: Operation, a: Self, b: Self, _c: Self) -> Option<Self> {
+        // GLSL integer arithmetic wraps, division rounds towards zero.
+        match operation {
+            Operation::Add => Some(a.wrapping_add(b)),
+            Operation::Sub => Some(a.wrapping_sub(b)),
+            Operation::Mul => Some(a.wrapping_mul(b)),
+            Operation::Div => Some(a.wrapping_div(b)),
+            _ => None,
+        }
+    }
+
+    fn less(a: Self, b: Self) -> bool {
+        a < b
+    }
+}
+
+/// Halves are stored as their bits. Arithmetic is done in f32 and rounded back, which is correctly rounded because f32
+/// has more than twice the precision of f16.
+impl Reference for u16 {
+    fn reference(operation: Operation, a: Self, b: Self, c: Self) -> Option<Self> {
+        match operation {
+            Operation::Add | Operation::Sub | Operation::Mul | Operation::Div => {
+                f32::reference(operation, f16_to_f32(a), f16_to_f32(b), f16_to_f32(c)).map(f32_to_f16)
+            }
+            _ => None,
+        }
+    }
+
+    fn less(a: Self, b: Self) -> bool {
+        f16_to_f32(a) < f16_to_f32(b)
+    }
+}
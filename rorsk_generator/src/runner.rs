@@ -1,3 +1,5 @@
+use std::{fmt, mem, str::FromStr, sync::Arc};
+
 use vulkano::{
     buffer::{Buffer, BufferCreateInfo, BufferUsage, BufferContents},
     command_buffer::{
@@ -7,8 +9,8 @@ use vulkano::{
         allocator::StandardDescriptorSetAllocator, PersistentDescriptorSet, WriteDescriptorSet,
     },
     device::{
-        physical::PhysicalDeviceType, Device, DeviceCreateInfo, DeviceExtensions, QueueCreateInfo,
-        QueueFlags, Features,
+        physical::{PhysicalDevice, PhysicalDeviceType}, Device, DeviceCreateInfo, DeviceExtensions, Queue,
+        QueueCreateInfo, QueueFlags, Features,
     },
     instance::{Instance, InstanceCreateInfo},
     memory::allocator::{AllocationCreateInfo, MemoryUsage, StandardMemoryAllocator},
@@ -17,154 +19,422 @@ use vulkano::{
     VulkanLibrary, shader::ShaderModule,
 };
 
-pub struct Output<T> {
-    pub data: Vec<T>,
-    pub device_name: String,
-    pub device_vendor_id: u32,
-    pub device_id: u32
+use rorsk_core::result_file::{DeviceInfo, FloatControls};
+
+use crate::error::Error;
+
+pub struct Output {
+    /// Bytes of the results.
+    pub data: Vec<u8>,
+    pub device: DeviceInfo,
 }
 
-pub fn run<T>(
-    spirv_code: &[u8], initial_data: &[T], group_count: usize
-) -> Output<T> where T: BufferContents + Clone {
-    let library = VulkanLibrary::new().unwrap();
-    let instance = Instance::new(
+fn create_instance() -> Result<Arc<Instance>, Error> {
+    let library = VulkanLibrary::new().map_err(Error::vulkan("load the Vulkan library"))?;
+    Instance::new(
         library,
         InstanceCreateInfo {
             enumerate_portability: true,
             ..Default::default()
         },
     )
-    .unwrap();
+    .map_err(Error::vulkan("create the instance"))
+}
 
-    // Choose which physical device to use.
-    let device_extensions = DeviceExtensions {
+fn required_extensions() -> DeviceExtensions {
+    DeviceExtensions {
         khr_storage_buffer_storage_class: true,
         ..DeviceExtensions::empty()
+    }
+}
+
+fn compute_queue_family_index(physical_device: &PhysicalDevice) -> Option<u32> {
+    if !physical_device.supported_extensions().contains(&required_extensions()) {
+        return None;
+    }
+
+    // The Vulkan specs guarantee that a compliant implementation must provide at least one
+    // queue that supports compute operations.
+    physical_device.queue_family_properties()
+        .iter()
+        .position(|q| q.queue_flags.intersects(QueueFlags::COMPUTE))
+        .map(|i| i as u32)
+}
+
+/// Selects devices by their index from `list-devices`, a name substring, a vendor and device id or a type.
+#[derive(Clone, Debug)]
+pub enum DeviceSelector {
+    Index(usize),
+    Name(String),
+    Id { vendor_id: u32, device_id: Option<u32> },
+    Type(PhysicalDeviceType),
+}
+
+impl DeviceSelector {
+    fn matches(&self, index: usize, physical_device: &PhysicalDevice) -> bool {
+        let properties = physical_device.properties();
+        match self {
+            DeviceSelector::Index(i) => *i == index,
+            DeviceSelector::Name(name) => properties.device_name.to_lowercase().contains(&name.to_lowercase()),
+            DeviceSelector::Id { vendor_id, device_id } => {
+                properties.vendor_id == *vendor_id && device_id.is_none_or(|id| properties.device_id == id)
+            },
+            DeviceSelector::Type(device_type) => properties.device_type == *device_type,
+        }
+    }
+}
+
+impl FromStr for DeviceSelector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_id = |id: &str| match id.strip_prefix("0x") {
+            Some(hex) => u32::from_str_radix(hex, 16),
+            None => id.parse(),
+        }.map_err(|e| format!("invalid id `{id}`: {e}"));
+
+        if let Ok(index) = s.parse() {
+            return Ok(DeviceSelector::Index(index));
+        }
+        match s.split_once(':') {
+            Some(("name", name)) => Ok(DeviceSelector::Name(name.to_owned())),
+            Some(("id", ids)) => match ids.split_once(':') {
+                Some((vendor_id, device_id)) => Ok(DeviceSelector::Id {
+                    vendor_id: parse_id(vendor_id)?,
+                    device_id: Some(parse_id(device_id)?),
+                }),
+                None => Ok(DeviceSelector::Id { vendor_id: parse_id(ids)?, device_id: None }),
+            },
+            Some(("type", device_type)) => Ok(DeviceSelector::Type(match device_type {
+                "discrete" => PhysicalDeviceType::DiscreteGpu,
+                "integrated" => PhysicalDeviceType::IntegratedGpu,
+                "virtual" => PhysicalDeviceType::VirtualGpu,
+                "cpu" => PhysicalDeviceType::Cpu,
+                "other" => PhysicalDeviceType::Other,
+                _ => return Err(format!("unknown device type `{device_type}`, expected `discrete`, `integrated`, \
+                    `virtual`, `cpu` or `other`")),
+            })),
+            _ => Ok(DeviceSelector::Name(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for DeviceSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeviceSelector::Index(index) => write!(f, "{index}"),
+            DeviceSelector::Name(name) => write!(f, "name:{name}"),
+            DeviceSelector::Id { vendor_id, device_id: None } => write!(f, "id:{vendor_id:#x}"),
+            DeviceSelector::Id { vendor_id, device_id: Some(device_id) } => {
+                write!(f, "id:{vendor_id:#x}:{device_id:#x}")
+            },
+            DeviceSelector::Type(device_type) => write!(f, "type:{device_type:?}"),
+        }
+    }
+}
+
+/// Creates runners for devices matched by any of the selectors, or for every suitable device when `all` is set.
+/// Without selectors the best suited device is chosen, discrete GPUs first.
+pub fn create_runners(selectors: &[DeviceSelector], all: bool) -> Result<Vec<Runner>, Error> {
+    let instance = create_instance()?;
+    let physical_devices = instance.enumerate_physical_devices()
+        .map_err(Error::vulkan("enumerate devices"))?
+        .collect::<Vec<_>>();
+
+    for selector in selectors {
+        if !physical_devices.iter().enumerate().any(|(i, p)| selector.matches(i, p)) {
+            return Err(Error::NoDevice(selector.to_string()));
+        }
+        if let DeviceSelector::Index(index) = selector {
+            compute_queue_family_index(&physical_devices[*index]).ok_or(Error::UnsuitableDevice(*index))?;
+        }
+    }
+
+    let suitable = physical_devices.into_iter()
+        .enumerate()
+        .filter(|(i, p)| all || selectors.is_empty() || selectors.iter().any(|selector| selector.matches(*i, p)))
+        .filter_map(|(i, p)| compute_queue_family_index(&p).map(|q| (i, p, q)));
+
+    let selected = if all || !selectors.is_empty() {
+        suitable.collect::<Vec<_>>()
+    } else {
+        suitable
+            .min_by_key(|(_, p, _)| match p.properties().device_type {
+                PhysicalDeviceType::DiscreteGpu => 0,
+                PhysicalDeviceType::IntegratedGpu => 1,
+                PhysicalDeviceType::VirtualGpu => 2,
+                PhysicalDeviceType::Cpu => 3,
+                PhysicalDeviceType::Other => 4,
+                _ => 5,
+            })
+            .into_iter()
+            .collect()
     };
-    let (physical_device, queue_family_index) = instance
-        .enumerate_physical_devices()
-        .unwrap()
-        .filter(|p| p.supported_extensions().contains(&device_extensions))
-        .filter_map(|p| {
-            // The Vulkan specs guarantee that a compliant implementation must provide at least one
-            // queue that supports compute operations.
-            p.queue_family_properties()
-                .iter()
-                .position(|q| q.queue_flags.intersects(QueueFlags::COMPUTE))
-                .map(|i| (p, i as u32))
-        }).skip(0)
-        .min_by_key(|(p, _)| match p.properties().device_type {
-            PhysicalDeviceType::DiscreteGpu => 0,
-            PhysicalDeviceType::IntegratedGpu => 1,
-            PhysicalDeviceType::VirtualGpu => 2,
-            PhysicalDeviceType::Cpu => 3,
-            PhysicalDeviceType::Other => 4,
-            _ => 5,
-        })
-        .unwrap();
-
-    println!(
-        "Using device: {} (type: {:?})",
-        physical_device.properties().device_name,
-        physical_device.properties().device_type,
-    );
-
-    // Now initializing the device.
-    let (device, mut queues) = Device::new(
-        physical_device.clone(),
-        DeviceCreateInfo {
-            enabled_extensions: device_extensions,
-            queue_create_infos: vec![QueueCreateInfo {
-                queue_family_index,
-                ..Default::default()
-            }],
-            enabled_features: Features {
-                shader_int64: true,
+    if selected.is_empty() {
+        return Err(Error::NoSuitableDevice);
+    }
+
+    let mut runners = Vec::new();
+    for (index, physical_device, queue_family_index) in selected {
+        let name = physical_device.properties().device_name.clone();
+        match Runner::new(index, physical_device, queue_family_index) {
+            Ok(runner) => runners.push(runner),
+            // A device without a required feature does not prevent testing the others.
+            Err(e) if all => eprintln!("Skipped device `{name}`: {e}."),
+            Err(e) => return Err(e),
+        }
+    }
+    if runners.is_empty() {
+        return Err(Error::NoSuitableDevice);
+    }
+    Ok(runners)
+}
+
+pub fn list_devices() -> Result<(), Error> {
+    let instance = create_instance()?;
+    let physical_devices = instance.enumerate_physical_devices().map_err(Error::vulkan("enumerate devices"))?;
+    for (index, physical_device) in physical_devices.enumerate() {
+        let properties = physical_device.properties();
+        let suitable_str = if compute_queue_family_index(&physical_device).is_some() { "" } else { ", not suitable" };
+        println!(
+            "{index}: {} (type: {:?}, id: {:#x}:{:#x}{suitable_str})",
+            properties.device_name, properties.device_type, properties.vendor_id, properties.device_id,
+        );
+    }
+    Ok(())
+}
+
+/// Vulkan device with its queue and allocators, created once and reused by every problem.
+pub struct Runner {
+    /// Index from `list-devices`, it tells identical devices apart when their UUID is unknown.
+    index: usize,
+    physical_device: Arc<PhysicalDevice>,
+    device: Arc<Device>,
+    queue: Arc<Queue>,
+    memory_allocator: StandardMemoryAllocator,
+    descriptor_set_allocator: StandardDescriptorSetAllocator,
+    command_buffer_allocator: StandardCommandBufferAllocator,
+}
+
+impl Runner {
+    fn new(index: usize, physical_device: Arc<PhysicalDevice>, queue_family_index: u32) -> Result<Self, Error> {
+        // Half and double problems need these, devices without them can still run the other problems.
+        let optional_extensions = DeviceExtensions {
+            khr_shader_float16_int8: true,
+            khr_16bit_storage: true,
+            ..DeviceExtensions::empty()
+        };
+        let supported_features = physical_device.supported_features();
+
+        // Now initializing the device.
+        let (device, mut queues) = Device::new(
+            physical_device.clone(),
+            DeviceCreateInfo {
+                enabled_extensions: required_extensions().union(
+                    &physical_device.supported_extensions().intersection(&optional_extensions)
+                ),
+                queue_create_infos: vec![QueueCreateInfo {
+                    queue_family_index,
+                    ..Default::default()
+                }],
+                enabled_features: Features {
+                    shader_int64: true,
+                    shader_float64: supported_features.shader_float64,
+                    shader_float16: supported_features.shader_float16,
+                    storage_buffer16_bit_access: supported_features.storage_buffer16_bit_access,
+                    ..Default::default()
+                },
                 ..Default::default()
             },
-            ..Default::default()
-        },
-    )
-    .unwrap();
+        )
+        .map_err(Error::vulkan("create the device"))?;
 
-    let queue = queues.next().unwrap();
+        // One queue was requested, so it is always returned.
+        let queue = queues.next().unwrap();
 
-    let pipeline = {
-        let shader_module = unsafe {
-            ShaderModule::from_bytes(device.clone(), spirv_code)
-        }.expect("Failed to create shader module.");
+        println!(
+            "Using device: {} (type: {:?})",
+            physical_device.properties().device_name,
+            physical_device.properties().device_type,
+        );
 
-        ComputePipeline::new(
-            device.clone(),
-            shader_module.entry_point("main").expect("Shader module entry point not found."),
-            &(),
-            None,
-            |_| {},
-        )
-        .unwrap()
-    };
+        Ok(Runner {
+            index,
+            memory_allocator: StandardMemoryAllocator::new_default(device.clone()),
+            descriptor_set_allocator: StandardDescriptorSetAllocator::new(device.clone()),
+            command_buffer_allocator: StandardCommandBufferAllocator::new(device.clone(), Default::default()),
+            physical_device,
+            device,
+            queue,
+        })
+    }
 
-    let memory_allocator = StandardMemoryAllocator::new_default(device.clone());
-    let descriptor_set_allocator = StandardDescriptorSetAllocator::new(device.clone());
-    let command_buffer_allocator =
-        StandardCommandBufferAllocator::new(device.clone(), Default::default());
+    pub fn device_name(&self) -> &str {
+        &self.physical_device.properties().device_name
+    }
 
-    let data_buffer = Buffer::from_iter(
-        &memory_allocator,
-        BufferCreateInfo {
-            usage: BufferUsage::STORAGE_BUFFER,
-            ..Default::default()
-        },
-        AllocationCreateInfo {
-            usage: MemoryUsage::Upload,
-            ..Default::default()
-        },
-        initial_data.to_vec(),
-    )
-    .unwrap();
+    /// Number of elements which fit into one dispatch and into buffers of the given element size.
+    fn chunk_len(&self, element_size: usize) -> usize {
+        let properties = self.physical_device.properties();
+        let max_invocations = properties.max_compute_work_group_count[0] as usize * 64;
+        let max_size = (properties.max_storage_buffer_range as u64)
+            .min(properties.max_memory_allocation_size.unwrap_or(u64::MAX));
+        max_invocations.min((max_size / element_size as u64) as usize)
+    }
 
-    let layout = pipeline.layout().set_layouts().get(0).unwrap();
-    let set = PersistentDescriptorSet::new(
-        &descriptor_set_allocator,
-        layout.clone(),
-        [WriteDescriptorSet::buffer(0, data_buffer.clone())],
-    )
-    .unwrap();
+    /// Compiles a pipeline from the shader and runs it with operands bound to the first bindings and the result to the
+    /// next one. Returns bytes of the result, whose elements have `result_size` bytes. The data is split into chunks
+    /// when it exceeds the device limits.
+    pub fn run<T>(
+        &self, spirv_code: &[u8], operands: &[&[T]], result_size: usize
+    ) -> Result<Output, Error> where T: BufferContents + Clone {
+        let pipeline = {
+            let shader_module = unsafe {
+                ShaderModule::from_bytes(self.device.clone(), spirv_code)
+            }.map_err(Error::vulkan("create the shader module"))?;
+            let entry_point = shader_module.entry_point("main").ok_or(Error::Vulkan {
+                context: "find the shader entry point",
+                message: "`main` was not found".to_owned(),
+            })?;
 
-    let mut builder = AutoCommandBufferBuilder::primary(
-        &command_buffer_allocator,
-        queue.queue_family_index(),
-        CommandBufferUsage::OneTimeSubmit,
-    )
-    .unwrap();
-    builder
-        .bind_pipeline_compute(pipeline.clone())
-        .bind_descriptor_sets(
-            PipelineBindPoint::Compute,
-            pipeline.layout().clone(),
-            0,
-            set,
+            ComputePipeline::new(self.device.clone(), entry_point, &(), None, |_| {})
+                .map_err(Error::vulkan("create the compute pipeline"))?
+        };
+
+        let count = operands[0].len();
+        let chunk_len = self.chunk_len(mem::size_of::<T>().max(result_size));
+        let mut data = Vec::with_capacity(count * result_size);
+        for start in (0..count).step_by(chunk_len) {
+            let end = (start + chunk_len).min(count);
+            let chunk = operands.iter().map(|operand| &operand[start..end]).collect::<Vec<_>>();
+            data.extend(self.run_chunk(&pipeline, &chunk, result_size)?);
+        }
+
+        Ok(Output { data, device: self.device_info() })
+    }
+
+    /// Identity, driver and float controls of the device, saved with every result so differences can be explained.
+    fn device_info(&self) -> DeviceInfo {
+        let properties = self.physical_device.properties();
+        let float_controls = |
+            signed_zero_inf_nan_preserve: Option<bool>, denorm_preserve: Option<bool>,
+            denorm_flush_to_zero: Option<bool>, rounding_mode_rte: Option<bool>, rounding_mode_rtz: Option<bool>
+        | Some(FloatControls {
+            signed_zero_inf_nan_preserve: signed_zero_inf_nan_preserve?,
+            denorm_preserve: denorm_preserve?,
+            denorm_flush_to_zero: denorm_flush_to_zero?,
+            rounding_mode_rte: rounding_mode_rte?,
+            rounding_mode_rtz: rounding_mode_rtz?,
+        });
+        let float_controls = [
+            float_controls(
+                properties.shader_signed_zero_inf_nan_preserve_float16, properties.shader_denorm_preserve_float16,
+                properties.shader_denorm_flush_to_zero_float16, properties.shader_rounding_mode_rte_float16,
+                properties.shader_rounding_mode_rtz_float16,
+            ),
+            float_controls(
+                properties.shader_signed_zero_inf_nan_preserve_float32, properties.shader_denorm_preserve_float32,
+                properties.shader_denorm_flush_to_zero_float32, properties.shader_rounding_mode_rte_float32,
+                properties.shader_rounding_mode_rtz_float32,
+            ),
+            float_controls(
+                properties.shader_signed_zero_inf_nan_preserve_float64, properties.shader_denorm_preserve_float64,
+                properties.shader_denorm_flush_to_zero_float64, properties.shader_rounding_mode_rte_float64,
+                properties.shader_rounding_mode_rtz_float64,
+            ),
+        ];
+
+        DeviceInfo {
+            name: properties.device_name.clone(),
+            vendor_id: properties.vendor_id,
+            device_id: properties.device_id,
+            driver_version: properties.driver_version,
+            driver_id: properties.driver_id.map_or(0, |id| id as u32),
+            driver_name: properties.driver_name.clone().unwrap_or_default(),
+            driver_info: properties.driver_info.clone().unwrap_or_default(),
+            api_version: properties.api_version.try_into().unwrap_or_default(),
+            conformance_version: properties.conformance_version
+                .map_or([0; 4], |v| [v.major, v.minor, v.subminor, v.patch]),
+            subgroup_size: properties.subgroup_size.unwrap_or_default(),
+            float_controls: float_controls[0].zip(float_controls[1]).zip(float_controls[2])
+                .map(|((f16, f32), f64)| [f16, f32, f64]),
+            index: self.index as u32,
+            uuid: properties.device_uuid.unwrap_or_default(),
+        }
+    }
+
+    fn run_chunk<T>(
+        &self, pipeline: &Arc<ComputePipeline>, operands: &[&[T]], result_size: usize
+    ) -> Result<Vec<u8>, Error> where T: BufferContents + Clone {
+        let mut writes = Vec::new();
+        for (binding, operand) in operands.iter().enumerate() {
+            let buffer = Buffer::from_iter(
+                &self.memory_allocator,
+                BufferCreateInfo {
+                    usage: BufferUsage::STORAGE_BUFFER,
+                    ..Default::default()
+                },
+                AllocationCreateInfo {
+                    usage: MemoryUsage::Upload,
+                    ..Default::default()
+                },
+                operand.to_vec(),
+            )
+            .map_err(Error::vulkan("create an operand buffer"))?;
+            writes.push(WriteDescriptorSet::buffer(binding as u32, buffer));
+        }
+
+        let count = operands[0].len();
+        let result_buffer = Buffer::from_iter(
+            &self.memory_allocator,
+            BufferCreateInfo {
+                usage: BufferUsage::STORAGE_BUFFER,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                usage: MemoryUsage::Download,
+                ..Default::default()
+            },
+            vec![0u8; count * result_size],
         )
-        .dispatch([group_count as u32, 1, 1])
-        .unwrap();
+        .map_err(Error::vulkan("create the result buffer"))?;
+        writes.push(WriteDescriptorSet::buffer(operands.len() as u32, result_buffer.clone()));
 
-    let command_buffer = builder.build().unwrap();
+        let layout = pipeline.layout().set_layouts().first().ok_or(Error::Vulkan {
+            context: "create the descriptor set",
+            message: "the shader has no descriptor set".to_owned(),
+        })?;
+        let set = PersistentDescriptorSet::new(&self.descriptor_set_allocator, layout.clone(), writes)
+        .map_err(Error::vulkan("create the descriptor set"))?;
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &self.command_buffer_allocator,
+            self.queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .map_err(Error::vulkan("begin the command buffer"))?;
+        builder
+            .bind_pipeline_compute(pipeline.clone())
+            .bind_descriptor_sets(
+                PipelineBindPoint::Compute,
+                pipeline.layout().clone(),
+                0,
+                set,
+            )
+            .dispatch([count.div_ceil(64) as u32, 1, 1])
+            .map_err(Error::vulkan("record the dispatch"))?;
 
-    let future = sync::now(device)
-        .then_execute(queue, command_buffer)
-        .unwrap()
-        .then_signal_fence_and_flush()
-        .unwrap();
+        let command_buffer = builder.build().map_err(Error::vulkan("build the command buffer"))?;
 
-    future.wait(None).unwrap();
+        let future = sync::now(self.device.clone())
+            .then_execute(self.queue.clone(), command_buffer)
+            .map_err(Error::vulkan("execute the command buffer"))?
+            .then_signal_fence_and_flush()
+            .map_err(Error::vulkan("flush the command buffer"))?;
 
-    let data_buffer_content = data_buffer.read().unwrap();
+        future.wait(None).map_err(Error::vulkan("wait for the computation"))?;
 
-    Output {
-        data: data_buffer_content.split_at(data_buffer_content.len() / 2).0.to_vec(),
-        device_name: physical_device.properties().device_name.clone(),
-        device_vendor_id: physical_device.properties().vendor_id,
-        device_id: physical_device.properties().device_id
+        let result = result_buffer.read().map_err(Error::vulkan("read the result buffer"))?;
+        Ok(result.to_vec())
     }
 }